@@ -5,7 +5,39 @@ pub const SIGNER: &str = "signer";
 pub const FEE: &str = "fee";
 pub const TREASURY: &str = "treasury";
 pub const ESCROW: &str = "escrow";
+pub const LISTING_RECEIPT: &str = "listing_receipt";
+pub const BID_RECEIPT: &str = "bid_receipt";
+pub const PURCHASE_RECEIPT: &str = "purchase_receipt";
+pub const COLLECTION_OFFER: &str = "collection_offer";
+pub const TRAIT_OFFER: &str = "trait_offer";
+pub const AUCTION: &str = "auction";
+pub const DUTCH_AUCTION: &str = "dutch_auction";
+pub const SEALED_AUCTION: &str = "sealed_auction";
+pub const SEALED_BID: &str = "sealed_bid";
+pub const BUNDLE: &str = "bundle";
+pub const SWAP_ORDER: &str = "swap_order";
 
-pub const TRADE_STATE_SIZE: usize = 1;
-pub const MAX_AUCTION_HOUSE_SIZE: usize = 32 * 7 + 8 + 1 * 3 + 8;
-pub const COMPRESSION_ID: &str = "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK";
+/// Most items of a bundle, `buy_bundle` settles all of them in one transaction
+/// and the trees, creators and proofs of more items exceed its account limits
+pub const MAX_BUNDLE_ITEMS: u8 = 3;
+
+/// Seconds after the end of an auction, or the reveal end of a sealed
+/// auction, during which only settlement can release the winning bid, the
+/// winner can reclaim it afterwards
+pub const AUCTION_SETTLEMENT_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+pub const TRADE_STATE_SIZE: usize = 8 + 32 * 4 + 4 + 8 + 1 + 8 + 1 + (1 + 8) + (1 + 32);
+pub const MAX_AUCTION_HOUSE_SIZE: usize = 32 * 7 + 8 + 1 * 3 + 8 + (1 + 2 * 2);
+pub const LISTING_RECEIPT_SIZE: usize = 8 + 32 * 5 + 4 + 8 + 8 + (1 + 8) * 2 + (1 + 32) + 1;
+pub const BID_RECEIPT_SIZE: usize = 8 + 32 * 5 + 4 + 8 + 8 + (1 + 8) * 2 + (1 + 32) + 1;
+pub const PURCHASE_RECEIPT_SIZE: usize = 8 + 32 * 5 + 4 + 8 + 8 + 1;
+pub const COLLECTION_OFFER_SIZE: usize = 8 + 32 * 3 + 8 + 4 + 8 + 1 + (1 + 8);
+pub const TRAIT_OFFER_SIZE: usize = 8 + 32 * 3 + 8 + 4 + 8 + 1 + (1 + 8);
+pub const AUCTION_SIZE: usize = 8 + 32 * 4 + 4 + 8 * 4 + (1 + 8) + (1 + 32) + 8 + 1;
+pub const DUTCH_AUCTION_SIZE: usize = 8 + 32 * 4 + 4 + 8 * 4 + (1 + 8) + 1;
+pub const SEALED_AUCTION_SIZE: usize = 8 + 32 * 4 + 4 + 8 * 3 + 1 + (1 + 32) + 8 * 2 + 1;
+pub const SEALED_BID_SIZE: usize = 8 + 32 * 3 + 8 + (1 + 8) + 8 + 1;
+pub const BUNDLE_ITEM_SIZE: usize = 32 * 2 + 4;
+pub const BUNDLE_SIZE: usize =
+    8 + 32 * 2 + 8 * 2 + 1 + (4 + BUNDLE_ITEM_SIZE * MAX_BUNDLE_ITEMS as usize) + 8 + 1;
+pub const SWAP_ORDER_SIZE: usize = 8 + 32 * 4 + 4 + (1 + 32) + 8 * 3 + (1 + 8) + 1;
@@ -46,4 +46,124 @@ pub enum AuctionHouseV2Errors {
 
     #[msg("Seller token account cannot have delegate")]
     SellerTokenAccountCannotHaveDelegate,
+
+    #[msg("Treasury withdrawal owner is required to create the withdrawal token account")]
+    TreasuryWithdrawalOwnerRequired,
+
+    #[msg("Asset id does not match the merkle tree and nonce")]
+    AssetIdMismatch,
+
+    #[msg("Trade state does not belong to this leaf")]
+    TradeStateLeafMismatch,
+
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("Order has expired")]
+    OrderExpired,
+
+    #[msg("Order has not expired yet")]
+    OrderNotExpired,
+
+    #[msg("Receipt does not belong to this sale")]
+    ReceiptMismatch,
+
+    #[msg("Quantity must be greater than zero")]
+    InvalidQuantity,
+
+    #[msg("Asset is not a verified member of the offer collection")]
+    CollectionMismatch,
+
+    #[msg("Asset is not part of the eligible assets of the offer")]
+    AssetNotEligible,
+
+    #[msg("Auction has to end after it starts and in the future")]
+    InvalidAuctionTimes,
+
+    #[msg("Auction is not accepting bids")]
+    AuctionNotActive,
+
+    #[msg("Auction has not ended yet")]
+    AuctionNotEnded,
+
+    #[msg("Bid is below the reserve price or the minimum increment")]
+    BidTooLow,
+
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+
+    #[msg("Auction has no bids")]
+    AuctionHasNoBids,
+
+    #[msg("Minimum bid increment has to be positive")]
+    InvalidBidIncrement,
+
+    #[msg("Auction can still be settled")]
+    SettlementGracePeriodNotOver,
+
+    #[msg("Auction start price has to be at least its end price")]
+    InvalidAuctionPrices,
+
+    #[msg("Price curve step duration has to be positive")]
+    InvalidPriceCurve,
+
+    #[msg("Current price is above the maximum price")]
+    PriceAboveMaximum,
+
+    #[msg("Auction is not accepting reveals")]
+    RevealNotActive,
+
+    #[msg("Revealed price and salt do not match the commitment")]
+    InvalidCommitment,
+
+    #[msg("Revealed price exceeds the bid deposit")]
+    InsufficientDeposit,
+
+    #[msg("Sealed bid has already been revealed")]
+    BidAlreadyRevealed,
+
+    #[msg("Winning bid can only be settled until the settlement deadline")]
+    WinningBidCannotBeWithdrawn,
+
+    #[msg("Bid price is below the ask price")]
+    BidBelowAsk,
+
+    #[msg("Bundle has to hold between two and the maximum number of items")]
+    InvalidBundleSize,
+
+    #[msg("Bundle already holds all of its items")]
+    BundleFull,
+
+    #[msg("Bundle does not hold all of its items yet")]
+    BundleIncomplete,
+
+    #[msg("Asset is already part of the bundle")]
+    DuplicateBundleItem,
+
+    #[msg("Asset is not part of the bundle")]
+    BundleItemNotFound,
+
+    #[msg("Bundle still holds items")]
+    BundleNotEmpty,
+
+    #[msg("Only one side of a swap can add a payment")]
+    InvalidSwapPayment,
+
+    #[msg("Asset is not the one requested by the swap order")]
+    SwapAssetMismatch,
+
+    #[msg("Metadata of the asset is required to match a collection")]
+    MetadataRequired,
+
+    #[msg("Listing is reserved for another buyer")]
+    BuyerNotAllowed,
+
+    #[msg("Creator hash does not match the metadata creators")]
+    CreatorHashMismatch,
+
+    #[msg("Creator account does not match the metadata creator")]
+    CreatorMismatch,
+
+    #[msg("Royalty policy minimum has to be at most its maximum, which with the auction house fee can not exceed 10000 basis points")]
+    InvalidRoyaltyPolicy,
 }
@@ -0,0 +1,291 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{PriceCurve, RoyaltyPolicy, SwapTarget, TradeSide};
+
+#[event]
+pub struct AuctionHouseCreated {
+    pub auction_house: Pubkey,
+    pub authority: Pubkey,
+    pub treasury_mint: Pubkey,
+    pub seller_fee_basis_points: u16,
+    pub requires_sign_off: bool,
+    pub royalty_policy: RoyaltyPolicy,
+}
+
+#[event]
+pub struct Listed {
+    pub trade_state: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub price: u64,
+    pub expiry: Option<i64>,
+    pub allowed_buyer: Option<Pubkey>,
+}
+
+#[event]
+pub struct ListingPriceUpdated {
+    pub trade_state: Pubkey,
+    pub new_trade_state: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+    pub price: u64,
+    pub new_price: u64,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub trade_state: Pubkey,
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub price: u64,
+    pub expiry: Option<i64>,
+}
+
+#[event]
+pub struct CollectionOfferPlaced {
+    pub collection_offer: Pubkey,
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    pub collection: Pubkey,
+    pub price: u64,
+    pub quantity: u32,
+    pub expiry: Option<i64>,
+}
+
+#[event]
+pub struct CollectionOfferCancelled {
+    pub collection_offer: Pubkey,
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    pub collection: Pubkey,
+    pub price: u64,
+    pub quantity: u32,
+}
+
+#[event]
+pub struct TraitOfferPlaced {
+    pub trait_offer: Pubkey,
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    pub eligible_assets_root: [u8; 32],
+    pub price: u64,
+    pub quantity: u32,
+    pub expiry: Option<i64>,
+}
+
+#[event]
+pub struct TraitOfferCancelled {
+    pub trait_offer: Pubkey,
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    pub eligible_assets_root: [u8; 32],
+    pub price: u64,
+    pub quantity: u32,
+}
+
+#[event]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub reserve_price: u64,
+    pub min_bid_increment: u64,
+    pub extension_window: Option<i64>,
+}
+
+#[event]
+pub struct AuctionBidPlaced {
+    pub auction: Pubkey,
+    pub auction_house: Pubkey,
+    pub bidder: Pubkey,
+    pub asset_id: Pubkey,
+    pub amount: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct AuctionBidRefunded {
+    pub auction: Pubkey,
+    pub auction_house: Pubkey,
+    pub bidder: Pubkey,
+    pub asset_id: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DutchAuctionCreated {
+    pub auction: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub start_price: u64,
+    pub end_price: u64,
+    pub curve: PriceCurve,
+}
+
+#[event]
+pub struct SealedAuctionCreated {
+    pub auction: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub commit_end_time: i64,
+    pub reveal_end_time: i64,
+    pub reserve_price: u64,
+    pub second_price: bool,
+}
+
+#[event]
+pub struct SealedBidCommitted {
+    pub sealed_bid: Pubkey,
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct SealedBidRevealed {
+    pub sealed_bid: Pubkey,
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct SealedBidWithdrawn {
+    pub sealed_bid: Pubkey,
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+}
+
+#[event]
+pub struct BundleCreated {
+    pub bundle: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub item_count: u8,
+}
+
+#[event]
+pub struct BundleItemAdded {
+    pub bundle: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+}
+
+#[event]
+pub struct BundleItemRemoved {
+    pub bundle: Pubkey,
+    pub asset_id: Pubkey,
+}
+
+#[event]
+pub struct BundleCancelled {
+    pub bundle: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+}
+
+#[event]
+pub struct BundleSold {
+    pub bundle: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct SwapOrderCreated {
+    pub swap_order: Pubkey,
+    pub auction_house: Pubkey,
+    pub maker: Pubkey,
+    pub asset_id: Pubkey,
+    pub requested: SwapTarget,
+    pub maker_payment: u64,
+    pub taker_payment: u64,
+    pub expiry: Option<i64>,
+}
+
+#[event]
+pub struct SwapOrderCancelled {
+    pub swap_order: Pubkey,
+    pub auction_house: Pubkey,
+    pub maker: Pubkey,
+    pub asset_id: Pubkey,
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub swap_order: Pubkey,
+    pub auction_house: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub maker_asset_id: Pubkey,
+    pub taker_asset_id: Pubkey,
+    pub maker_payment: u64,
+    pub taker_payment: u64,
+    pub auction_house_fee: u64,
+    pub creator_royalties: Vec<CreatorRoyalty>,
+}
+
+#[event]
+pub struct Cancelled {
+    pub trade_state: Pubkey,
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub asset_id: Pubkey,
+    pub price: u64,
+    pub side: TradeSide,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone)]
+pub struct CreatorRoyalty {
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SaleExecuted {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub price: u64,
+    pub auction_house_fee: u64,
+    pub royalty_basis_points: u16,
+    pub primary_sale: bool,
+    pub creator_royalties: Vec<CreatorRoyalty>,
+    pub seller_proceeds: u64,
+}
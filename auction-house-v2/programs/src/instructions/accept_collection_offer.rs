@@ -0,0 +1,159 @@
+use crate::offers::OfferFill;
+use crate::settlement::{LeafTransfer, SaleAccounts};
+use crate::state::{AuctionHouseV2Data, CollectionOffer};
+use crate::MetadataArgs;
+use crate::{constants::*, errors::AuctionHouseV2Errors};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(buyer_price:u64)]
+pub struct AcceptCollectionOfferInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
+
+    /// CHECK: verified in auction_house seeds constraints
+    pub auction_house_authority: AccountInfo<'info>,
+
+    pub treasury_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[TREASURY.as_bytes(),auction_house.key().as_ref()],bump)]
+    pub treasury_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: verified in the logic
+    #[account(mut)]
+    pub seller_receipt_account: AccountInfo<'info>,
+
+    /// CHECK: mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: verified in collection_offer seeds constraints
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),buyer.key().as_ref()],bump)]
+    pub buyer_escrow: UncheckedAccount<'info>,
+
+    #[account(mut,seeds=[
+            COLLECTION_OFFER.as_ref(),
+            buyer.key().as_ref(),
+            auction_house.key().as_ref(),
+            collection.key().as_ref(),
+            buyer_price.to_le_bytes().as_ref()
+        ],
+        bump = collection_offer.bump
+    )]
+    pub collection_offer: Box<Account<'info, CollectionOffer>>,
+
+    /// CHECK: Verified in collection_offer seeds constraints
+    pub collection: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    /* Remaining Accounts
+       - ...Creator Accounts
+       - ...Cnft proofs in the remaining accounts
+    */
+}
+
+pub fn accept_collection_offer<'a>(
+    ctx: Context<'_, '_, '_, 'a, AcceptCollectionOfferInstruction<'a>>,
+    buyer_price: u64,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    metadata: MetadataArgs,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let seller_info = &ctx.accounts.seller.to_account_info();
+    let buyer_info = &ctx.accounts.buyer.to_account_info();
+    let buyer_escrow_bump = ctx
+        .bumps
+        .get("buyer_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    let sale_accounts = SaleAccounts::from_house(
+        auction_house,
+        &ctx.accounts.auction_house_authority,
+        &ctx.accounts.auction_house_fee_account,
+        &ctx.accounts.treasury_mint.to_account_info(),
+        &ctx.accounts.treasury_account,
+        seller_info,
+        &ctx.accounts.seller_receipt_account,
+        buyer_info,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+    )?;
+    let offer_fill = OfferFill {
+        auction_house,
+        buyer: buyer_info,
+        buyer_escrow: &ctx.accounts.buyer_escrow.to_account_info(),
+        buyer_escrow_bump,
+        sale_accounts,
+        previous_leaf_delegate: &ctx.accounts.previous_leaf_delegate.to_account_info(),
+        leaf_transfer: LeafTransfer {
+            bubblegum_program: &ctx.accounts.bubblegum_program.to_account_info(),
+            tree_config: &ctx.accounts.tree_config.to_account_info(),
+            merkle_tree: &ctx.accounts.merkle_tree.to_account_info(),
+            log_wrapper: &ctx.accounts.log_wrapper.to_account_info(),
+            compression_program: &ctx.accounts.compression_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+        },
+    };
+
+    // the collection in the metadata is the one the asset was minted with
+    let check_eligibility =
+        |collection_offer: &CollectionOffer, _asset_id: &Pubkey| match &metadata.collection {
+            Some(collection)
+                if collection.verified && collection.key == collection_offer.collection =>
+            {
+                Ok(())
+            }
+            _ => Err(AuctionHouseV2Errors::CollectionMismatch.into()),
+        };
+
+    offer_fill.fill(
+        &mut ctx.accounts.collection_offer,
+        buyer_price,
+        &metadata,
+        check_eligibility,
+        &mut ctx.remaining_accounts.iter(),
+    )
+}
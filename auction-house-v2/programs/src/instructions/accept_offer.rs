@@ -0,0 +1,248 @@
+use crate::events::SaleExecuted;
+use crate::settlement::{escrow_seeds, LeafTransfer, PaymentSource, SaleAccounts};
+use crate::state::{AuctionHouseV2Data, BidReceipt, TradeSide, TradeState};
+use crate::utils::{escrow_balance, hash_creators, hash_metadata, update_receipt};
+use crate::MetadataArgs;
+use crate::{constants::*, errors::AuctionHouseV2Errors, utils::cmp_bytes};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(buyer_price:u64)]
+pub struct AcceptOfferInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
+
+    /// CHECK: verified in auction_house seeds constraints
+    pub auction_house_authority: AccountInfo<'info>,
+
+    pub treasury_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[TREASURY.as_bytes(),auction_house.key().as_ref()],bump)]
+    pub treasury_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: verified in the logic
+    #[account(mut)]
+    pub seller_receipt_account: AccountInfo<'info>,
+
+    /// CHECK: mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: verified in buyer_trade_state seeds constraints
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),buyer.key().as_ref()],bump)]
+    pub buyer_escrow: UncheckedAccount<'info>,
+
+    #[account(mut,seeds=[
+            TRADE_STATE.as_ref(),
+            buyer.key().as_ref(),
+            auction_house.key().as_ref(),
+            asset_id.key().as_ref(),
+            buyer_price.to_le_bytes().as_ref()
+        ],
+        constraint = buyer_trade_state.side == TradeSide::Bid @ AuctionHouseV2Errors::InvalidBuyerTradeState,
+        close = buyer,
+        bump = buyer_trade_state.bump
+    )]
+    pub buyer_trade_state: Box<Account<'info, TradeState>>,
+
+    /// CHECK: Verified in CPI
+    pub asset_id: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            BID_RECEIPT.as_bytes(),
+            buyer_trade_state.key().as_ref(),
+            buyer_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bid_receipt: UncheckedAccount<'info>,
+    /* Remaining Accounts
+       - ...Creator Accounts
+       - ...Cnft proofs in the remaining accounts
+    */
+}
+
+pub fn accept_offer<'a>(
+    ctx: Context<'_, '_, '_, 'a, AcceptOfferInstruction<'a>>,
+    buyer_price: u64,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    metadata: MetadataArgs,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let merkle_tree_info = &ctx.accounts.merkle_tree.to_account_info();
+    let seller_info = &ctx.accounts.seller.to_account_info();
+    let buyer_info = &ctx.accounts.buyer.to_account_info();
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let buyer_escrow = &ctx.accounts.buyer_escrow.to_account_info();
+    let previous_leaf_delegate_info = &ctx.accounts.previous_leaf_delegate.to_account_info();
+    let compression_program_info = &ctx.accounts.compression_program.to_account_info();
+    let system_program_info = &ctx.accounts.system_program.to_account_info();
+    let tree_config_info = &ctx.accounts.tree_config.to_account_info();
+    let log_wrapper_info = &ctx.accounts.log_wrapper.to_account_info();
+    let auction_house_info = &ctx.accounts.auction_house.to_account_info();
+    let bubblegum_program_info = &ctx.accounts.bubblegum_program.to_account_info();
+    let remaining_accounts = ctx.remaining_accounts;
+
+    let hashed_metadata = hash_metadata(&metadata)?;
+    if !cmp_bytes(&data_hash, &hashed_metadata, 32) {
+        return Err(AuctionHouseV2Errors::MetadataHashMismatch.into());
+    }
+    if !cmp_bytes(&creator_hash, &hash_creators(&metadata.creators), 32) {
+        return Err(AuctionHouseV2Errors::CreatorHashMismatch.into());
+    }
+
+    if buyer_trade_state.merkle_tree != merkle_tree_info.key()
+        || buyer_trade_state.leaf_index != index
+    {
+        return Err(AuctionHouseV2Errors::TradeStateLeafMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if buyer_trade_state.is_expired(now) {
+        return Err(AuctionHouseV2Errors::OrderExpired.into());
+    }
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    if escrow_balance(buyer_escrow, is_native, &Rent::get()?)? < buyer_price {
+        return Err(AuctionHouseV2Errors::NotEnoughFunds.into());
+    }
+
+    let buyer_escrow_bump = ctx
+        .bumps
+        .get("buyer_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    let remaining_accounts_iter = &mut remaining_accounts.iter();
+
+    // native escrow signs for itself, token escrow is owned by the auction house
+    let payment_source = PaymentSource::escrow(
+        buyer_escrow,
+        escrow_seeds(auction_house_info.key, buyer_info.key, buyer_escrow_bump),
+        auction_house,
+    );
+
+    let sale_accounts = SaleAccounts::from_house(
+        auction_house,
+        &ctx.accounts.auction_house_authority,
+        &ctx.accounts.auction_house_fee_account,
+        &ctx.accounts.treasury_mint.to_account_info(),
+        &ctx.accounts.treasury_account,
+        seller_info,
+        &ctx.accounts.seller_receipt_account,
+        buyer_info,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+    )?;
+    let payouts = sale_accounts.pay(
+        &payment_source,
+        buyer_price,
+        auction_house.seller_fee_basis_points,
+        auction_house.royalty_policy.royalty_basis_points(
+            auction_house.seller_fee_basis_points,
+            metadata.seller_fee_basis_points,
+            None,
+        ),
+        sale_accounts.is_primary_sale(&metadata),
+        &metadata.creators,
+        remaining_accounts_iter,
+    )?;
+
+    // seller signs as the leaf owner so the leaf moves without delegating it first
+    let leaf_transfer = LeafTransfer {
+        bubblegum_program: bubblegum_program_info,
+        tree_config: tree_config_info,
+        merkle_tree: merkle_tree_info,
+        log_wrapper: log_wrapper_info,
+        compression_program: compression_program_info,
+        system_program: system_program_info,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+    };
+    leaf_transfer.invoke(
+        seller_info,
+        previous_leaf_delegate_info,
+        buyer_info,
+        remaining_accounts_iter,
+        &[],
+    )?;
+
+    // point the bid receipt to the purchase receipt which can be printed
+    // afterwards, the seller wallet stands in for the missing listing receipt
+    let bid_receipt_info = &ctx.accounts.bid_receipt.to_account_info();
+    let (purchase_receipt, _) = Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT.as_bytes(),
+            seller_info.key.as_ref(),
+            bid_receipt_info.key.as_ref(),
+        ],
+        ctx.program_id,
+    );
+    update_receipt(bid_receipt_info, |bid_receipt: &mut BidReceipt| {
+        bid_receipt.purchased_at = Some(now);
+        bid_receipt.purchase_receipt = Some(purchase_receipt);
+    })?;
+
+    emit!(SaleExecuted {
+        auction_house: auction_house.key(),
+        seller: seller_info.key(),
+        buyer: buyer_info.key(),
+        asset_id: buyer_trade_state.asset_id,
+        merkle_tree: merkle_tree_info.key(),
+        leaf_index: index,
+        price: buyer_price,
+        auction_house_fee: payouts.auction_house_fee,
+        royalty_basis_points: payouts.royalty_basis_points,
+        primary_sale: payouts.primary_sale,
+        creator_royalties: payouts.creator_royalties,
+        seller_proceeds: payouts.seller_proceeds,
+    });
+
+    Ok(())
+}
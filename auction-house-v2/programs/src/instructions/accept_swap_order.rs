@@ -0,0 +1,344 @@
+use crate::events::SwapExecuted;
+use crate::settlement::{
+    auction_house_seeds, escrow_seeds, program_as_signer_seeds, LeafTransfer, PaymentSource,
+    SaleAccounts,
+};
+use crate::state::{AuctionHouseV2Data, SwapLeafArgs, SwapOrder, SwapTarget};
+use crate::utils::{escrow_balance, hash_creators, hash_metadata, transfer_from_escrow};
+use crate::MetadataArgs;
+use crate::{constants::*, errors::AuctionHouseV2Errors, utils::cmp_bytes};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token};
+use mpl_bubblegum::utils::get_asset_id;
+
+#[derive(Accounts)]
+pub struct AcceptSwapOrderInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
+
+    /// CHECK: verified in auction_house seeds constraints
+    pub auction_house_authority: AccountInfo<'info>,
+
+    pub treasury_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[TREASURY.as_bytes(),auction_house.key().as_ref()],bump)]
+    pub treasury_account: UncheckedAccount<'info>,
+
+    /// CHECK: verified in swap_order constraints
+    #[account(mut)]
+    pub maker: AccountInfo<'info>,
+
+    /// CHECK: verified in the logic
+    #[account(mut)]
+    pub maker_receipt_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds=[SWAP_ORDER.as_ref(), maker.key().as_ref(), auction_house.key().as_ref(), swap_order.asset_id.as_ref()],
+        has_one=auction_house,
+        has_one=maker,
+        close=maker,
+        bump=swap_order.bump
+    )]
+    pub swap_order: Box<Account<'info, SwapOrder>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),swap_order.key().as_ref()],bump)]
+    pub swap_escrow: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// CHECK: taker wallet for native auction house, taker token account otherwise
+    #[account(mut)]
+    pub taker_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: verified in the logic
+    #[account(mut)]
+    pub taker_receipt_account: AccountInfo<'info>,
+
+    /// CHECK: Verified in CPI
+    pub maker_tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the swap order, mutated in downstream program
+    #[account(mut, address = swap_order.merkle_tree)]
+    pub maker_merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub taker_tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: mutated in downstream program
+    #[account(mut)]
+    pub taker_merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    /* Remaining Accounts
+       - ...Creator accounts of the cnft paid for
+       - ...Maker cnft proofs, `maker_proof_length` of them
+       - ...Taker cnft proofs
+    */
+}
+
+/// Metadata of a swapped leaf, checked against the leaf hashes
+fn leaf_metadata<'m>(
+    metadata: Option<&'m MetadataArgs>,
+    leaf: &SwapLeafArgs,
+) -> Result<&'m MetadataArgs> {
+    let metadata = metadata.ok_or(AuctionHouseV2Errors::MetadataRequired)?;
+    if !cmp_bytes(&leaf.data_hash, &hash_metadata(metadata)?, 32) {
+        return Err(AuctionHouseV2Errors::MetadataHashMismatch.into());
+    }
+    if !cmp_bytes(&leaf.creator_hash, &hash_creators(&metadata.creators), 32) {
+        return Err(AuctionHouseV2Errors::CreatorHashMismatch.into());
+    }
+    Ok(metadata)
+}
+
+pub fn accept_swap_order<'a>(
+    ctx: Context<'_, '_, '_, 'a, AcceptSwapOrderInstruction<'a>>,
+    maker_leaf: SwapLeafArgs,
+    maker_metadata: Option<MetadataArgs>,
+    maker_proof_length: u8,
+    taker_leaf: SwapLeafArgs,
+    taker_metadata: Option<MetadataArgs>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let maker_info = &ctx.accounts.maker.to_account_info();
+    let swap_order = &ctx.accounts.swap_order;
+    let swap_escrow = &ctx.accounts.swap_escrow.to_account_info();
+    let taker_info = &ctx.accounts.taker.to_account_info();
+    let maker_merkle_tree_info = &ctx.accounts.maker_merkle_tree.to_account_info();
+    let taker_merkle_tree_info = &ctx.accounts.taker_merkle_tree.to_account_info();
+    let program_as_signer_info = &ctx.accounts.program_as_signer.to_account_info();
+    let bubblegum_program_info = &ctx.accounts.bubblegum_program.to_account_info();
+    let compression_program_info = &ctx.accounts.compression_program.to_account_info();
+    let system_program_info = &ctx.accounts.system_program.to_account_info();
+    let log_wrapper_info = &ctx.accounts.log_wrapper.to_account_info();
+    let auction_house_info = &auction_house.to_account_info();
+
+    if maker_leaf.index != swap_order.leaf_index {
+        return Err(AuctionHouseV2Errors::TradeStateLeafMismatch.into());
+    }
+    if get_asset_id(maker_merkle_tree_info.key, maker_leaf.nonce) != swap_order.asset_id {
+        return Err(AuctionHouseV2Errors::AssetIdMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if swap_order.is_expired(now) {
+        return Err(AuctionHouseV2Errors::OrderExpired.into());
+    }
+
+    let taker_asset_id = get_asset_id(taker_merkle_tree_info.key, taker_leaf.nonce);
+    match swap_order.requested {
+        SwapTarget::Asset { asset_id } => {
+            if asset_id != taker_asset_id {
+                return Err(AuctionHouseV2Errors::SwapAssetMismatch.into());
+            }
+        }
+        SwapTarget::Collection { collection } => {
+            let metadata = leaf_metadata(taker_metadata.as_ref(), &taker_leaf)?;
+            // collection in the metadata is the one the asset was minted with
+            match &metadata.collection {
+                Some(asset_collection)
+                    if asset_collection.verified && asset_collection.key == collection => {}
+                _ => return Err(AuctionHouseV2Errors::CollectionMismatch.into()),
+            }
+        }
+    }
+
+    let swap_order_key = swap_order.key();
+    let swap_escrow_bump = ctx
+        .bumps
+        .get("swap_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    let program_as_signer_bump = ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let program_as_signer_seeds = program_as_signer_seeds(program_as_signer_bump);
+
+    // the payment leg is a sale of the cnft received for it, royalties go to
+    // the creators of that cnft
+    let remaining_accounts_iter = &mut ctx.remaining_accounts.iter();
+    let mut auction_house_fee = 0;
+    let mut creator_royalties = Vec::new();
+    if swap_order.taker_payment > 0 {
+        let metadata = leaf_metadata(maker_metadata.as_ref(), &maker_leaf)?;
+        // taker signs the transaction so no seeds are needed to move its funds
+        let payment_source = PaymentSource::wallet(
+            &ctx.accounts.taker_payment_account,
+            taker_info,
+            auction_house,
+        )?;
+        let sale_accounts = SaleAccounts::from_house(
+            auction_house,
+            &ctx.accounts.auction_house_authority,
+            &ctx.accounts.auction_house_fee_account,
+            &ctx.accounts.treasury_mint.to_account_info(),
+            &ctx.accounts.treasury_account,
+            maker_info,
+            &ctx.accounts.maker_receipt_account,
+            taker_info,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+            &ctx.accounts.associated_token_program,
+        )?;
+        let payouts = sale_accounts.pay(
+            &payment_source,
+            swap_order.taker_payment,
+            auction_house.seller_fee_basis_points,
+            auction_house.royalty_policy.royalty_basis_points(
+                auction_house.seller_fee_basis_points,
+                metadata.seller_fee_basis_points,
+                None,
+            ),
+            sale_accounts.is_primary_sale(metadata),
+            &metadata.creators,
+            remaining_accounts_iter,
+        )?;
+        auction_house_fee = payouts.auction_house_fee;
+        creator_royalties = payouts.creator_royalties;
+    } else if swap_order.maker_payment > 0 {
+        let metadata = leaf_metadata(taker_metadata.as_ref(), &taker_leaf)?;
+        // native escrow signs for itself, token escrow is owned by the auction house
+        let payment_source = PaymentSource::escrow(
+            swap_escrow,
+            escrow_seeds(auction_house_info.key, &swap_order_key, swap_escrow_bump),
+            auction_house,
+        );
+        let sale_accounts = SaleAccounts::from_house(
+            auction_house,
+            &ctx.accounts.auction_house_authority,
+            &ctx.accounts.auction_house_fee_account,
+            &ctx.accounts.treasury_mint.to_account_info(),
+            &ctx.accounts.treasury_account,
+            taker_info,
+            &ctx.accounts.taker_receipt_account,
+            maker_info,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+            &ctx.accounts.associated_token_program,
+        )?;
+        let payouts = sale_accounts.pay(
+            &payment_source,
+            swap_order.maker_payment,
+            auction_house.seller_fee_basis_points,
+            auction_house.royalty_policy.royalty_basis_points(
+                auction_house.seller_fee_basis_points,
+                metadata.seller_fee_basis_points,
+                None,
+            ),
+            sale_accounts.is_primary_sale(metadata),
+            &metadata.creators,
+            remaining_accounts_iter,
+        )?;
+        auction_house_fee = payouts.auction_house_fee;
+        creator_royalties = payouts.creator_royalties;
+
+        // return what is left in the escrow and close it
+        let rent = Rent::get()?;
+        transfer_from_escrow(
+            swap_escrow,
+            &escrow_seeds(auction_house_info.key, &swap_order_key, swap_escrow_bump),
+            auction_house_info,
+            &auction_house_seeds(auction_house),
+            maker_info,
+            &ctx.accounts.maker_receipt_account,
+            &ctx.accounts.treasury_mint.key(),
+            system_program_info,
+            &ctx.accounts.token_program.to_account_info(),
+            &rent,
+            escrow_balance(swap_escrow, sale_accounts.is_native(), &rent)?,
+        )?;
+    }
+
+    let remaining_accounts = remaining_accounts_iter.as_slice();
+    let maker_proof_length = maker_proof_length as usize;
+    if remaining_accounts.len() < maker_proof_length {
+        return Err(ErrorCode::AccountNotEnoughKeys.into());
+    }
+    let (maker_proof, taker_proof) = remaining_accounts.split_at(maker_proof_length);
+
+    // maker leaf goes to the taker through the program delegate
+    let maker_leaf_transfer = LeafTransfer {
+        bubblegum_program: bubblegum_program_info,
+        tree_config: &ctx.accounts.maker_tree_config.to_account_info(),
+        merkle_tree: maker_merkle_tree_info,
+        log_wrapper: log_wrapper_info,
+        compression_program: compression_program_info,
+        system_program: system_program_info,
+        root: maker_leaf.root,
+        data_hash: maker_leaf.data_hash,
+        creator_hash: maker_leaf.creator_hash,
+        nonce: maker_leaf.nonce,
+        index: maker_leaf.index,
+    };
+    maker_leaf_transfer.invoke(
+        maker_info,
+        program_as_signer_info,
+        taker_info,
+        &mut maker_proof.iter(),
+        &[&program_as_signer_seeds],
+    )?;
+
+    // taker leaf goes to the maker through the program delegate as well
+    let taker_leaf_transfer = LeafTransfer {
+        bubblegum_program: bubblegum_program_info,
+        tree_config: &ctx.accounts.taker_tree_config.to_account_info(),
+        merkle_tree: taker_merkle_tree_info,
+        log_wrapper: log_wrapper_info,
+        compression_program: compression_program_info,
+        system_program: system_program_info,
+        root: taker_leaf.root,
+        data_hash: taker_leaf.data_hash,
+        creator_hash: taker_leaf.creator_hash,
+        nonce: taker_leaf.nonce,
+        index: taker_leaf.index,
+    };
+    taker_leaf_transfer.invoke(
+        taker_info,
+        program_as_signer_info,
+        maker_info,
+        &mut taker_proof.iter(),
+        &[&program_as_signer_seeds],
+    )?;
+
+    emit!(SwapExecuted {
+        swap_order: swap_order_key,
+        auction_house: auction_house.key(),
+        maker: maker_info.key(),
+        taker: taker_info.key(),
+        maker_asset_id: swap_order.asset_id,
+        taker_asset_id,
+        maker_payment: swap_order.maker_payment,
+        taker_payment: swap_order.taker_payment,
+        auction_house_fee,
+        creator_royalties,
+    });
+
+    Ok(())
+}
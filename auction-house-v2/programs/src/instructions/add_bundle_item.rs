@@ -0,0 +1,116 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::BundleItemAdded;
+use crate::state::{Bundle, BundleItem};
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+
+#[derive(Accounts)]
+pub struct AddBundleItemInstruction<'info> {
+    #[account(
+        mut,
+        seeds=[
+            BUNDLE.as_ref(),
+            seller.key().as_ref(),
+            bundle.auction_house.as_ref(),
+            bundle.bundle_id.to_le_bytes().as_ref()
+        ],
+        has_one=seller,
+        bump=bundle.bump
+    )]
+    pub bundle: Box<Account<'info, Bundle>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the merkle tree and nonce
+    pub asset_id: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    // Cnft proofs in the remaining accounts
+}
+
+pub fn add_bundle_item<'a>(
+    ctx: Context<'_, '_, '_, 'a, AddBundleItemInstruction<'a>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+) -> Result<()> {
+    let seller = &ctx.accounts.seller.to_account_info();
+    let merkle_tree = &ctx.accounts.merkle_tree.to_account_info();
+    let asset_id = ctx.accounts.asset_id.key();
+    let bundle = &ctx.accounts.bundle;
+
+    if get_asset_id(merkle_tree.key, nonce) != asset_id {
+        return Err(AuctionHouseV2Errors::AssetIdMismatch.into());
+    }
+    if bundle.is_complete() {
+        return Err(AuctionHouseV2Errors::BundleFull.into());
+    }
+    if bundle.items.iter().any(|item| item.asset_id == asset_id) {
+        return Err(AuctionHouseV2Errors::DuplicateBundleItem.into());
+    }
+
+    // each leaf is delegated in its own transaction to keep proofs within limits
+    let mut builder = DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program);
+    builder
+        .leaf_owner(seller)
+        .tree_config(&ctx.accounts.tree_config)
+        .previous_leaf_delegate(&ctx.accounts.previous_leaf_delegate)
+        .new_leaf_delegate(&ctx.accounts.program_as_signer)
+        .merkle_tree(merkle_tree)
+        .log_wrapper(&ctx.accounts.log_wrapper)
+        .compression_program(&ctx.accounts.compression_program)
+        .system_program(&ctx.accounts.system_program)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index);
+    for info in ctx.remaining_accounts.iter() {
+        builder.add_remaining_account(info, false, false);
+    }
+    builder.invoke()?;
+
+    let bundle = &mut ctx.accounts.bundle;
+    bundle.items.push(BundleItem {
+        asset_id,
+        merkle_tree: merkle_tree.key(),
+        leaf_index: index,
+    });
+
+    emit!(BundleItemAdded {
+        bundle: bundle.key(),
+        asset_id,
+        merkle_tree: merkle_tree.key(),
+        leaf_index: index,
+    });
+
+    Ok(())
+}
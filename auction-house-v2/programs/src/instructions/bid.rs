@@ -1,19 +1,14 @@
-use crate::state::AuctionHouseV2Data;
-use crate::utils::create_program_associated_token_account;
-use crate::ID as PROGRAM_ID;
+use crate::events::BidPlaced;
+use crate::state::{AuctionHouseV2Data, TradeSide, TradeState};
+use crate::utils::{assert_valid_expiry, escrow_balance, transfer_to_escrow};
 use crate::{constants::*, errors::AuctionHouseV2Errors};
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::system_instruction::transfer;
-use anchor_lang::solana_program::{
-    program::invoke,
-    program_pack::{IsInitialized, Pack},
-};
 use anchor_lang::{
     accounts::{account::Account, program::Program, unchecked_account::UncheckedAccount},
     system_program::System,
 };
 use anchor_spl::token::{Mint, Token};
-use mpl_utils::create_or_allocate_account_raw;
+use mpl_bubblegum::utils::get_asset_id;
 
 #[derive(Accounts)]
 #[instruction(buyer_price:u64)]
@@ -33,22 +28,24 @@ pub struct BidInstruction<'info> {
     #[account(mut)]
     pub payment_account: UncheckedAccount<'info>,
 
-    /// CHECK: Verified in CPI
+    /// CHECK: Verified against the merkle tree and leaf index
     pub asset_id: UncheckedAccount<'info>,
 
+    /// CHECK: Used to derive the asset id
+    pub merkle_tree: UncheckedAccount<'info>,
+
     /// CHECK: Account seeds checked in constraints
     #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),bidder.key().as_ref()],bump)]
     pub buyer_escrow: UncheckedAccount<'info>,
 
-    /// CHECK: Account seeds checked in constraints
-    #[account(mut,seeds=[
+    #[account(init,payer=bidder,space=TRADE_STATE_SIZE,seeds=[
         TRADE_STATE.as_ref(),
         bidder.key().as_ref(),
         auction_house.key().as_ref(),
         asset_id.key().as_ref(),
         buyer_price.to_le_bytes().as_ref()
         ],bump)]
-    pub buyer_trade_state: UncheckedAccount<'info>,
+    pub buyer_trade_state: Box<Account<'info, TradeState>>,
 
     /// CHECK: Account seeds checked in constraints
     #[account(seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump)]
@@ -61,126 +58,90 @@ pub struct BidInstruction<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn bid(ctx: Context<BidInstruction>, buyer_price: u64) -> Result<()> {
+pub fn bid(
+    ctx: Context<BidInstruction>,
+    buyer_price: u64,
+    leaf_index: u32,
+    expiry: Option<i64>,
+) -> Result<()> {
     let auction_house = ctx.accounts.auction_house.to_account_info();
     let treasury_mint = ctx.accounts.treasury_mint.to_account_info();
     let bidder = &ctx.accounts.bidder.to_account_info();
     let asset_id = ctx.accounts.asset_id.to_account_info();
     let buyer_escrow = &ctx.accounts.buyer_escrow.to_account_info();
-    let buyer_trade_state_info = ctx.accounts.buyer_trade_state.to_account_info();
+    let merkle_tree = ctx.accounts.merkle_tree.to_account_info();
     let system_program = &ctx.accounts.system_program.to_account_info();
     let token_program = &ctx.accounts.token_program;
     let payment_account = ctx.accounts.payment_account.to_account_info();
     let rent = &ctx.accounts.rent;
-    let buyer_trade_state_bump = ctx
+    let buyer_trade_state_bump = *ctx
         .bumps
         .get("buyer_trade_state")
         .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
     let auction_house_key = auction_house.key();
-    let token_program_key = token_program.key();
     let bidder_key = bidder.key();
     let buyer_escrow_bump = ctx
         .bumps
         .get("buyer_escrow")
         .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
 
+    if get_asset_id(merkle_tree.key, leaf_index.into()) != asset_id.key() {
+        return Err(AuctionHouseV2Errors::AssetIdMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    assert_valid_expiry(expiry, now)?;
+
     let is_native = treasury_mint.key() == spl_token::native_mint::ID;
 
-    if is_native {
-        let minimum_funds_required = buyer_price
-            .checked_add(rent.minimum_balance(buyer_escrow.data_len()))
+    let escrow_funds = escrow_balance(buyer_escrow, is_native, rent)?;
+    if escrow_funds < buyer_price {
+        let required_funds = buyer_price
+            .checked_sub(escrow_funds)
             .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
-
-        if buyer_escrow.lamports() < minimum_funds_required {
-            let required_funds = minimum_funds_required
-                .checked_sub(buyer_escrow.lamports())
-                .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
-
-            let total_buyer_funds = bidder
-                .lamports()
-                .checked_sub(rent.minimum_balance(bidder.data_len()))
-                .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
-
-            if total_buyer_funds < required_funds {
-                return Err(AuctionHouseV2Errors::NotEnoughFunds)?;
-            }
-
-            let transfer_instruction_accounts = [
-                bidder.to_account_info(),
-                buyer_escrow.to_account_info(),
-                system_program.to_account_info(),
-            ];
-            let transfer_instruction = transfer(bidder.key, buyer_escrow.key, required_funds);
-            invoke(&transfer_instruction, &transfer_instruction_accounts)?;
-        }
-    } else {
-        // create token account for the escrow
         let escrow_signer_seeds = [
             ESCROW.as_ref(),
             auction_house_key.as_ref(),
             bidder_key.as_ref(),
             &[*buyer_escrow_bump],
         ];
-        create_program_associated_token_account(
-            buyer_escrow,
+        transfer_to_escrow(
             bidder,
-            auction_house,
-            treasury_mint,
-            system_program,
-            token_program.to_account_info(),
+            &payment_account,
+            buyer_escrow,
             &escrow_signer_seeds,
+            &auction_house,
+            &treasury_mint,
+            system_program,
+            &token_program.to_account_info(),
+            rent,
+            required_funds,
         )?;
-
-        // transfer the required amount to escrow
-        let escrow_data = spl_token::state::Account::unpack(&buyer_escrow.data.borrow())?;
-
-        if !escrow_data.is_initialized() {
-            return Err(AuctionHouseV2Errors::AccountNotInitialized.into());
-        }
-
-        if escrow_data.amount < buyer_price {
-            let required_amount = buyer_price
-                .checked_sub(escrow_data.amount)
-                .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
-            let transfer_instruction_accounts = [
-                bidder.to_account_info(),
-                buyer_escrow.to_account_info(),
-                token_program.to_account_info(),
-                payment_account.to_account_info(),
-                system_program.to_account_info(),
-            ];
-            let transfer_instruction = spl_token::instruction::transfer(
-                &token_program_key,
-                payment_account.key,
-                buyer_escrow.key,
-                &bidder_key,
-                &[&bidder_key],
-                required_amount,
-            )?;
-            invoke(&transfer_instruction, &transfer_instruction_accounts)?;
-        }
     }
 
-    if buyer_trade_state_info.data_is_empty() {
-        let buyer_trade_state_seeds = [
-            TRADE_STATE.as_ref(),
-            bidder_key.as_ref(),
-            auction_house_key.as_ref(),
-            asset_id.key.as_ref(),
-            &buyer_price.to_le_bytes(),
-            &[*buyer_trade_state_bump],
-        ];
-        create_or_allocate_account_raw(
-            PROGRAM_ID,
-            &buyer_trade_state_info,
-            &system_program,
-            &bidder,
-            TRADE_STATE_SIZE,
-            &buyer_trade_state_seeds,
-        )?;
-    }
-    let data = &mut buyer_trade_state_info.data.borrow_mut();
-    data[0] = *buyer_trade_state_bump;
+    let buyer_trade_state = &mut ctx.accounts.buyer_trade_state;
+    buyer_trade_state.wallet = bidder_key;
+    buyer_trade_state.auction_house = auction_house_key;
+    buyer_trade_state.asset_id = asset_id.key();
+    buyer_trade_state.merkle_tree = merkle_tree.key();
+    buyer_trade_state.leaf_index = leaf_index;
+    buyer_trade_state.price = buyer_price;
+    buyer_trade_state.side = TradeSide::Bid;
+    buyer_trade_state.created_at = now;
+    buyer_trade_state.bump = buyer_trade_state_bump;
+    buyer_trade_state.expiry = expiry;
+    buyer_trade_state.allowed_buyer = None;
+
+    emit!(BidPlaced {
+        trade_state: buyer_trade_state.key(),
+        auction_house: auction_house_key,
+        buyer: bidder_key,
+        asset_id: buyer_trade_state.asset_id,
+        merkle_tree: buyer_trade_state.merkle_tree,
+        leaf_index,
+        price: buyer_price,
+        expiry,
+    });
 
     Ok(())
 }
@@ -0,0 +1,249 @@
+use crate::events::SaleExecuted;
+use crate::settlement::{program_as_signer_seeds, LeafTransfer, PaymentSource, SaleAccounts};
+use crate::state::{AuctionHouseV2Data, ListingReceipt, TradeSide, TradeState};
+use crate::utils::{hash_creators, hash_metadata, update_receipt};
+use crate::MetadataArgs;
+use crate::{constants::*, errors::AuctionHouseV2Errors, utils::cmp_bytes};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(buyer_price:u64)]
+pub struct BuyInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
+
+    /// CHECK: verified in auction_house seeds constraints
+    pub auction_house_authority: AccountInfo<'info>,
+
+    pub treasury_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[TREASURY.as_bytes(),auction_house.key().as_ref()],bump)]
+    pub treasury_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: verified in seller_trade_state seeds constraints
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+
+    /// CHECK: verified in the logic
+    #[account(mut)]
+    pub seller_receipt_account: AccountInfo<'info>,
+
+    /// CHECK: mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            TRADE_STATE.as_ref(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            asset_id.key().as_ref(),
+            buyer_price.to_le_bytes().as_ref()
+        ],
+        constraint = seller_trade_state.side == TradeSide::Ask @ AuctionHouseV2Errors::InvalidSellerTradeState,
+        close = seller,
+        bump = seller_trade_state.bump
+    )]
+    pub seller_trade_state: Box<Account<'info, TradeState>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: buyer wallet for native auction house, buyer token account otherwise
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in seller_trade_state seeds constraints
+    pub asset_id: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            LISTING_RECEIPT.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            seller_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub listing_receipt: UncheckedAccount<'info>,
+    /* Remaining Accounts
+       - ...Creator Accounts
+       - ...Cnft proofs in the remaining accounts
+    */
+}
+
+pub fn buy<'a>(
+    ctx: Context<'_, '_, '_, 'a, BuyInstruction<'a>>,
+    buyer_price: u64,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    metadata: MetadataArgs,
+    buyer_royalty_basis_points: Option<u16>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let merkle_tree_info = &ctx.accounts.merkle_tree.to_account_info();
+    let seller_info = &ctx.accounts.seller.to_account_info();
+    let buyer_info = &ctx.accounts.buyer.to_account_info();
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let program_as_signer_info = &ctx.accounts.program_as_signer.to_account_info();
+    let compression_program_info = &ctx.accounts.compression_program.to_account_info();
+    let system_program_info = &ctx.accounts.system_program.to_account_info();
+    let tree_config_info = &ctx.accounts.tree_config.to_account_info();
+    let log_wrapper_info = &ctx.accounts.log_wrapper.to_account_info();
+    let bubblegum_program_info = &ctx.accounts.bubblegum_program.to_account_info();
+    let remaining_accounts = ctx.remaining_accounts;
+
+    let hashed_metadata = hash_metadata(&metadata)?;
+    if !cmp_bytes(&data_hash, &hashed_metadata, 32) {
+        return Err(AuctionHouseV2Errors::MetadataHashMismatch.into());
+    }
+    if !cmp_bytes(&creator_hash, &hash_creators(&metadata.creators), 32) {
+        return Err(AuctionHouseV2Errors::CreatorHashMismatch.into());
+    }
+
+    if seller_trade_state.merkle_tree != merkle_tree_info.key()
+        || seller_trade_state.leaf_index != index
+    {
+        return Err(AuctionHouseV2Errors::TradeStateLeafMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if seller_trade_state.is_expired(now) {
+        return Err(AuctionHouseV2Errors::OrderExpired.into());
+    }
+
+    if !seller_trade_state.accepts_buyer(buyer_info.key) {
+        return Err(AuctionHouseV2Errors::BuyerNotAllowed.into());
+    }
+
+    let program_as_signer_bump = ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let program_as_signer_seeds = program_as_signer_seeds(program_as_signer_bump);
+
+    let remaining_accounts_iter = &mut remaining_accounts.iter();
+
+    // buyer signs the transaction so no seeds are needed to move its funds
+    let payment_source =
+        PaymentSource::wallet(&ctx.accounts.payment_account, buyer_info, auction_house)?;
+
+    let sale_accounts = SaleAccounts::from_house(
+        auction_house,
+        &ctx.accounts.auction_house_authority,
+        &ctx.accounts.auction_house_fee_account,
+        &ctx.accounts.treasury_mint.to_account_info(),
+        &ctx.accounts.treasury_account,
+        seller_info,
+        &ctx.accounts.seller_receipt_account,
+        buyer_info,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+    )?;
+    let payouts = sale_accounts.pay(
+        &payment_source,
+        buyer_price,
+        auction_house.seller_fee_basis_points,
+        auction_house.royalty_policy.royalty_basis_points(
+            auction_house.seller_fee_basis_points,
+            metadata.seller_fee_basis_points,
+            buyer_royalty_basis_points,
+        ),
+        sale_accounts.is_primary_sale(&metadata),
+        &metadata.creators,
+        remaining_accounts_iter,
+    )?;
+
+    // transfer nft to buyer
+    let leaf_transfer = LeafTransfer {
+        bubblegum_program: bubblegum_program_info,
+        tree_config: tree_config_info,
+        merkle_tree: merkle_tree_info,
+        log_wrapper: log_wrapper_info,
+        compression_program: compression_program_info,
+        system_program: system_program_info,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+    };
+    leaf_transfer.invoke(
+        seller_info,
+        program_as_signer_info,
+        buyer_info,
+        remaining_accounts_iter,
+        &[&program_as_signer_seeds],
+    )?;
+
+    // point the listing receipt to the purchase receipt which can be printed
+    // afterwards, the buyer wallet stands in for the missing bid receipt
+    let listing_receipt_info = &ctx.accounts.listing_receipt.to_account_info();
+    let (purchase_receipt, _) = Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT.as_bytes(),
+            listing_receipt_info.key.as_ref(),
+            buyer_info.key.as_ref(),
+        ],
+        ctx.program_id,
+    );
+    update_receipt(
+        listing_receipt_info,
+        |listing_receipt: &mut ListingReceipt| {
+            listing_receipt.purchased_at = Some(now);
+            listing_receipt.purchase_receipt = Some(purchase_receipt);
+        },
+    )?;
+
+    emit!(SaleExecuted {
+        auction_house: auction_house.key(),
+        seller: seller_info.key(),
+        buyer: buyer_info.key(),
+        asset_id: seller_trade_state.asset_id,
+        merkle_tree: merkle_tree_info.key(),
+        leaf_index: index,
+        price: buyer_price,
+        auction_house_fee: payouts.auction_house_fee,
+        royalty_basis_points: payouts.royalty_basis_points,
+        primary_sale: payouts.primary_sale,
+        creator_royalties: payouts.creator_royalties,
+        seller_proceeds: payouts.seller_proceeds,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use crate::events::{BundleSold, SaleExecuted};
+use crate::settlement::{program_as_signer_seeds, LeafTransfer, PaymentSource, SaleAccounts};
+use crate::state::{AuctionHouseV2Data, Bundle, BundleItemArgs};
+use crate::utils::{hash_creators, hash_metadata};
+use crate::{constants::*, errors::AuctionHouseV2Errors, utils::cmp_bytes};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token};
+use mpl_bubblegum::utils::get_asset_id;
+
+#[derive(Accounts)]
+pub struct BuyBundleInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
+
+    /// CHECK: verified in auction_house seeds constraints
+    pub auction_house_authority: AccountInfo<'info>,
+
+    pub treasury_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[TREASURY.as_bytes(),auction_house.key().as_ref()],bump)]
+    pub treasury_account: UncheckedAccount<'info>,
+
+    /// CHECK: verified in bundle constraints
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+
+    /// CHECK: verified in the logic
+    #[account(mut)]
+    pub seller_receipt_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            BUNDLE.as_ref(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            bundle.bundle_id.to_le_bytes().as_ref()
+        ],
+        has_one=auction_house,
+        has_one=seller,
+        constraint = bundle.is_complete() @ AuctionHouseV2Errors::BundleIncomplete,
+        close=seller,
+        bump=bundle.bump
+    )]
+    pub bundle: Box<Account<'info, Bundle>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: buyer wallet for native auction house, buyer token account otherwise
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    /* Remaining Accounts, for every bundle item in order
+       - Tree config
+       - Merkle tree
+       - ...Creator Accounts
+       - ...Cnft proofs, `proof_length` of them
+    */
+}
+
+pub fn buy_bundle<'a>(
+    ctx: Context<'_, '_, '_, 'a, BuyBundleInstruction<'a>>,
+    items: Vec<BundleItemArgs>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let seller_info = &ctx.accounts.seller.to_account_info();
+    let buyer_info = &ctx.accounts.buyer.to_account_info();
+    let bundle = &ctx.accounts.bundle;
+    let program_as_signer_info = &ctx.accounts.program_as_signer.to_account_info();
+    let compression_program_info = &ctx.accounts.compression_program.to_account_info();
+    let system_program_info = &ctx.accounts.system_program.to_account_info();
+    let log_wrapper_info = &ctx.accounts.log_wrapper.to_account_info();
+    let bubblegum_program_info = &ctx.accounts.bubblegum_program.to_account_info();
+
+    if items.len() != bundle.items.len() {
+        return Err(AuctionHouseV2Errors::BundleIncomplete.into());
+    }
+
+    let program_as_signer_bump = ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let program_as_signer_seeds = program_as_signer_seeds(program_as_signer_bump);
+
+    // buyer signs the transaction so no seeds are needed to move its funds
+    let payment_source =
+        PaymentSource::wallet(&ctx.accounts.payment_account, buyer_info, auction_house)?;
+
+    let sale_accounts = SaleAccounts::from_house(
+        auction_house,
+        &ctx.accounts.auction_house_authority,
+        &ctx.accounts.auction_house_fee_account,
+        &ctx.accounts.treasury_mint.to_account_info(),
+        &ctx.accounts.treasury_account,
+        seller_info,
+        &ctx.accounts.seller_receipt_account,
+        buyer_info,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+    )?;
+
+    // the price is split evenly across the items so royalties follow each asset,
+    // the last item takes the rounding remainder
+    let item_count = bundle.items.len() as u64;
+    let item_price = bundle.price / item_count;
+    let last_item_price = bundle
+        .price
+        .checked_sub(item_price * (item_count - 1))
+        .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+
+    let mut remaining_accounts = ctx.remaining_accounts;
+    for (position, (item, args)) in bundle.items.iter().zip(items.iter()).enumerate() {
+        let hashed_metadata = hash_metadata(&args.metadata)?;
+        if !cmp_bytes(&args.data_hash, &hashed_metadata, 32) {
+            return Err(AuctionHouseV2Errors::MetadataHashMismatch.into());
+        }
+        if !cmp_bytes(
+            &args.creator_hash,
+            &hash_creators(&args.metadata.creators),
+            32,
+        ) {
+            return Err(AuctionHouseV2Errors::CreatorHashMismatch.into());
+        }
+
+        let remaining_accounts_iter = &mut remaining_accounts.iter();
+        let tree_config_info = next_account_info(remaining_accounts_iter)?;
+        let merkle_tree_info = next_account_info(remaining_accounts_iter)?;
+        if merkle_tree_info.key() != item.merkle_tree {
+            return Err(AuctionHouseV2Errors::TradeStateLeafMismatch.into());
+        }
+        if get_asset_id(merkle_tree_info.key, args.nonce) != item.asset_id {
+            return Err(AuctionHouseV2Errors::AssetIdMismatch.into());
+        }
+
+        let price = if position as u64 == item_count - 1 {
+            last_item_price
+        } else {
+            item_price
+        };
+        let payouts = sale_accounts.pay(
+            &payment_source,
+            price,
+            auction_house.seller_fee_basis_points,
+            auction_house.royalty_policy.royalty_basis_points(
+                auction_house.seller_fee_basis_points,
+                args.metadata.seller_fee_basis_points,
+                None,
+            ),
+            sale_accounts.is_primary_sale(&args.metadata),
+            &args.metadata.creators,
+            remaining_accounts_iter,
+        )?;
+
+        let unread_accounts = remaining_accounts_iter.as_slice();
+        let proof_length = args.proof_length as usize;
+        if unread_accounts.len() < proof_length {
+            return Err(ErrorCode::AccountNotEnoughKeys.into());
+        }
+        let (proof, next_accounts) = unread_accounts.split_at(proof_length);
+        remaining_accounts = next_accounts;
+
+        // transfer nft to buyer
+        let leaf_transfer = LeafTransfer {
+            bubblegum_program: bubblegum_program_info,
+            tree_config: tree_config_info,
+            merkle_tree: merkle_tree_info,
+            log_wrapper: log_wrapper_info,
+            compression_program: compression_program_info,
+            system_program: system_program_info,
+            root: args.root,
+            data_hash: args.data_hash,
+            creator_hash: args.creator_hash,
+            nonce: args.nonce,
+            index: item.leaf_index,
+        };
+        leaf_transfer.invoke(
+            seller_info,
+            program_as_signer_info,
+            buyer_info,
+            &mut proof.iter(),
+            &[&program_as_signer_seeds],
+        )?;
+
+        emit!(SaleExecuted {
+            auction_house: auction_house.key(),
+            seller: seller_info.key(),
+            buyer: buyer_info.key(),
+            asset_id: item.asset_id,
+            merkle_tree: item.merkle_tree,
+            leaf_index: item.leaf_index,
+            price,
+            auction_house_fee: payouts.auction_house_fee,
+            royalty_basis_points: payouts.royalty_basis_points,
+            primary_sale: payouts.primary_sale,
+            creator_royalties: payouts.creator_royalties,
+            seller_proceeds: payouts.seller_proceeds,
+        });
+    }
+
+    emit!(BundleSold {
+        bundle: bundle.key(),
+        auction_house: auction_house.key(),
+        seller: seller_info.key(),
+        buyer: buyer_info.key(),
+        price: bundle.price,
+    });
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use crate::constants::*;
 use crate::errors::AuctionHouseV2Errors;
-use crate::state::AuctionHouseV2Data;
-use crate::utils::{assert_valid_trade_state, close, cmp_bytes};
+use crate::events::Cancelled;
+use crate::state::{AuctionHouseV2Data, ListingReceipt, TradeSide, TradeState};
+use crate::utils::update_receipt;
 use anchor_lang::prelude::*;
 use anchor_lang::{
     accounts::{account::Account, program::Program, unchecked_account::UncheckedAccount},
@@ -33,98 +34,117 @@ pub struct CancelInstruction<'info> {
     #[account(mut)]
     pub wallet: Signer<'info>,
 
-    /// CHECK: validated in the main functionality
-    #[account(mut)]
-    pub trade_state: UncheckedAccount<'info>,
-}
+    #[account(
+        mut,
+        seeds=[
+            TRADE_STATE.as_ref(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            asset_id.key().as_ref(),
+            price.to_le_bytes().as_ref()
+        ],
+        constraint = trade_state.side == TradeSide::Ask @ AuctionHouseV2Errors::InvalidSellerTradeState,
+        close = wallet,
+        bump = trade_state.bump
+    )]
+    pub trade_state: Box<Account<'info, TradeState>>,
 
-#[derive(Accounts)]
-pub struct CancelRemainingAccounts<'info> {
     /// CHECK: Verified in CPI
-    pub compression_program: UncheckedAccount<'info>,
+    pub tree_config: UncheckedAccount<'info>,
 
     /// CHECK: mutated in downstream program
     #[account(mut)]
     pub merkle_tree: UncheckedAccount<'info>,
 
-    /// CHECK: Verified in CPI
-    pub tree_config: UncheckedAccount<'info>,
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
 
     /// CHECK: Verified in CPI
     pub bubblegum_program: UncheckedAccount<'info>,
 
-    /// CHECK: Account seeds checked in constraints
-    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
-    pub program_as_signer: UncheckedAccount<'info>,
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
 
     /// CHECK: Verified in CPI
     pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            LISTING_RECEIPT.as_bytes(),
+            trade_state.key().as_ref(),
+            trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub listing_receipt: UncheckedAccount<'info>,
     // Cnft proofs in the remaining accounts
 }
 
 pub fn cancel<'a>(
     ctx: Context<'_, '_, '_, 'a, CancelInstruction<'a>>,
-    price: u64,
+    _price: u64,
     root: [u8; 32],
     data_hash: [u8; 32],
     creator_hash: [u8; 32],
     nonce: u64,
     index: u32,
 ) -> Result<()> {
-    let auction_house = ctx.accounts.auction_house.to_account_info();
     let wallet = ctx.accounts.wallet.to_account_info();
-    let trade_state_info = ctx.accounts.trade_state.to_account_info();
-    let asset_id = ctx.accounts.asset_id.to_account_info();
-    let remaining_accounts = &mut ctx.remaining_accounts.iter();
-
-    if trade_state_info.data_is_empty() {
-        return Err(AuctionHouseV2Errors::InvalidSellerTradeState.into());
+    let trade_state = &ctx.accounts.trade_state;
+    let tree_config = &ctx.accounts.tree_config.to_account_info();
+    let merkle_tree = &ctx.accounts.merkle_tree.to_account_info();
+    let program_as_signer = &ctx.accounts.program_as_signer.to_account_info();
+    let bubblegum_program = &ctx.accounts.bubblegum_program.to_account_info();
+    let compression_program = &ctx.accounts.compression_program.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let log_wrapper = &ctx.accounts.log_wrapper.to_account_info();
+    let remaining_accounts = ctx.remaining_accounts;
+
+    if trade_state.merkle_tree != merkle_tree.key() || trade_state.leaf_index != index {
+        return Err(AuctionHouseV2Errors::TradeStateLeafMismatch.into());
     }
 
-    let compression_program = next_account_info(remaining_accounts)?;
-    if cmp_bytes(
-        &compression_program.key.as_ref(),
-        COMPRESSION_ID.as_bytes(),
-        32,
-    ) {
-        let merkle_tree = next_account_info(remaining_accounts)?;
-        let tree_config = next_account_info(remaining_accounts)?;
-        let bubblegum_program = next_account_info(remaining_accounts)?;
-        let program_as_signer = next_account_info(remaining_accounts)?;
-        let system_program = next_account_info(remaining_accounts)?;
-        let log_wrapper = next_account_info(remaining_accounts)?;
-        if !assert_valid_trade_state(
-            trade_state_info.key,
-            wallet.key,
-            auction_house.key,
-            asset_id.key,
-            price.to_be_bytes(),
-        ) {
-            return Err(AuctionHouseV2Errors::InvalidBuyingOrSellingOrder.into());
-        }
-        let mut builder = DelegateCpiBuilder::new(&bubblegum_program);
-        builder
-            .leaf_owner(&wallet)
-            .tree_config(&tree_config)
-            .previous_leaf_delegate(&program_as_signer)
-            .new_leaf_delegate(&wallet)
-            .merkle_tree(&merkle_tree)
-            .log_wrapper(&log_wrapper)
-            .compression_program(&compression_program)
-            .system_program(&system_program)
-            .root(root)
-            .data_hash(data_hash)
-            .creator_hash(creator_hash)
-            .nonce(nonce)
-            .index(index);
-        for info in remaining_accounts {
-            builder.add_remaining_account(info, false, false);
-        }
-        builder.invoke()?;
+    let mut builder = DelegateCpiBuilder::new(&bubblegum_program);
+    builder
+        .leaf_owner(&wallet)
+        .tree_config(&tree_config)
+        .previous_leaf_delegate(&program_as_signer)
+        .new_leaf_delegate(&wallet)
+        .merkle_tree(&merkle_tree)
+        .log_wrapper(&log_wrapper)
+        .compression_program(&compression_program)
+        .system_program(&system_program)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index);
+    for info in remaining_accounts.iter() {
+        builder.add_remaining_account(info, false, false);
     }
-    close(trade_state_info, wallet)?;
+    builder.invoke()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    update_receipt(
+        &ctx.accounts.listing_receipt,
+        |listing_receipt: &mut ListingReceipt| {
+            listing_receipt.canceled_at = Some(now);
+        },
+    )?;
+
+    emit!(Cancelled {
+        trade_state: trade_state.key(),
+        auction_house: trade_state.auction_house,
+        wallet: trade_state.wallet,
+        asset_id: trade_state.asset_id,
+        price: trade_state.price,
+        side: trade_state.side,
+    });
 
     Ok(())
 }
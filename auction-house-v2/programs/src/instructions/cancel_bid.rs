@@ -0,0 +1,127 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::Cancelled;
+use crate::settlement::{auction_house_seeds, escrow_seeds};
+use crate::state::{AuctionHouseV2Data, BidReceipt, TradeSide, TradeState};
+use crate::utils::{escrow_balance, transfer_from_escrow, update_receipt};
+use anchor_lang::prelude::*;
+use anchor_lang::{
+    accounts::{account::Account, program::Program, unchecked_account::UncheckedAccount},
+    system_program::System,
+};
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(buyer_price:u64)]
+pub struct CancelBidInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified in buyer_trade_state seeds constraints
+    pub asset_id: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Wallet for native auction house, payment token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),wallet.key().as_ref()],bump)]
+    pub buyer_escrow: UncheckedAccount<'info>,
+
+    #[account(mut,seeds=[
+        TRADE_STATE.as_ref(),
+        wallet.key().as_ref(),
+        auction_house.key().as_ref(),
+        asset_id.key().as_ref(),
+        buyer_price.to_le_bytes().as_ref()
+        ],
+        constraint = buyer_trade_state.side == TradeSide::Bid @ AuctionHouseV2Errors::InvalidBuyerTradeState,
+        close = wallet,
+        bump = buyer_trade_state.bump)]
+    pub buyer_trade_state: Box<Account<'info, TradeState>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            BID_RECEIPT.as_bytes(),
+            buyer_trade_state.key().as_ref(),
+            buyer_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bid_receipt: UncheckedAccount<'info>,
+}
+
+pub fn cancel_bid(
+    ctx: Context<CancelBidInstruction>,
+    buyer_price: u64,
+    refund_escrow: bool,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let wallet = &ctx.accounts.wallet.to_account_info();
+    let receipt_account = &ctx.accounts.receipt_account.to_account_info();
+    let buyer_escrow = &ctx.accounts.buyer_escrow.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let rent = &ctx.accounts.rent;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+
+    if refund_escrow {
+        let treasury_mint_key = treasury_mint.key();
+        let is_native = treasury_mint_key == spl_token::native_mint::ID;
+        let buyer_escrow_bump = ctx
+            .bumps
+            .get("buyer_escrow")
+            .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+        // escrow may hold less than the bid if it was spent by another order
+        let refund_amount = escrow_balance(buyer_escrow, is_native, rent)?.min(buyer_price);
+        if refund_amount > 0 {
+            transfer_from_escrow(
+                buyer_escrow,
+                &escrow_seeds(&auction_house.key(), wallet.key, buyer_escrow_bump),
+                &auction_house.to_account_info(),
+                &auction_house_seeds(auction_house),
+                wallet,
+                receipt_account,
+                &treasury_mint_key,
+                system_program,
+                token_program,
+                rent,
+                refund_amount,
+            )?;
+        }
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    update_receipt(&ctx.accounts.bid_receipt, |bid_receipt: &mut BidReceipt| {
+        bid_receipt.canceled_at = Some(now);
+    })?;
+
+    emit!(Cancelled {
+        trade_state: buyer_trade_state.key(),
+        auction_house: buyer_trade_state.auction_house,
+        wallet: buyer_trade_state.wallet,
+        asset_id: buyer_trade_state.asset_id,
+        price: buyer_trade_state.price,
+        side: buyer_trade_state.side,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::BundleCancelled;
+use crate::state::Bundle;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelBundleInstruction<'info> {
+    #[account(
+        mut,
+        seeds=[
+            BUNDLE.as_ref(),
+            seller.key().as_ref(),
+            bundle.auction_house.as_ref(),
+            bundle.bundle_id.to_le_bytes().as_ref()
+        ],
+        has_one=seller,
+        constraint = bundle.items.is_empty() @ AuctionHouseV2Errors::BundleNotEmpty,
+        close=seller,
+        bump=bundle.bump
+    )]
+    pub bundle: Box<Account<'info, Bundle>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+}
+
+/// Items have to be removed first so that every leaf gets its delegate back.
+pub fn cancel_bundle(ctx: Context<CancelBundleInstruction>) -> Result<()> {
+    let bundle = &ctx.accounts.bundle;
+
+    emit!(BundleCancelled {
+        bundle: bundle.key(),
+        auction_house: bundle.auction_house,
+        seller: bundle.seller,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::CollectionOfferCancelled;
+use crate::offers::OfferEscrow;
+use crate::state::{AuctionHouseV2Data, CollectionOffer};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(buyer_price:u64)]
+pub struct CancelCollectionOfferInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified in collection_offer seeds constraints
+    pub collection: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Wallet for native auction house, payment token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),wallet.key().as_ref()],bump)]
+    pub buyer_escrow: UncheckedAccount<'info>,
+
+    #[account(mut,seeds=[
+        COLLECTION_OFFER.as_ref(),
+        wallet.key().as_ref(),
+        auction_house.key().as_ref(),
+        collection.key().as_ref(),
+        buyer_price.to_le_bytes().as_ref()
+        ],
+        close = wallet,
+        bump = collection_offer.bump)]
+    pub collection_offer: Box<Account<'info, CollectionOffer>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn cancel_collection_offer(
+    ctx: Context<CancelCollectionOfferInstruction>,
+    buyer_price: u64,
+    refund_escrow: bool,
+) -> Result<()> {
+    let collection_offer = &ctx.accounts.collection_offer;
+
+    if refund_escrow {
+        let buyer_escrow_bump = ctx
+            .bumps
+            .get("buyer_escrow")
+            .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+        let offer_escrow = OfferEscrow {
+            auction_house: &ctx.accounts.auction_house,
+            wallet: &ctx.accounts.wallet.to_account_info(),
+            buyer_escrow: &ctx.accounts.buyer_escrow.to_account_info(),
+            buyer_escrow_bump,
+            treasury_mint: &ctx.accounts.treasury_mint.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            rent: &ctx.accounts.rent,
+        };
+        offer_escrow.refund(
+            &ctx.accounts.receipt_account.to_account_info(),
+            buyer_price,
+            collection_offer.quantity,
+        )?;
+    }
+
+    emit!(CollectionOfferCancelled {
+        collection_offer: collection_offer.key(),
+        auction_house: collection_offer.auction_house,
+        buyer: collection_offer.wallet,
+        collection: collection_offer.collection,
+        price: collection_offer.price,
+        quantity: collection_offer.quantity,
+    });
+
+    Ok(())
+}
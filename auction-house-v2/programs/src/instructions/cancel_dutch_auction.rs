@@ -0,0 +1,86 @@
+use crate::constants::*;
+use crate::events::AuctionCancelled;
+use crate::settlement::LeafTransfer;
+use crate::state::DutchAuction;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelDutchAuctionInstruction<'info> {
+    #[account(
+        mut,
+        seeds=[DUTCH_AUCTION.as_ref(), dutch_auction.auction_house.as_ref(), dutch_auction.asset_id.as_ref()],
+        has_one=seller,
+        has_one=merkle_tree,
+        close=seller,
+        bump=dutch_auction.bump
+    )]
+    pub dutch_auction: Box<Account<'info, DutchAuction>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the auction, mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    // Cnft proofs in the remaining accounts
+}
+
+pub fn cancel_dutch_auction<'a>(
+    ctx: Context<'_, '_, '_, 'a, CancelDutchAuctionInstruction<'a>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+) -> Result<()> {
+    let auction = &ctx.accounts.dutch_auction;
+    let seller = &ctx.accounts.seller.to_account_info();
+
+    // transferring the leaf to its own owner resets the leaf delegate to the owner
+    let leaf_transfer = LeafTransfer {
+        bubblegum_program: &ctx.accounts.bubblegum_program.to_account_info(),
+        tree_config: &ctx.accounts.tree_config.to_account_info(),
+        merkle_tree: &ctx.accounts.merkle_tree.to_account_info(),
+        log_wrapper: &ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: &ctx.accounts.compression_program.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index: auction.leaf_index,
+    };
+    leaf_transfer.invoke(
+        seller,
+        &ctx.accounts.program_as_signer.to_account_info(),
+        seller,
+        &mut ctx.remaining_accounts.iter(),
+        &[],
+    )?;
+
+    emit!(AuctionCancelled {
+        auction: auction.key(),
+        auction_house: auction.auction_house,
+        seller: auction.seller,
+        asset_id: auction.asset_id,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::SwapOrderCancelled;
+use crate::settlement::{auction_house_seeds, escrow_seeds, LeafTransfer};
+use crate::state::{AuctionHouseV2Data, SwapLeafArgs, SwapOrder};
+use crate::utils::transfer_from_escrow;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+pub struct CancelSwapOrderInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
+
+    /// CHECK: Verified in auction house seeds
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: Wallet for native auction house, maker token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[SWAP_ORDER.as_ref(), maker.key().as_ref(), auction_house.key().as_ref(), swap_order.asset_id.as_ref()],
+        has_one=auction_house,
+        has_one=maker,
+        has_one=merkle_tree,
+        close=maker,
+        bump=swap_order.bump
+    )]
+    pub swap_order: Box<Account<'info, SwapOrder>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),swap_order.key().as_ref()],bump)]
+    pub swap_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the swap order, mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    // Cnft proofs in the remaining accounts when the leaf is passed
+}
+
+pub fn cancel_swap_order<'a>(
+    ctx: Context<'_, '_, '_, 'a, CancelSwapOrderInstruction<'a>>,
+    maker_leaf: Option<SwapLeafArgs>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let maker = &ctx.accounts.maker.to_account_info();
+    let swap_order = &ctx.accounts.swap_order;
+    let system_program = &ctx.accounts.system_program.to_account_info();
+
+    // the maker may have moved the leaf since, so the refund never depends on
+    // the leaf and its delegate is only reset when the leaf is passed
+    if let Some(maker_leaf) = maker_leaf {
+        if maker_leaf.index != swap_order.leaf_index {
+            return Err(AuctionHouseV2Errors::TradeStateLeafMismatch.into());
+        }
+        // transferring the leaf to its own owner resets the leaf delegate to the owner
+        let leaf_transfer = LeafTransfer {
+            bubblegum_program: &ctx.accounts.bubblegum_program.to_account_info(),
+            tree_config: &ctx.accounts.tree_config.to_account_info(),
+            merkle_tree: &ctx.accounts.merkle_tree.to_account_info(),
+            log_wrapper: &ctx.accounts.log_wrapper.to_account_info(),
+            compression_program: &ctx.accounts.compression_program.to_account_info(),
+            system_program,
+            root: maker_leaf.root,
+            data_hash: maker_leaf.data_hash,
+            creator_hash: maker_leaf.creator_hash,
+            nonce: maker_leaf.nonce,
+            index: maker_leaf.index,
+        };
+        leaf_transfer.invoke(
+            maker,
+            &ctx.accounts.program_as_signer.to_account_info(),
+            maker,
+            &mut ctx.remaining_accounts.iter(),
+            &[],
+        )?;
+    }
+
+    if swap_order.maker_payment > 0 {
+        let swap_order_key = swap_order.key();
+        let swap_escrow_bump = ctx
+            .bumps
+            .get("swap_escrow")
+            .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+        transfer_from_escrow(
+            &ctx.accounts.swap_escrow.to_account_info(),
+            &escrow_seeds(&auction_house.key(), &swap_order_key, swap_escrow_bump),
+            &auction_house.to_account_info(),
+            &auction_house_seeds(auction_house),
+            maker,
+            &ctx.accounts.receipt_account.to_account_info(),
+            &ctx.accounts.treasury_mint.key(),
+            system_program,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.rent,
+            swap_order.maker_payment,
+        )?;
+    }
+
+    emit!(SwapOrderCancelled {
+        swap_order: swap_order.key(),
+        auction_house: swap_order.auction_house,
+        maker: swap_order.maker,
+        asset_id: swap_order.asset_id,
+    });
+
+    Ok(())
+}
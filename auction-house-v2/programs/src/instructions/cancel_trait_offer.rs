@@ -0,0 +1,88 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::TraitOfferCancelled;
+use crate::offers::OfferEscrow;
+use crate::state::{AuctionHouseV2Data, TraitOffer};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(buyer_price:u64)]
+pub struct CancelTraitOfferInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Wallet for native auction house, payment token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),wallet.key().as_ref()],bump)]
+    pub buyer_escrow: UncheckedAccount<'info>,
+
+    #[account(mut,seeds=[
+        TRAIT_OFFER.as_ref(),
+        wallet.key().as_ref(),
+        auction_house.key().as_ref(),
+        trait_offer.eligible_assets_root.as_ref(),
+        buyer_price.to_le_bytes().as_ref()
+        ],
+        close = wallet,
+        bump = trait_offer.bump)]
+    pub trait_offer: Box<Account<'info, TraitOffer>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn cancel_trait_offer(
+    ctx: Context<CancelTraitOfferInstruction>,
+    buyer_price: u64,
+    refund_escrow: bool,
+) -> Result<()> {
+    let trait_offer = &ctx.accounts.trait_offer;
+
+    if refund_escrow {
+        let buyer_escrow_bump = ctx
+            .bumps
+            .get("buyer_escrow")
+            .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+        let offer_escrow = OfferEscrow {
+            auction_house: &ctx.accounts.auction_house,
+            wallet: &ctx.accounts.wallet.to_account_info(),
+            buyer_escrow: &ctx.accounts.buyer_escrow.to_account_info(),
+            buyer_escrow_bump,
+            treasury_mint: &ctx.accounts.treasury_mint.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            rent: &ctx.accounts.rent,
+        };
+        offer_escrow.refund(
+            &ctx.accounts.receipt_account.to_account_info(),
+            buyer_price,
+            trait_offer.quantity,
+        )?;
+    }
+
+    emit!(TraitOfferCancelled {
+        trait_offer: trait_offer.key(),
+        auction_house: trait_offer.auction_house,
+        buyer: trait_offer.wallet,
+        eligible_assets_root: trait_offer.eligible_assets_root,
+        price: trait_offer.price,
+        quantity: trait_offer.quantity,
+    });
+
+    Ok(())
+}
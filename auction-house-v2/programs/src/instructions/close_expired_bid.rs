@@ -0,0 +1,56 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::Cancelled;
+use crate::state::{BidReceipt, TradeSide, TradeState};
+use crate::utils::update_receipt;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseExpiredBidInstruction<'info> {
+    #[account(
+        mut,
+        constraint = buyer_trade_state.side == TradeSide::Bid @ AuctionHouseV2Errors::InvalidBuyerTradeState,
+        close = wallet
+    )]
+    pub buyer_trade_state: Box<Account<'info, TradeState>>,
+
+    /// CHECK: Verified against the trade state, receives the rent back
+    #[account(mut, address = buyer_trade_state.wallet)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            BID_RECEIPT.as_bytes(),
+            buyer_trade_state.key().as_ref(),
+            buyer_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bid_receipt: UncheckedAccount<'info>,
+}
+
+pub fn close_expired_bid(ctx: Context<CloseExpiredBidInstruction>) -> Result<()> {
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+
+    let now = Clock::get()?.unix_timestamp;
+    if !buyer_trade_state.is_expired(now) {
+        return Err(AuctionHouseV2Errors::OrderNotExpired.into());
+    }
+
+    update_receipt(&ctx.accounts.bid_receipt, |bid_receipt: &mut BidReceipt| {
+        bid_receipt.canceled_at = Some(now);
+    })?;
+
+    emit!(Cancelled {
+        trade_state: buyer_trade_state.key(),
+        auction_house: buyer_trade_state.auction_house,
+        wallet: buyer_trade_state.wallet,
+        asset_id: buyer_trade_state.asset_id,
+        price: buyer_trade_state.price,
+        side: buyer_trade_state.side,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::Cancelled;
+use crate::state::{ListingReceipt, TradeSide, TradeState};
+use crate::utils::update_receipt;
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::TransferCpiBuilder;
+
+#[derive(Accounts)]
+pub struct CloseExpiredListingInstruction<'info> {
+    #[account(
+        mut,
+        constraint = seller_trade_state.side == TradeSide::Ask @ AuctionHouseV2Errors::InvalidSellerTradeState,
+        close = seller
+    )]
+    pub seller_trade_state: Box<Account<'info, TradeState>>,
+
+    /// CHECK: Verified against the trade state, receives the rent back
+    #[account(mut, address = seller_trade_state.wallet)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the trade state, mutated in downstream program
+    #[account(mut, address = seller_trade_state.merkle_tree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            LISTING_RECEIPT.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            seller_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub listing_receipt: UncheckedAccount<'info>,
+    // Cnft proofs in the remaining accounts
+}
+
+pub fn close_expired_listing<'a>(
+    ctx: Context<'_, '_, '_, 'a, CloseExpiredListingInstruction<'a>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+) -> Result<()> {
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let seller = &ctx.accounts.seller.to_account_info();
+    let tree_config = &ctx.accounts.tree_config.to_account_info();
+    let merkle_tree = &ctx.accounts.merkle_tree.to_account_info();
+    let program_as_signer = &ctx.accounts.program_as_signer.to_account_info();
+    let bubblegum_program = &ctx.accounts.bubblegum_program.to_account_info();
+    let compression_program = &ctx.accounts.compression_program.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let log_wrapper = &ctx.accounts.log_wrapper.to_account_info();
+    let remaining_accounts = ctx.remaining_accounts;
+
+    let now = Clock::get()?.unix_timestamp;
+    if !seller_trade_state.is_expired(now) {
+        return Err(AuctionHouseV2Errors::OrderNotExpired.into());
+    }
+
+    let program_as_signer_bump = ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    let program_as_signer_seeds = [
+        PROGRAM.as_bytes(),
+        SIGNER.as_bytes(),
+        &[*program_as_signer_bump],
+    ];
+
+    // transferring the leaf to its own owner resets the leaf delegate to the owner
+    let mut builder = TransferCpiBuilder::new(bubblegum_program);
+    builder
+        .leaf_owner(seller, false)
+        .leaf_delegate(program_as_signer, true)
+        .new_leaf_owner(seller)
+        .tree_config(tree_config)
+        .merkle_tree(merkle_tree)
+        .log_wrapper(log_wrapper)
+        .compression_program(compression_program)
+        .system_program(system_program)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(seller_trade_state.leaf_index);
+    for info in remaining_accounts.iter() {
+        builder.add_remaining_account(info, false, false);
+    }
+    builder.invoke_signed(&[&program_as_signer_seeds])?;
+
+    update_receipt(
+        &ctx.accounts.listing_receipt,
+        |listing_receipt: &mut ListingReceipt| {
+            listing_receipt.canceled_at = Some(now);
+        },
+    )?;
+
+    emit!(Cancelled {
+        trade_state: seller_trade_state.key(),
+        auction_house: seller_trade_state.auction_house,
+        wallet: seller_trade_state.wallet,
+        asset_id: seller_trade_state.asset_id,
+        price: seller_trade_state.price,
+        side: seller_trade_state.side,
+    });
+
+    Ok(())
+}
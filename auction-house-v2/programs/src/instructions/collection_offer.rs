@@ -0,0 +1,105 @@
+use crate::events::CollectionOfferPlaced;
+use crate::offers::OfferEscrow;
+use crate::state::{AuctionHouseV2Data, CollectionOffer};
+use crate::{constants::*, errors::AuctionHouseV2Errors};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(buyer_price:u64)]
+pub struct CollectionOfferInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// CHECK: Validated in CPI
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Collection key the offer can be filled with, verified against the metadata on fill
+    pub collection: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),bidder.key().as_ref()],bump)]
+    pub buyer_escrow: UncheckedAccount<'info>,
+
+    #[account(init,payer=bidder,space=COLLECTION_OFFER_SIZE,seeds=[
+        COLLECTION_OFFER.as_ref(),
+        bidder.key().as_ref(),
+        auction_house.key().as_ref(),
+        collection.key().as_ref(),
+        buyer_price.to_le_bytes().as_ref()
+        ],bump)]
+    pub collection_offer: Box<Account<'info, CollectionOffer>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn collection_offer(
+    ctx: Context<CollectionOfferInstruction>,
+    buyer_price: u64,
+    quantity: u32,
+    expiry: Option<i64>,
+) -> Result<()> {
+    let bidder = &ctx.accounts.bidder.to_account_info();
+    let collection = ctx.accounts.collection.key();
+    let collection_offer_bump = *ctx
+        .bumps
+        .get("collection_offer")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let buyer_escrow_bump = ctx
+        .bumps
+        .get("buyer_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    let offer_escrow = OfferEscrow {
+        auction_house: &ctx.accounts.auction_house,
+        wallet: bidder,
+        buyer_escrow: &ctx.accounts.buyer_escrow.to_account_info(),
+        buyer_escrow_bump,
+        treasury_mint: &ctx.accounts.treasury_mint.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+        token_program: &ctx.accounts.token_program.to_account_info(),
+        rent: &ctx.accounts.rent,
+    };
+    let now = offer_escrow.place(
+        &ctx.accounts.payment_account.to_account_info(),
+        buyer_price,
+        quantity,
+        expiry,
+    )?;
+
+    let auction_house_key = ctx.accounts.auction_house.key();
+    let collection_offer = &mut ctx.accounts.collection_offer;
+    collection_offer.wallet = bidder.key();
+    collection_offer.auction_house = auction_house_key;
+    collection_offer.collection = collection;
+    collection_offer.price = buyer_price;
+    collection_offer.quantity = quantity;
+    collection_offer.created_at = now;
+    collection_offer.bump = collection_offer_bump;
+    collection_offer.expiry = expiry;
+
+    emit!(CollectionOfferPlaced {
+        collection_offer: collection_offer.key(),
+        auction_house: auction_house_key,
+        buyer: bidder.key(),
+        collection,
+        price: buyer_price,
+        quantity,
+        expiry,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::SealedBidCommitted;
+use crate::settlement::escrow_seeds;
+use crate::state::{AuctionHouseV2Data, SealedAuction, SealedBid};
+use crate::utils::transfer_to_escrow;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+pub struct CommitSealedBidInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// CHECK: Validated in CPI
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds=[SEALED_AUCTION.as_ref(), auction_house.key().as_ref(), sealed_auction.asset_id.as_ref()],
+        has_one=auction_house,
+        bump=sealed_auction.bump
+    )]
+    pub sealed_auction: Box<Account<'info, SealedAuction>>,
+
+    #[account(
+        init,
+        payer=bidder,
+        space=SEALED_BID_SIZE,
+        seeds=[SEALED_BID.as_ref(), sealed_auction.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sealed_bid: Box<Account<'info, SealedBid>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),sealed_bid.key().as_ref()],bump)]
+    pub bid_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn commit_sealed_bid(
+    ctx: Context<CommitSealedBidInstruction>,
+    commitment: [u8; 32],
+    deposit: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let treasury_mint = ctx.accounts.treasury_mint.to_account_info();
+    let bidder = &ctx.accounts.bidder.to_account_info();
+    let payment_account = &ctx.accounts.payment_account.to_account_info();
+    let sealed_auction = &ctx.accounts.sealed_auction;
+    let bid_escrow = &ctx.accounts.bid_escrow.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let rent = &ctx.accounts.rent;
+    let sealed_bid_bump = *ctx
+        .bumps
+        .get("sealed_bid")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let bid_escrow_bump = ctx
+        .bumps
+        .get("bid_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if now >= sealed_auction.commit_end_time {
+        return Err(AuctionHouseV2Errors::AuctionNotActive.into());
+    }
+    if deposit == 0 {
+        return Err(AuctionHouseV2Errors::InsufficientDeposit.into());
+    }
+
+    let sealed_bid_key = ctx.accounts.sealed_bid.key();
+    // the deposit only bounds the hidden price, it can be anything above it
+    transfer_to_escrow(
+        bidder,
+        payment_account,
+        bid_escrow,
+        &escrow_seeds(&auction_house.key(), &sealed_bid_key, bid_escrow_bump),
+        &auction_house.to_account_info(),
+        &treasury_mint,
+        system_program,
+        token_program,
+        rent,
+        deposit,
+    )?;
+
+    let sealed_auction_key = sealed_auction.key();
+    let reveal_end_time = sealed_auction.reveal_end_time;
+    let sealed_bid = &mut ctx.accounts.sealed_bid;
+    sealed_bid.auction = sealed_auction_key;
+    sealed_bid.bidder = bidder.key();
+    sealed_bid.commitment = commitment;
+    sealed_bid.deposit = deposit;
+    sealed_bid.revealed_price = None;
+    sealed_bid.reveal_end_time = reveal_end_time;
+    sealed_bid.bump = sealed_bid_bump;
+
+    emit!(SealedBidCommitted {
+        sealed_bid: sealed_bid_key,
+        auction: sealed_auction_key,
+        bidder: sealed_bid.bidder,
+        deposit,
+    });
+
+    Ok(())
+}
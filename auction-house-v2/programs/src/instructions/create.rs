@@ -1,13 +1,12 @@
 use crate::constants::*;
 use crate::errors::AuctionHouseV2Errors;
-use crate::state::AuctionHouseV2Data;
-use crate::utils::{check_if_ata_valid, create_program_associated_token_account};
+use crate::events::AuctionHouseCreated;
+use crate::state::{AuctionHouseV2Data, RoyaltyPolicy};
+use crate::utils::{create_program_associated_token_account, create_treasury_withdrawal_account};
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::spl_token::native_mint;
 use anchor_spl::token::Token;
-use spl_associated_token_account::instruction::create_associated_token_account;
 
 #[derive(Accounts)]
 pub struct CreateInstruction<'info> {
@@ -52,10 +51,14 @@ pub fn create(
     ctx: Context<CreateInstruction>,
     seller_fee_basis_points: u16,
     requires_sign_off: bool,
+    royalty_policy: RoyaltyPolicy,
 ) -> Result<()> {
     if seller_fee_basis_points > 10000 {
         return Err(AuctionHouseV2Errors::InvalidSellerFeeBasisPoints)?;
     }
+    if !royalty_policy.is_valid(seller_fee_basis_points) {
+        return Err(AuctionHouseV2Errors::InvalidRoyaltyPolicy.into());
+    }
 
     let auction_house = &mut ctx.accounts.auction_house;
     let treasury_account = &ctx.accounts.treasury_account;
@@ -92,6 +95,7 @@ pub fn create(
     auction_house.authority = ctx.accounts.authority.key();
     auction_house.requires_sign_off = requires_sign_off;
     auction_house.treasury_account = ctx.accounts.treasury_account.key();
+    auction_house.royalty_policy = royalty_policy;
 
     let is_native = ctx.accounts.treasury_mint.key() == native_mint::id();
     if !is_native {
@@ -112,32 +116,25 @@ pub fn create(
         )?;
 
         // Create treasury withdrawal ATA
-        if treasury_withdrawal_account.data_is_empty() {
-            let create_ata_instruction = create_associated_token_account(
-                payer.key,
-                treasury_withdrawal_owner.key,
-                treasury_mint.key,
-                token_program.key,
-            );
-            invoke(
-                &create_ata_instruction,
-                &[
-                    payer.to_account_info(),
-                    treasury_withdrawal_owner.to_account_info(),
-                    treasury_withdrawal_account.to_account_info(),
-                    treasury_mint.to_account_info(),
-                    token_program.to_account_info(),
-                    system_program.to_account_info(),
-                    associated_token_program.to_account_info(),
-                ],
-            )?;
-            check_if_ata_valid(
-                treasury_withdrawal_account,
-                treasury_withdrawal_owner.key,
-                treasury_mint.key,
-            )?;
-        }
+        create_treasury_withdrawal_account(
+            treasury_withdrawal_account,
+            treasury_withdrawal_owner,
+            treasury_mint,
+            payer,
+            system_program,
+            token_program,
+            associated_token_program,
+        )?;
     }
 
+    emit!(AuctionHouseCreated {
+        auction_house: auction_house_key,
+        authority: auction_house.authority,
+        treasury_mint: auction_house.treasury_mint,
+        seller_fee_basis_points,
+        requires_sign_off,
+        royalty_policy,
+    });
+
     Ok(())
 }
@@ -0,0 +1,163 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::AuctionCreated;
+use crate::state::{Auction, AuctionHouseV2Data};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+
+#[derive(Accounts)]
+pub struct CreateAuctionInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in auction house seeds
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer=owner,
+        space=AUCTION_SIZE,
+        seeds=[
+            AUCTION.as_ref(),
+            auction_house.key().as_ref(),
+            asset_id.key().as_ref()
+        ],
+        bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    /// CHECK: Verified against the merkle tree and nonce
+    pub asset_id: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    // Cnft proofs in the remaining accounts
+}
+
+pub fn create_auction<'a>(
+    ctx: Context<'_, '_, '_, 'a, CreateAuctionInstruction<'a>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    start_time: i64,
+    end_time: i64,
+    reserve_price: u64,
+    min_bid_increment: u64,
+    extension_window: Option<i64>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house.to_account_info();
+    let asset_id = &ctx.accounts.asset_id.to_account_info();
+    let merkle_tree = &ctx.accounts.merkle_tree.to_account_info();
+    let owner = &ctx.accounts.owner.to_account_info();
+    let previous_leaf_delegate = &ctx.accounts.previous_leaf_delegate.to_account_info();
+    let program_as_signer = &ctx.accounts.program_as_signer.to_account_info();
+    let compression_program = &ctx.accounts.compression_program.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let tree_config = &ctx.accounts.tree_config.to_account_info();
+    let log_wrapper = &ctx.accounts.log_wrapper.to_account_info();
+    let bubblegum_program = &ctx.accounts.bubblegum_program.to_account_info();
+    let remaining_accounts = ctx.remaining_accounts;
+    let auction_bump = *ctx
+        .bumps
+        .get("auction")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    if get_asset_id(merkle_tree.key, nonce) != asset_id.key() {
+        return Err(AuctionHouseV2Errors::AssetIdMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if end_time <= start_time || end_time <= now {
+        return Err(AuctionHouseV2Errors::InvalidAuctionTimes.into());
+    }
+    if matches!(extension_window, Some(window) if window <= 0) {
+        return Err(AuctionHouseV2Errors::InvalidAuctionTimes.into());
+    }
+    // a zero increment lets bidders outbid each other with the same amount
+    if min_bid_increment == 0 {
+        return Err(AuctionHouseV2Errors::InvalidBidIncrement.into());
+    }
+
+    // delegate the leaf so the auction can be settled without the seller
+    let mut builder = DelegateCpiBuilder::new(bubblegum_program);
+    builder
+        .leaf_owner(owner)
+        .tree_config(tree_config)
+        .previous_leaf_delegate(previous_leaf_delegate)
+        .new_leaf_delegate(program_as_signer)
+        .merkle_tree(merkle_tree)
+        .log_wrapper(log_wrapper)
+        .compression_program(compression_program)
+        .system_program(system_program)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index);
+    for info in remaining_accounts.iter() {
+        builder.add_remaining_account(info, false, false);
+    }
+    builder.invoke()?;
+
+    let auction = &mut ctx.accounts.auction;
+    auction.seller = owner.key();
+    auction.auction_house = auction_house.key();
+    auction.asset_id = asset_id.key();
+    auction.merkle_tree = merkle_tree.key();
+    auction.leaf_index = index;
+    auction.start_time = start_time;
+    auction.end_time = end_time;
+    auction.reserve_price = reserve_price;
+    auction.min_bid_increment = min_bid_increment;
+    auction.extension_window = extension_window;
+    auction.highest_bidder = None;
+    auction.highest_bid = 0;
+    auction.bump = auction_bump;
+
+    emit!(AuctionCreated {
+        auction: auction.key(),
+        auction_house: auction.auction_house,
+        seller: auction.seller,
+        asset_id: auction.asset_id,
+        merkle_tree: auction.merkle_tree,
+        leaf_index: index,
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+        extension_window,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::BundleCreated;
+use crate::state::{AuctionHouseV2Data, Bundle};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+#[instruction(bundle_id:u64)]
+pub struct CreateBundleInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in auction house seeds
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        init,
+        payer=seller,
+        space=BUNDLE_SIZE,
+        seeds=[
+            BUNDLE.as_ref(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            bundle_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bundle: Box<Account<'info, Bundle>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_bundle(
+    ctx: Context<CreateBundleInstruction>,
+    bundle_id: u64,
+    price: u64,
+    item_count: u8,
+) -> Result<()> {
+    let bundle_bump = *ctx
+        .bumps
+        .get("bundle")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    if !(2..=MAX_BUNDLE_ITEMS).contains(&item_count) {
+        return Err(AuctionHouseV2Errors::InvalidBundleSize.into());
+    }
+
+    let auction_house_key = ctx.accounts.auction_house.key();
+    let seller_key = ctx.accounts.seller.key();
+    let bundle = &mut ctx.accounts.bundle;
+    bundle.seller = seller_key;
+    bundle.auction_house = auction_house_key;
+    bundle.bundle_id = bundle_id;
+    bundle.price = price;
+    bundle.item_count = item_count;
+    bundle.items = Vec::with_capacity(item_count as usize);
+    bundle.created_at = Clock::get()?.unix_timestamp;
+    bundle.bump = bundle_bump;
+
+    emit!(BundleCreated {
+        bundle: bundle.key(),
+        auction_house: auction_house_key,
+        seller: seller_key,
+        price,
+        item_count,
+    });
+
+    Ok(())
+}
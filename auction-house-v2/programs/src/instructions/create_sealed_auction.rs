@@ -0,0 +1,154 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::SealedAuctionCreated;
+use crate::state::{AuctionHouseV2Data, SealedAuction};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+
+#[derive(Accounts)]
+pub struct CreateSealedAuctionInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in auction house seeds
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer=owner,
+        space=SEALED_AUCTION_SIZE,
+        seeds=[
+            SEALED_AUCTION.as_ref(),
+            auction_house.key().as_ref(),
+            asset_id.key().as_ref()
+        ],
+        bump
+    )]
+    pub sealed_auction: Box<Account<'info, SealedAuction>>,
+
+    /// CHECK: Verified against the merkle tree and nonce
+    pub asset_id: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    // Cnft proofs in the remaining accounts
+}
+
+pub fn create_sealed_auction<'a>(
+    ctx: Context<'_, '_, '_, 'a, CreateSealedAuctionInstruction<'a>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    commit_end_time: i64,
+    reveal_end_time: i64,
+    reserve_price: u64,
+    second_price: bool,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house.to_account_info();
+    let asset_id = &ctx.accounts.asset_id.to_account_info();
+    let merkle_tree = &ctx.accounts.merkle_tree.to_account_info();
+    let owner = &ctx.accounts.owner.to_account_info();
+    let previous_leaf_delegate = &ctx.accounts.previous_leaf_delegate.to_account_info();
+    let program_as_signer = &ctx.accounts.program_as_signer.to_account_info();
+    let compression_program = &ctx.accounts.compression_program.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let tree_config = &ctx.accounts.tree_config.to_account_info();
+    let log_wrapper = &ctx.accounts.log_wrapper.to_account_info();
+    let bubblegum_program = &ctx.accounts.bubblegum_program.to_account_info();
+    let remaining_accounts = ctx.remaining_accounts;
+    let sealed_auction_bump = *ctx
+        .bumps
+        .get("sealed_auction")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    if get_asset_id(merkle_tree.key, nonce) != asset_id.key() {
+        return Err(AuctionHouseV2Errors::AssetIdMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if commit_end_time <= now || reveal_end_time <= commit_end_time {
+        return Err(AuctionHouseV2Errors::InvalidAuctionTimes.into());
+    }
+
+    // delegate the leaf so the auction can be settled without the seller
+    let mut builder = DelegateCpiBuilder::new(bubblegum_program);
+    builder
+        .leaf_owner(owner)
+        .tree_config(tree_config)
+        .previous_leaf_delegate(previous_leaf_delegate)
+        .new_leaf_delegate(program_as_signer)
+        .merkle_tree(merkle_tree)
+        .log_wrapper(log_wrapper)
+        .compression_program(compression_program)
+        .system_program(system_program)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index);
+    for info in remaining_accounts.iter() {
+        builder.add_remaining_account(info, false, false);
+    }
+    builder.invoke()?;
+
+    let sealed_auction = &mut ctx.accounts.sealed_auction;
+    sealed_auction.seller = owner.key();
+    sealed_auction.auction_house = auction_house.key();
+    sealed_auction.asset_id = asset_id.key();
+    sealed_auction.merkle_tree = merkle_tree.key();
+    sealed_auction.leaf_index = index;
+    sealed_auction.commit_end_time = commit_end_time;
+    sealed_auction.reveal_end_time = reveal_end_time;
+    sealed_auction.reserve_price = reserve_price;
+    sealed_auction.second_price = second_price;
+    sealed_auction.highest_bidder = None;
+    sealed_auction.highest_bid = 0;
+    sealed_auction.second_highest_bid = 0;
+    sealed_auction.bump = sealed_auction_bump;
+
+    emit!(SealedAuctionCreated {
+        auction: sealed_auction.key(),
+        auction_house: sealed_auction.auction_house,
+        seller: sealed_auction.seller,
+        asset_id: sealed_auction.asset_id,
+        merkle_tree: sealed_auction.merkle_tree,
+        leaf_index: index,
+        commit_end_time,
+        reveal_end_time,
+        reserve_price,
+        second_price,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::SwapOrderCreated;
+use crate::settlement::escrow_seeds;
+use crate::state::{AuctionHouseV2Data, SwapOrder, SwapTarget};
+use crate::utils::{assert_valid_expiry, transfer_to_escrow};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+
+#[derive(Accounts)]
+pub struct CreateSwapOrderInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
+
+    /// CHECK: Verified in auction house seeds
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: Maker wallet for native auction house, maker token account otherwise. Validated in CPI
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the merkle tree and nonce
+    pub asset_id: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer=maker,
+        space=SWAP_ORDER_SIZE,
+        seeds=[
+            SWAP_ORDER.as_ref(),
+            maker.key().as_ref(),
+            auction_house.key().as_ref(),
+            asset_id.key().as_ref()
+        ],
+        bump
+    )]
+    pub swap_order: Box<Account<'info, SwapOrder>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),swap_order.key().as_ref()],bump)]
+    pub swap_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    // Cnft proofs in the remaining accounts
+}
+
+pub fn create_swap_order<'a>(
+    ctx: Context<'_, '_, '_, 'a, CreateSwapOrderInstruction<'a>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    requested: SwapTarget,
+    maker_payment: u64,
+    taker_payment: u64,
+    expiry: Option<i64>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let treasury_mint = &ctx.accounts.treasury_mint.to_account_info();
+    let maker = &ctx.accounts.maker.to_account_info();
+    let payment_account = &ctx.accounts.payment_account.to_account_info();
+    let merkle_tree = &ctx.accounts.merkle_tree.to_account_info();
+    let asset_id = ctx.accounts.asset_id.key();
+    let swap_escrow = &ctx.accounts.swap_escrow.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let rent = &ctx.accounts.rent;
+    let swap_order_bump = *ctx
+        .bumps
+        .get("swap_order")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let swap_escrow_bump = ctx
+        .bumps
+        .get("swap_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    if get_asset_id(merkle_tree.key, nonce) != asset_id {
+        return Err(AuctionHouseV2Errors::AssetIdMismatch.into());
+    }
+    if maker_payment > 0 && taker_payment > 0 {
+        return Err(AuctionHouseV2Errors::InvalidSwapPayment.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    assert_valid_expiry(expiry, now)?;
+
+    let mut builder = DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program);
+    builder
+        .leaf_owner(maker)
+        .tree_config(&ctx.accounts.tree_config)
+        .previous_leaf_delegate(&ctx.accounts.previous_leaf_delegate)
+        .new_leaf_delegate(&ctx.accounts.program_as_signer)
+        .merkle_tree(merkle_tree)
+        .log_wrapper(&ctx.accounts.log_wrapper)
+        .compression_program(&ctx.accounts.compression_program)
+        .system_program(system_program)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index);
+    for info in ctx.remaining_accounts.iter() {
+        builder.add_remaining_account(info, false, false);
+    }
+    builder.invoke()?;
+
+    // the maker side of the payment is locked until the order is taken or cancelled
+    let auction_house_key = auction_house.key();
+    let swap_order_key = ctx.accounts.swap_order.key();
+    if maker_payment > 0 {
+        transfer_to_escrow(
+            maker,
+            payment_account,
+            swap_escrow,
+            &escrow_seeds(&auction_house_key, &swap_order_key, swap_escrow_bump),
+            &auction_house.to_account_info(),
+            treasury_mint,
+            system_program,
+            token_program,
+            rent,
+            maker_payment,
+        )?;
+    }
+
+    let swap_order = &mut ctx.accounts.swap_order;
+    swap_order.maker = maker.key();
+    swap_order.auction_house = auction_house_key;
+    swap_order.asset_id = asset_id;
+    swap_order.merkle_tree = merkle_tree.key();
+    swap_order.leaf_index = index;
+    swap_order.requested = requested;
+    swap_order.maker_payment = maker_payment;
+    swap_order.taker_payment = taker_payment;
+    swap_order.created_at = now;
+    swap_order.expiry = expiry;
+    swap_order.bump = swap_order_bump;
+
+    emit!(SwapOrderCreated {
+        swap_order: swap_order_key,
+        auction_house: auction_house_key,
+        maker: swap_order.maker,
+        asset_id,
+        requested,
+        maker_payment,
+        taker_payment,
+        expiry,
+    });
+
+    Ok(())
+}
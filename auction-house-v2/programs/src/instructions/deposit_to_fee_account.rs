@@ -0,0 +1,47 @@
+use crate::constants::*;
+use crate::state::AuctionHouseV2Data;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::system_instruction::transfer;
+
+#[derive(Accounts)]
+pub struct DepositToFeeAccountInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in auction house seeds
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in auction house seeds
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn deposit_to_fee_account(
+    ctx: Context<DepositToFeeAccountInstruction>,
+    amount: u64,
+) -> Result<()> {
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let payer = &ctx.accounts.payer;
+    let system_program = &ctx.accounts.system_program;
+
+    let deposit_instruction = transfer(payer.key, auction_house_fee_account.key, amount);
+    invoke(
+        &deposit_instruction,
+        &[
+            payer.to_account_info(),
+            auction_house_fee_account.to_account_info(),
+            system_program.to_account_info(),
+        ],
+    )?;
+
+    Ok(())
+}
@@ -1,17 +1,17 @@
-use crate::utils::{check_if_ata_valid, close, get_fee_payer, hash_metadata};
-use crate::MetadataArgs;
-use crate::{
-    constants::*, errors::AuctionHouseV2Errors, state::AuctionHouseV2Data, utils::cmp_bytes,
+use crate::events::SaleExecuted;
+use crate::settlement::{
+    escrow_seeds, program_as_signer_seeds, LeafTransfer, PaymentSource, SaleAccounts,
 };
-use anchor_lang::solana_program::program::invoke_signed;
-use anchor_lang::{prelude::*, solana_program::system_instruction::transfer};
+use crate::state::{AuctionHouseV2Data, BidReceipt, ListingReceipt, TradeSide, TradeState};
+use crate::utils::{escrow_balance, hash_creators, hash_metadata, update_receipt};
+use crate::MetadataArgs;
+use crate::{constants::*, errors::AuctionHouseV2Errors, utils::cmp_bytes};
+use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token};
-use mpl_bubblegum::instructions::TransferCpiBuilder;
-use spl_associated_token_account::instruction::create_associated_token_account;
 
 #[derive(Accounts)]
-#[instruction(buyer_price:u64)]
+#[instruction(buyer_price:u64, seller_price:u64)]
 pub struct ExecuteSaleInstruction<'info> {
     #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
     pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
@@ -40,7 +40,6 @@ pub struct ExecuteSaleInstruction<'info> {
     #[account(mut)]
     pub merkle_tree: UncheckedAccount<'info>,
 
-    /// CHECK: Account seeds checked in constraints
     #[account(
         mut,
         seeds=[
@@ -48,11 +47,13 @@ pub struct ExecuteSaleInstruction<'info> {
             seller.key().as_ref(),
             auction_house.key().as_ref(),
             asset_id.key().as_ref(),
-            buyer_price.to_le_bytes().as_ref()
+            seller_price.to_le_bytes().as_ref()
         ],
-        bump
+        constraint = seller_trade_state.side == TradeSide::Ask @ AuctionHouseV2Errors::BothPartiesNeedToAgreeToSale,
+        close = seller,
+        bump = seller_trade_state.bump
     )]
-    pub seller_trade_state: UncheckedAccount<'info>,
+    pub seller_trade_state: Box<Account<'info, TradeState>>,
 
     /// CHECK: verified in buyer_trade_state seeds constraints
     #[account(mut)]
@@ -62,7 +63,6 @@ pub struct ExecuteSaleInstruction<'info> {
     #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),buyer.key().as_ref()],bump)]
     pub buyer_escrow: UncheckedAccount<'info>,
 
-    /// CHECK: Account seeds checked in constraints
     #[account(mut,seeds=[
             TRADE_STATE.as_ref(),
             buyer.key().as_ref(),
@@ -70,15 +70,17 @@ pub struct ExecuteSaleInstruction<'info> {
             asset_id.key().as_ref(),
             buyer_price.to_le_bytes().as_ref()
         ],
-        bump
+        constraint = buyer_trade_state.side == TradeSide::Bid @ AuctionHouseV2Errors::InvalidBuyerTradeState,
+        close = buyer,
+        bump = buyer_trade_state.bump
     )]
-    pub buyer_trade_state: UncheckedAccount<'info>,
+    pub buyer_trade_state: Box<Account<'info, TradeState>>,
 
     /// CHECK: Verified in CPI
     pub asset_id: UncheckedAccount<'info>,
 
     /// CHECK: Account seeds checked in constraints
-    #[account(seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
     pub auction_house_fee_account: UncheckedAccount<'info>,
 
     /// CHECK: Account seeds checked in constraints
@@ -99,6 +101,30 @@ pub struct ExecuteSaleInstruction<'info> {
 
     /// CHECK: Verified in CPI
     pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            LISTING_RECEIPT.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            seller_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub listing_receipt: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            BID_RECEIPT.as_bytes(),
+            buyer_trade_state.key().as_ref(),
+            buyer_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bid_receipt: UncheckedAccount<'info>,
     /* Remaining Accounts
        - ...Creator Accounts
        - ...Cnft proofs in the remaining accounts
@@ -108,349 +134,190 @@ pub struct ExecuteSaleInstruction<'info> {
 pub fn execute_sale<'a>(
     ctx: Context<'_, '_, '_, 'a, ExecuteSaleInstruction<'a>>,
     buyer_price: u64,
+    seller_price: u64,
     root: [u8; 32],
     data_hash: [u8; 32],
     creator_hash: [u8; 32],
     nonce: u64,
     index: u32,
-    royalty_basis_points: u16,
     metadata: MetadataArgs,
+    buyer_royalty_basis_points: Option<u16>,
 ) -> Result<()> {
     let auction_house = &ctx.accounts.auction_house;
-    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account.to_account_info();
-    let auction_house_authority = &ctx.accounts.auction_house_authority;
     let merkle_tree_info = &ctx.accounts.merkle_tree.to_account_info();
-    let seller_info = ctx.accounts.seller.to_account_info();
-    let buyer_info = ctx.accounts.buyer.to_account_info();
-    let treasury_account = &ctx.accounts.treasury_account.to_account_info();
+    let seller_info = &ctx.accounts.seller.to_account_info();
+    let buyer_info = &ctx.accounts.buyer.to_account_info();
     let treasury_mint = &ctx.accounts.treasury_mint;
-    let seller_trade_state_info = ctx.accounts.seller_trade_state.to_account_info();
-    let buyer_trade_state_info = ctx.accounts.buyer_trade_state.to_account_info();
-    let buyer_escrow = ctx.accounts.buyer_escrow.to_account_info();
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let buyer_escrow = &ctx.accounts.buyer_escrow.to_account_info();
     let program_as_signer_info = &ctx.accounts.program_as_signer.to_account_info();
     let compression_program_info = &ctx.accounts.compression_program.to_account_info();
-    let system_program = &ctx.accounts.system_program;
+    let system_program_info = &ctx.accounts.system_program.to_account_info();
     let tree_config_info = &ctx.accounts.tree_config.to_account_info();
     let log_wrapper_info = &ctx.accounts.log_wrapper.to_account_info();
     let auction_house_info = &ctx.accounts.auction_house.to_account_info();
     let bubblegum_program_info = &ctx.accounts.bubblegum_program.to_account_info();
-    let seller_receipt_info = &ctx.accounts.seller_receipt_account.to_account_info();
-    let token_program = &ctx.accounts.token_program;
-    let associated_token_program = &ctx.accounts.associated_token_program;
-    let remaining_accounts = &ctx.remaining_accounts;
+    let remaining_accounts = ctx.remaining_accounts;
 
     let hashed_metadata = hash_metadata(&metadata)?;
     if !cmp_bytes(&data_hash, &hashed_metadata, 32) {
         return Err(AuctionHouseV2Errors::MetadataHashMismatch.into());
     }
+    if !cmp_bytes(&creator_hash, &hash_creators(&metadata.creators), 32) {
+        return Err(AuctionHouseV2Errors::CreatorHashMismatch.into());
+    }
 
-    if buyer_trade_state_info.data_is_empty() || (buyer_trade_state_info.try_borrow_data()?[0] == 0)
+    if seller_trade_state.merkle_tree != merkle_tree_info.key()
+        || seller_trade_state.leaf_index != index
     {
-        return Err(AuctionHouseV2Errors::InvalidBuyerTradeState.into());
+        return Err(AuctionHouseV2Errors::TradeStateLeafMismatch.into());
     }
 
-    if seller_trade_state_info.data_is_empty()
-        || (seller_trade_state_info.try_borrow_data()?[0] == 0)
-    {
-        return Err(AuctionHouseV2Errors::BothPartiesNeedToAgreeToSale.into());
+    let now = Clock::get()?.unix_timestamp;
+    if seller_trade_state.is_expired(now) || buyer_trade_state.is_expired(now) {
+        return Err(AuctionHouseV2Errors::OrderExpired.into());
     }
 
-    // assert buyer and seller trade state configs
-    if buyer_escrow.lamports() < buyer_price {
-        return Err(AuctionHouseV2Errors::NotEnoughFunds.into());
+    if !seller_trade_state.accepts_buyer(buyer_info.key) {
+        return Err(AuctionHouseV2Errors::BuyerNotAllowed.into());
     }
 
-    let treasury_mint_key = treasury_mint.key();
-    let auction_house_authority_key = auction_house_authority.key();
-    let is_native = treasury_mint_key == spl_token::native_mint::id();
-
-    let auction_house_seeds = [
-        AUCTION_HOUSE.as_ref(),
-        auction_house_authority_key.as_ref(),
-        treasury_mint_key.as_ref(),
-        &[auction_house.bump],
-    ];
-
-    let auction_house_fee_account_bump = auction_house.bump;
-
-    let auction_house_key = auction_house.key();
-    let auction_house_fee_payer_seeds = [
-        FEE.as_bytes(),
-        auction_house_key.as_ref(),
-        &[auction_house_fee_account_bump],
-    ];
-
-    // Use this fee payer for creating token accounts in non native auction house
-    let (fee_payer, fee_payer_seeds) = get_fee_payer(
-        auction_house.clone(),
-        auction_house_fee_account.clone(),
-        &auction_house_fee_payer_seeds,
-        auction_house_authority.clone(),
-        seller_info.clone(),
-        buyer_info.clone(),
-    )?;
+    // a royalty choice only counts when the buyer signed for it
+    let buyer_royalty_basis_points = buyer_royalty_basis_points.filter(|_| buyer_info.is_signer);
+
+    // orders cross when the bid is at least the ask, the sale clears at the ask
+    // and the rest of the bid stays in the buyer escrow
+    if buyer_price < seller_price {
+        return Err(AuctionHouseV2Errors::BidBelowAsk.into());
+    }
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    if escrow_balance(buyer_escrow, is_native, &Rent::get()?)? < seller_price {
+        return Err(AuctionHouseV2Errors::NotEnoughFunds.into());
+    }
 
     let buyer_escrow_bump = ctx
         .bumps
         .get("buyer_escrow")
         .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
 
-    let buyer_escrow_signer_seeds = [
-        ESCROW.as_bytes(),
-        auction_house_info.key.as_ref(),
-        buyer_info.key.as_ref(),
-        &[*buyer_escrow_bump],
-    ];
-
     let program_as_signer_bump = ctx
         .bumps
         .get("program_as_signer")
         .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
-
-    let program_as_signer_seeds = [
-        PROGRAM.as_bytes(),
-        SIGNER.as_bytes(),
-        &[*program_as_signer_bump],
-    ];
+    let program_as_signer_seeds = program_as_signer_seeds(program_as_signer_bump);
 
     let remaining_accounts_iter = &mut remaining_accounts.iter();
 
-    // pay auction house fees
-    let auction_house_fees = buyer_price
-        .checked_mul(auction_house.seller_fee_basis_points.into())
-        .unwrap()
-        .checked_div(10000)
-        .unwrap();
-
-    if is_native {
-        let pay_to_auction_house_instruction =
-            transfer(buyer_escrow.key, treasury_account.key, auction_house_fees);
-
-        let pay_to_auction_house_accounts = [
-            buyer_escrow.clone(),
-            treasury_account.clone(),
-            system_program.to_account_info(),
-        ];
-        invoke_signed(
-            &pay_to_auction_house_instruction,
-            &pay_to_auction_house_accounts,
-            &[&buyer_escrow_signer_seeds],
-        )?;
-    } else {
-        let pay_to_auction_house_instruction = spl_token::instruction::transfer(
-            token_program.key,
-            buyer_escrow.key,
-            treasury_account.key,
-            &auction_house_key,
-            &[],
-            auction_house_fees,
-        )?;
-        let pay_to_auction_house_accounts = [
-            token_program.to_account_info(),
-            buyer_escrow.clone(),
-            auction_house.to_account_info(),
-            treasury_account.clone(),
-        ];
-        invoke_signed(
-            &pay_to_auction_house_instruction,
-            &pay_to_auction_house_accounts,
-            &[&auction_house_seeds],
-        )?;
-    }
-
-    // pay creator royalties
-    let mut remaining_buyer_funds = buyer_price.checked_sub(auction_house_fees).unwrap();
-    let creator_royalties = buyer_price
-        .checked_mul(royalty_basis_points.into())
-        .unwrap()
-        .checked_div(10000)
-        .unwrap();
-
-    if !metadata.creators.is_empty() {
-        for creator in metadata.creators.iter() {
-            let share = creator_royalties
-                .checked_mul(creator.share.into())
-                .unwrap()
-                .checked_div(100)
-                .unwrap();
-            remaining_buyer_funds = remaining_buyer_funds.checked_sub(share).unwrap();
-
-            let creator_info = next_account_info(remaining_accounts_iter)?;
-            if is_native {
-                if share > 0 {
-                    let pay_to_creator_instruction =
-                        transfer(buyer_escrow.key, &creator.address, share);
-                    let pay_to_creator_accounts = [
-                        buyer_escrow.clone(),
-                        creator_info.clone(),
-                        system_program.to_account_info(),
-                    ];
-                    invoke_signed(
-                        &pay_to_creator_instruction,
-                        &pay_to_creator_accounts,
-                        &[&buyer_escrow_signer_seeds],
-                    )?;
-                }
-            } else {
-                let creator_token_account = next_account_info(remaining_accounts_iter)?;
-                if share > 0 {
-                    if creator_token_account.data_is_empty() {
-                        // create current creator token account if not initialised
-                        let create_ata_instruction = create_associated_token_account(
-                            fee_payer.key,
-                            creator_info.key,
-                            &treasury_mint_key,
-                            token_program.key,
-                        );
-                        let fee_payer_seeds = [fee_payer_seeds];
-                        let fee_signer_seeds: &[&[&[u8]]] = if fee_payer_seeds.is_empty() {
-                            &[]
-                        } else {
-                            &fee_payer_seeds
-                        };
-                        invoke_signed(
-                            &create_ata_instruction,
-                            &[
-                                fee_payer.clone(),
-                                creator_info.clone(),
-                                treasury_mint.to_account_info(),
-                                token_program.to_account_info(),
-                                creator_token_account.to_account_info(),
-                                associated_token_program.to_account_info(),
-                            ],
-                            fee_signer_seeds,
-                        )?;
-                    }
-                    check_if_ata_valid(
-                        creator_token_account,
-                        &creator_info.key(),
-                        &treasury_mint_key,
-                    )?;
-
-                    // transfer royalty tokens to creator token account
-                    let pay_to_creator_instruction = spl_token::instruction::transfer(
-                        token_program.key,
-                        buyer_escrow.key,
-                        creator_token_account.key,
-                        &auction_house_key,
-                        &[],
-                        share,
-                    )?;
-                    let pay_to_creator_accounts = [
-                        token_program.to_account_info(),
-                        buyer_escrow.clone(),
-                        auction_house.to_account_info(),
-                        creator_token_account.clone(),
-                    ];
-                    invoke_signed(
-                        &pay_to_creator_instruction,
-                        &pay_to_creator_accounts,
-                        &[&auction_house_seeds],
-                    )?;
-                }
-            }
-        }
-    }
-
-    // transfer funds to seller
-    if is_native {
-        let pay_to_seller_instruction =
-            transfer(buyer_escrow.key, seller_info.key, remaining_buyer_funds);
-        let pay_to_seller_accounts = [
-            buyer_escrow,
-            seller_info.clone(),
-            system_program.to_account_info(),
-        ];
-        invoke_signed(
-            &pay_to_seller_instruction,
-            &pay_to_seller_accounts,
-            &[&buyer_escrow_signer_seeds],
-        )?;
-    } else {
-        // create seller receipt ATA
-        if seller_receipt_info.data_is_empty() {
-            let create_ata_instruction = create_associated_token_account(
-                fee_payer.key,
-                seller_info.key,
-                &treasury_mint_key,
-                token_program.key,
-            );
-            let fee_payer_seeds = [fee_payer_seeds];
-            let fee_signer_seeds: &[&[&[u8]]] = if fee_payer_seeds.is_empty() {
-                &[]
-            } else {
-                &fee_payer_seeds
-            };
-            invoke_signed(
-                &create_ata_instruction,
-                &[
-                    fee_payer.clone(),
-                    seller_info.clone(),
-                    treasury_mint.to_account_info(),
-                    token_program.to_account_info(),
-                    seller_receipt_info.to_account_info(),
-                    associated_token_program.to_account_info(),
-                    system_program.to_account_info(),
-                ],
-                fee_signer_seeds,
-            )?;
-        }
-
-        let loaded_seller_token_account =
-            check_if_ata_valid(&seller_receipt_info, seller_info.key, &treasury_mint_key)?;
-
-        // check if seller token account have a delegate
-        if loaded_seller_token_account.delegate.is_some() {
-            return Err(AuctionHouseV2Errors::SellerTokenAccountCannotHaveDelegate.into());
-        }
-
-        let pay_to_seller_instruction = spl_token::instruction::transfer(
-            token_program.key,
-            buyer_escrow.key,
-            seller_receipt_info.key,
-            &auction_house_key,
-            &[],
-            remaining_buyer_funds,
-        )?;
-        let pay_to_seller_accounts = [
-            token_program.to_account_info(),
-            buyer_escrow.clone(),
-            auction_house.to_account_info(),
-            seller_receipt_info.clone(),
-        ];
-        invoke_signed(
-            &pay_to_seller_instruction,
-            &pay_to_seller_accounts,
-            &[&auction_house_seeds],
-        )?;
-    }
+    // native escrow signs for itself, token escrow is owned by the auction house
+    let payment_source = PaymentSource::escrow(
+        buyer_escrow,
+        escrow_seeds(auction_house_info.key, buyer_info.key, buyer_escrow_bump),
+        auction_house,
+    );
+
+    let sale_accounts = SaleAccounts::from_house(
+        auction_house,
+        &ctx.accounts.auction_house_authority,
+        &ctx.accounts.auction_house_fee_account,
+        &ctx.accounts.treasury_mint.to_account_info(),
+        &ctx.accounts.treasury_account,
+        seller_info,
+        &ctx.accounts.seller_receipt_account,
+        buyer_info,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+    )?;
+    // creators take the proceeds of a primary sale by a verified creator. Bubblegum
+    // has no instruction the program could use to flip `primary_sale_happened` on
+    // the leaf, so the flag stays unset until the tree or collection authority
+    // updates it and later resales by other sellers only pay royalties
+    let payouts = sale_accounts.pay(
+        &payment_source,
+        seller_price,
+        auction_house.seller_fee_basis_points,
+        auction_house.royalty_policy.royalty_basis_points(
+            auction_house.seller_fee_basis_points,
+            metadata.seller_fee_basis_points,
+            buyer_royalty_basis_points,
+        ),
+        sale_accounts.is_primary_sale(&metadata),
+        &metadata.creators,
+        remaining_accounts_iter,
+    )?;
 
     // transfer nft to buyer
-    let mut transfer_nft_to_buyer_builder = TransferCpiBuilder::new(&bubblegum_program_info);
-    let system_program_info = system_program.to_account_info();
-    transfer_nft_to_buyer_builder
-        .leaf_owner(&seller_info, false)
-        .leaf_delegate(&program_as_signer_info, true)
-        .new_leaf_owner(&buyer_info)
-        .tree_config(&tree_config_info)
-        .merkle_tree(&merkle_tree_info)
-        .log_wrapper(&log_wrapper_info)
-        .compression_program(&compression_program_info)
-        .system_program(&system_program_info)
-        .root(root)
-        .data_hash(data_hash)
-        .creator_hash(creator_hash)
-        .nonce(nonce)
-        .index(index);
-
-    for info in remaining_accounts_iter {
-        transfer_nft_to_buyer_builder.add_remaining_account(info, false, false);
-    }
-    transfer_nft_to_buyer_builder.invoke_signed(&[&program_as_signer_seeds])?;
+    let leaf_transfer = LeafTransfer {
+        bubblegum_program: bubblegum_program_info,
+        tree_config: tree_config_info,
+        merkle_tree: merkle_tree_info,
+        log_wrapper: log_wrapper_info,
+        compression_program: compression_program_info,
+        system_program: system_program_info,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+    };
+    leaf_transfer.invoke(
+        seller_info,
+        program_as_signer_info,
+        buyer_info,
+        remaining_accounts_iter,
+        &[&program_as_signer_seeds],
+    )?;
 
-    // close trade states
-    close(seller_trade_state_info, seller_info)?;
-    close(buyer_trade_state_info, buyer_info)?;
+    // point the receipts to the purchase receipt which can be printed
+    // afterwards, the wallet of a side stands in for a receipt never printed
+    let listing_receipt_info = &ctx.accounts.listing_receipt.to_account_info();
+    let bid_receipt_info = &ctx.accounts.bid_receipt.to_account_info();
+    let listing_receipt_key = if listing_receipt_info.data_is_empty() {
+        seller_info.key
+    } else {
+        listing_receipt_info.key
+    };
+    let bid_receipt_key = if bid_receipt_info.data_is_empty() {
+        buyer_info.key
+    } else {
+        bid_receipt_info.key
+    };
+    let (purchase_receipt, _) = Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT.as_bytes(),
+            listing_receipt_key.as_ref(),
+            bid_receipt_key.as_ref(),
+        ],
+        ctx.program_id,
+    );
+    update_receipt(
+        listing_receipt_info,
+        |listing_receipt: &mut ListingReceipt| {
+            listing_receipt.purchased_at = Some(now);
+            listing_receipt.purchase_receipt = Some(purchase_receipt);
+        },
+    )?;
+    update_receipt(bid_receipt_info, |bid_receipt: &mut BidReceipt| {
+        bid_receipt.purchased_at = Some(now);
+        bid_receipt.purchase_receipt = Some(purchase_receipt);
+    })?;
+
+    emit!(SaleExecuted {
+        auction_house: auction_house.key(),
+        seller: seller_info.key(),
+        buyer: buyer_info.key(),
+        asset_id: seller_trade_state.asset_id,
+        merkle_tree: merkle_tree_info.key(),
+        leaf_index: index,
+        price: seller_price,
+        auction_house_fee: payouts.auction_house_fee,
+        royalty_basis_points: payouts.royalty_basis_points,
+        primary_sale: payouts.primary_sale,
+        creator_royalties: payouts.creator_royalties,
+        seller_proceeds: payouts.seller_proceeds,
+    });
 
     Ok(())
 }
@@ -9,3 +9,89 @@ pub use execute_sale::*;
 pub use sell::*;
 pub mod cancel;
 pub use cancel::*;
+pub mod update_auction_house;
+pub use update_auction_house::*;
+pub mod withdraw_from_treasury;
+pub use withdraw_from_treasury::*;
+pub mod deposit_to_fee_account;
+pub mod withdraw_from_fee_account;
+pub use deposit_to_fee_account::*;
+pub use withdraw_from_fee_account::*;
+pub mod withdraw;
+pub use withdraw::*;
+pub mod deposit;
+pub use deposit::*;
+pub mod cancel_bid;
+pub use cancel_bid::*;
+pub mod close_expired_bid;
+pub mod close_expired_listing;
+pub use close_expired_bid::*;
+pub use close_expired_listing::*;
+pub mod print_bid_receipt;
+pub mod print_listing_receipt;
+pub mod print_purchase_receipt;
+pub use print_bid_receipt::*;
+pub use print_listing_receipt::*;
+pub use print_purchase_receipt::*;
+pub mod update_listing_price;
+pub use update_listing_price::*;
+pub mod buy;
+pub use buy::*;
+pub mod accept_offer;
+pub use accept_offer::*;
+pub mod accept_collection_offer;
+pub mod cancel_collection_offer;
+pub mod collection_offer;
+pub use accept_collection_offer::*;
+pub use cancel_collection_offer::*;
+pub use collection_offer::*;
+pub mod accept_trait_offer;
+pub mod cancel_trait_offer;
+pub mod trait_offer;
+pub use accept_trait_offer::*;
+pub use cancel_trait_offer::*;
+pub use trait_offer::*;
+pub mod cancel_auction;
+pub mod create_auction;
+pub mod place_auction_bid;
+pub mod refund_auction_bid;
+pub mod settle_auction;
+pub use cancel_auction::*;
+pub use create_auction::*;
+pub use place_auction_bid::*;
+pub use refund_auction_bid::*;
+pub use settle_auction::*;
+pub mod buy_dutch_auction;
+pub mod cancel_dutch_auction;
+pub mod create_dutch_auction;
+pub use buy_dutch_auction::*;
+pub use cancel_dutch_auction::*;
+pub use create_dutch_auction::*;
+pub mod cancel_sealed_auction;
+pub mod commit_sealed_bid;
+pub mod create_sealed_auction;
+pub mod reveal_sealed_bid;
+pub mod settle_sealed_auction;
+pub mod withdraw_sealed_bid;
+pub use cancel_sealed_auction::*;
+pub use commit_sealed_bid::*;
+pub use create_sealed_auction::*;
+pub use reveal_sealed_bid::*;
+pub use settle_sealed_auction::*;
+pub use withdraw_sealed_bid::*;
+pub mod add_bundle_item;
+pub mod buy_bundle;
+pub mod cancel_bundle;
+pub mod create_bundle;
+pub mod remove_bundle_item;
+pub use add_bundle_item::*;
+pub use buy_bundle::*;
+pub use cancel_bundle::*;
+pub use create_bundle::*;
+pub use remove_bundle_item::*;
+pub mod accept_swap_order;
+pub mod cancel_swap_order;
+pub mod create_swap_order;
+pub use accept_swap_order::*;
+pub use cancel_swap_order::*;
+pub use create_swap_order::*;
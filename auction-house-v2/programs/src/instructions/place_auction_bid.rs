@@ -0,0 +1,153 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::AuctionBidPlaced;
+use crate::settlement::{auction_house_seeds, escrow_seeds};
+use crate::state::{Auction, AuctionHouseV2Data};
+use crate::utils::{transfer_from_escrow, transfer_to_escrow};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+pub struct PlaceAuctionBidInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// CHECK: Validated in CPI
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[AUCTION.as_ref(), auction_house.key().as_ref(), auction.asset_id.as_ref()],
+        has_one=auction_house,
+        bump=auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),auction.key().as_ref()],bump)]
+    pub auction_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the auction highest bidder when there is one
+    #[account(mut)]
+    pub previous_bidder: UncheckedAccount<'info>,
+
+    /// CHECK: Wallet for native auction house, previous bidder token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub previous_bidder_receipt_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn place_auction_bid(ctx: Context<PlaceAuctionBidInstruction>, amount: u64) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_info = auction_house.to_account_info();
+    let treasury_mint = ctx.accounts.treasury_mint.to_account_info();
+    let bidder = &ctx.accounts.bidder.to_account_info();
+    let payment_account = &ctx.accounts.payment_account.to_account_info();
+    let auction_escrow = &ctx.accounts.auction_escrow.to_account_info();
+    let previous_bidder = &ctx.accounts.previous_bidder.to_account_info();
+    let previous_bidder_receipt_account = &ctx
+        .accounts
+        .previous_bidder_receipt_account
+        .to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let rent = &ctx.accounts.rent;
+    let auction = &ctx.accounts.auction;
+
+    let now = Clock::get()?.unix_timestamp;
+    if !auction.is_active(now) {
+        return Err(AuctionHouseV2Errors::AuctionNotActive.into());
+    }
+
+    let minimum_bid = match auction.highest_bidder {
+        Some(_) => auction
+            .highest_bid
+            .checked_add(auction.min_bid_increment)
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?,
+        None => auction.reserve_price,
+    };
+    if amount < minimum_bid || amount == 0 {
+        return Err(AuctionHouseV2Errors::BidTooLow.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let auction_key = auction.key();
+    let treasury_mint_key = treasury_mint.key();
+    let auction_escrow_bump = ctx
+        .bumps
+        .get("auction_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let escrow_signer_seeds = escrow_seeds(&auction_house_key, &auction_key, auction_escrow_bump);
+
+    // fund the new bid before refunding so the escrow is never emptied
+    transfer_to_escrow(
+        bidder,
+        payment_account,
+        auction_escrow,
+        &escrow_signer_seeds,
+        &auction_house_info,
+        &treasury_mint,
+        system_program,
+        token_program,
+        rent,
+        amount,
+    )?;
+
+    if let Some(highest_bidder) = auction.highest_bidder {
+        if previous_bidder.key() != highest_bidder {
+            return Err(AuctionHouseV2Errors::PublicKeyMismatch.into());
+        }
+        transfer_from_escrow(
+            auction_escrow,
+            &escrow_signer_seeds,
+            &auction_house_info,
+            &auction_house_seeds(auction_house),
+            previous_bidder,
+            previous_bidder_receipt_account,
+            &treasury_mint_key,
+            system_program,
+            token_program,
+            rent,
+            auction.highest_bid,
+        )?;
+    }
+
+    let auction = &mut ctx.accounts.auction;
+    auction.highest_bidder = Some(bidder.key());
+    auction.highest_bid = amount;
+
+    // anti sniping, late bids push the end of the auction back
+    if let Some(extension_window) = auction.extension_window {
+        let extended_end_time = now
+            .checked_add(extension_window)
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+        if extended_end_time > auction.end_time {
+            auction.end_time = extended_end_time;
+        }
+    }
+
+    emit!(AuctionBidPlaced {
+        auction: auction_key,
+        auction_house: auction_house_key,
+        bidder: bidder.key(),
+        asset_id: auction.asset_id,
+        amount,
+        end_time: auction.end_time,
+    });
+
+    Ok(())
+}
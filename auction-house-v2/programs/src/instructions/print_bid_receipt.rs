@@ -0,0 +1,53 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::state::{BidReceipt, TradeSide, TradeState};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PrintBidReceiptInstruction<'info> {
+    #[account(
+        constraint = buyer_trade_state.side == TradeSide::Bid @ AuctionHouseV2Errors::InvalidBuyerTradeState
+    )]
+    pub buyer_trade_state: Box<Account<'info, TradeState>>,
+
+    #[account(
+        init,
+        payer=payer,
+        space=BID_RECEIPT_SIZE,
+        seeds=[
+            BID_RECEIPT.as_bytes(),
+            buyer_trade_state.key().as_ref(),
+            buyer_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub receipt: Box<Account<'info, BidReceipt>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn print_bid_receipt(ctx: Context<PrintBidReceiptInstruction>) -> Result<()> {
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let receipt = &mut ctx.accounts.receipt;
+
+    receipt.trade_state = buyer_trade_state.key();
+    receipt.auction_house = buyer_trade_state.auction_house;
+    receipt.buyer = buyer_trade_state.wallet;
+    receipt.asset_id = buyer_trade_state.asset_id;
+    receipt.merkle_tree = buyer_trade_state.merkle_tree;
+    receipt.leaf_index = buyer_trade_state.leaf_index;
+    receipt.price = buyer_trade_state.price;
+    receipt.created_at = buyer_trade_state.created_at;
+    receipt.canceled_at = None;
+    receipt.purchased_at = None;
+    receipt.purchase_receipt = None;
+    receipt.bump = *ctx
+        .bumps
+        .get("receipt")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::state::{ListingReceipt, TradeSide, TradeState};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PrintListingReceiptInstruction<'info> {
+    #[account(
+        constraint = seller_trade_state.side == TradeSide::Ask @ AuctionHouseV2Errors::InvalidSellerTradeState
+    )]
+    pub seller_trade_state: Box<Account<'info, TradeState>>,
+
+    #[account(
+        init,
+        payer=payer,
+        space=LISTING_RECEIPT_SIZE,
+        seeds=[
+            LISTING_RECEIPT.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            seller_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub receipt: Box<Account<'info, ListingReceipt>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn print_listing_receipt(ctx: Context<PrintListingReceiptInstruction>) -> Result<()> {
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let receipt = &mut ctx.accounts.receipt;
+
+    receipt.trade_state = seller_trade_state.key();
+    receipt.auction_house = seller_trade_state.auction_house;
+    receipt.seller = seller_trade_state.wallet;
+    receipt.asset_id = seller_trade_state.asset_id;
+    receipt.merkle_tree = seller_trade_state.merkle_tree;
+    receipt.leaf_index = seller_trade_state.leaf_index;
+    receipt.price = seller_trade_state.price;
+    receipt.created_at = seller_trade_state.created_at;
+    receipt.canceled_at = None;
+    receipt.purchased_at = None;
+    receipt.purchase_receipt = None;
+    receipt.bump = *ctx
+        .bumps
+        .get("receipt")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::state::{BidReceipt, ListingReceipt, PurchaseReceipt};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PrintPurchaseReceiptInstruction<'info> {
+    /// CHECK: Listing receipt of the sell order, or the seller wallet when no listing receipt was printed
+    pub listing_receipt: UncheckedAccount<'info>,
+
+    /// CHECK: Bid receipt of the bid order, or the buyer wallet when no bid receipt was printed
+    pub bid_receipt: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer=payer,
+        space=PURCHASE_RECEIPT_SIZE,
+        seeds=[
+            PURCHASE_RECEIPT.as_bytes(),
+            listing_receipt.key().as_ref(),
+            bid_receipt.key().as_ref()
+        ],
+        bump
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Loads `receipt` when it is a receipt of the program rather than a wallet.
+fn load_receipt<'info, T>(receipt: &AccountInfo<'info>) -> Result<Option<Account<'info, T>>>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    if receipt.owner != &crate::ID {
+        return Ok(None);
+    }
+    Ok(Some(Account::try_from(receipt)?))
+}
+
+pub fn print_purchase_receipt(ctx: Context<PrintPurchaseReceiptInstruction>) -> Result<()> {
+    let listing_receipt = load_receipt::<ListingReceipt>(&ctx.accounts.listing_receipt)?;
+    let bid_receipt = load_receipt::<BidReceipt>(&ctx.accounts.bid_receipt)?;
+    let purchase_receipt_key = ctx.accounts.purchase_receipt.key();
+
+    // every receipt of the sale is stamped with the purchase receipt when the
+    // order is filled, the purchase receipt seeds tie the wallet of the other
+    // side so an unprinted receipt can't stand in for that wallet
+    let stamped_receipts = [
+        listing_receipt
+            .as_ref()
+            .map(|receipt| receipt.purchase_receipt),
+        bid_receipt.as_ref().map(|receipt| receipt.purchase_receipt),
+    ];
+    if stamped_receipts
+        .iter()
+        .flatten()
+        .any(|stamp| *stamp != Some(purchase_receipt_key))
+    {
+        return Err(AuctionHouseV2Errors::ReceiptMismatch.into());
+    }
+
+    let (auction_house, asset_id, merkle_tree, leaf_index, price, purchased_at) =
+        match (&listing_receipt, &bid_receipt) {
+            (Some(receipt), _) => (
+                receipt.auction_house,
+                receipt.asset_id,
+                receipt.merkle_tree,
+                receipt.leaf_index,
+                receipt.price,
+                receipt.purchased_at,
+            ),
+            (None, Some(receipt)) => (
+                receipt.auction_house,
+                receipt.asset_id,
+                receipt.merkle_tree,
+                receipt.leaf_index,
+                receipt.price,
+                receipt.purchased_at,
+            ),
+            (None, None) => return Err(AuctionHouseV2Errors::ReceiptMismatch.into()),
+        };
+
+    let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+    purchase_receipt.auction_house = auction_house;
+    purchase_receipt.seller = listing_receipt
+        .as_ref()
+        .map_or(ctx.accounts.listing_receipt.key(), |receipt| receipt.seller);
+    purchase_receipt.buyer = bid_receipt
+        .as_ref()
+        .map_or(ctx.accounts.bid_receipt.key(), |receipt| receipt.buyer);
+    purchase_receipt.asset_id = asset_id;
+    purchase_receipt.merkle_tree = merkle_tree;
+    purchase_receipt.leaf_index = leaf_index;
+    purchase_receipt.price = price;
+    purchase_receipt.created_at = purchased_at.ok_or(AuctionHouseV2Errors::ReceiptMismatch)?;
+    purchase_receipt.bump = *ctx
+        .bumps
+        .get("purchase_receipt")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::AuctionBidRefunded;
+use crate::settlement::{auction_house_seeds, escrow_seeds};
+use crate::state::{Auction, AuctionHouseV2Data};
+use crate::utils::transfer_from_escrow;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+pub struct RefundAuctionBidInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// CHECK: Wallet for native auction house, bidder token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[AUCTION.as_ref(), auction_house.key().as_ref(), auction.asset_id.as_ref()],
+        has_one=auction_house,
+        bump=auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),auction.key().as_ref()],bump)]
+    pub auction_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn refund_auction_bid(ctx: Context<RefundAuctionBidInstruction>) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_info = auction_house.to_account_info();
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let bidder = &ctx.accounts.bidder.to_account_info();
+    let receipt_account = &ctx.accounts.receipt_account.to_account_info();
+    let auction_escrow = &ctx.accounts.auction_escrow.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let rent = &ctx.accounts.rent;
+    let auction = &ctx.accounts.auction;
+
+    match auction.highest_bidder {
+        Some(highest_bidder) if highest_bidder == bidder.key() => {}
+        Some(_) => return Err(AuctionHouseV2Errors::PublicKeyMismatch.into()),
+        None => return Err(AuctionHouseV2Errors::AuctionHasNoBids.into()),
+    }
+
+    // the seller has the grace period to settle before the winner can walk away
+    let refund_time = auction
+        .end_time
+        .checked_add(AUCTION_SETTLEMENT_GRACE_PERIOD)
+        .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+    if Clock::get()?.unix_timestamp < refund_time {
+        return Err(AuctionHouseV2Errors::SettlementGracePeriodNotOver.into());
+    }
+
+    let auction_key = auction.key();
+    let auction_escrow_bump = ctx
+        .bumps
+        .get("auction_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    // the escrow only ever holds the highest bid, refund it and close the escrow
+    transfer_from_escrow(
+        auction_escrow,
+        &escrow_seeds(auction_house_info.key, &auction_key, auction_escrow_bump),
+        &auction_house_info,
+        &auction_house_seeds(auction_house),
+        bidder,
+        receipt_account,
+        &treasury_mint.key(),
+        system_program,
+        token_program,
+        rent,
+        auction.highest_bid,
+    )?;
+
+    let amount = auction.highest_bid;
+    let auction = &mut ctx.accounts.auction;
+    // without bids the seller can cancel the auction and get the cnft back
+    auction.highest_bidder = None;
+    auction.highest_bid = 0;
+
+    emit!(AuctionBidRefunded {
+        auction: auction_key,
+        auction_house: auction.auction_house,
+        bidder: bidder.key(),
+        asset_id: auction.asset_id,
+        amount,
+    });
+
+    Ok(())
+}
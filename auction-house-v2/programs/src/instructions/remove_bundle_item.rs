@@ -0,0 +1,101 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::BundleItemRemoved;
+use crate::settlement::LeafTransfer;
+use crate::state::Bundle;
+use anchor_lang::prelude::*;
+use mpl_bubblegum::utils::get_asset_id;
+
+#[derive(Accounts)]
+pub struct RemoveBundleItemInstruction<'info> {
+    #[account(
+        mut,
+        seeds=[
+            BUNDLE.as_ref(),
+            seller.key().as_ref(),
+            bundle.auction_house.as_ref(),
+            bundle.bundle_id.to_le_bytes().as_ref()
+        ],
+        has_one=seller,
+        bump=bundle.bump
+    )]
+    pub bundle: Box<Account<'info, Bundle>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the bundle item, mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    // Cnft proofs in the remaining accounts
+}
+
+pub fn remove_bundle_item<'a>(
+    ctx: Context<'_, '_, '_, 'a, RemoveBundleItemInstruction<'a>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+) -> Result<()> {
+    let seller = &ctx.accounts.seller.to_account_info();
+    let merkle_tree = &ctx.accounts.merkle_tree.to_account_info();
+    let asset_id = get_asset_id(merkle_tree.key, nonce);
+    let bundle = &ctx.accounts.bundle;
+
+    let position = bundle
+        .items
+        .iter()
+        .position(|item| item.asset_id == asset_id)
+        .ok_or(AuctionHouseV2Errors::BundleItemNotFound)?;
+    let leaf_index = bundle.items[position].leaf_index;
+
+    // transferring the leaf to its own owner resets the leaf delegate to the owner
+    let leaf_transfer = LeafTransfer {
+        bubblegum_program: &ctx.accounts.bubblegum_program.to_account_info(),
+        tree_config: &ctx.accounts.tree_config.to_account_info(),
+        merkle_tree,
+        log_wrapper: &ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: &ctx.accounts.compression_program.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index: leaf_index,
+    };
+    leaf_transfer.invoke(
+        seller,
+        &ctx.accounts.program_as_signer.to_account_info(),
+        seller,
+        &mut ctx.remaining_accounts.iter(),
+        &[],
+    )?;
+
+    let bundle = &mut ctx.accounts.bundle;
+    bundle.items.remove(position);
+
+    emit!(BundleItemRemoved {
+        bundle: bundle.key(),
+        asset_id,
+    });
+
+    Ok(())
+}
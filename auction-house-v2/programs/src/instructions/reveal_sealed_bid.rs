@@ -0,0 +1,81 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::SealedBidRevealed;
+use crate::state::{SealedAuction, SealedBid};
+use crate::utils::cmp_bytes;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+#[derive(Accounts)]
+pub struct RevealSealedBidInstruction<'info> {
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[SEALED_AUCTION.as_ref(), sealed_auction.auction_house.as_ref(), sealed_auction.asset_id.as_ref()],
+        bump=sealed_auction.bump
+    )]
+    pub sealed_auction: Box<Account<'info, SealedAuction>>,
+
+    #[account(
+        mut,
+        seeds=[SEALED_BID.as_ref(), sealed_auction.key().as_ref(), bidder.key().as_ref()],
+        bump=sealed_bid.bump
+    )]
+    pub sealed_bid: Box<Account<'info, SealedBid>>,
+}
+
+pub fn reveal_sealed_bid(
+    ctx: Context<RevealSealedBidInstruction>,
+    price: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    let sealed_auction = &mut ctx.accounts.sealed_auction;
+    let sealed_bid = &mut ctx.accounts.sealed_bid;
+
+    let now = Clock::get()?.unix_timestamp;
+    if now < sealed_auction.commit_end_time || now >= sealed_auction.reveal_end_time {
+        return Err(AuctionHouseV2Errors::RevealNotActive.into());
+    }
+    if sealed_bid.revealed_price.is_some() {
+        return Err(AuctionHouseV2Errors::BidAlreadyRevealed.into());
+    }
+
+    // binding the bidder and the auction keeps anyone from copying a commitment
+    // and revealing it first with the price and salt of the victim
+    let commitment = keccak::hashv(&[
+        &price.to_le_bytes(),
+        &salt,
+        sealed_bid.bidder.as_ref(),
+        sealed_auction.key().as_ref(),
+    ])
+    .to_bytes();
+    if !cmp_bytes(&commitment, &sealed_bid.commitment, 32) {
+        return Err(AuctionHouseV2Errors::InvalidCommitment.into());
+    }
+    if price > sealed_bid.deposit {
+        return Err(AuctionHouseV2Errors::InsufficientDeposit.into());
+    }
+    if price < sealed_auction.reserve_price {
+        return Err(AuctionHouseV2Errors::BidTooLow.into());
+    }
+    sealed_bid.revealed_price = Some(price);
+
+    // ties go to the bid revealed first
+    if sealed_auction.highest_bidder.is_none() || price > sealed_auction.highest_bid {
+        sealed_auction.second_highest_bid = sealed_auction.highest_bid;
+        sealed_auction.highest_bid = price;
+        sealed_auction.highest_bidder = Some(sealed_bid.bidder);
+    } else if price > sealed_auction.second_highest_bid {
+        sealed_auction.second_highest_bid = price;
+    }
+
+    emit!(SealedBidRevealed {
+        sealed_bid: sealed_bid.key(),
+        auction: sealed_auction.key(),
+        bidder: sealed_bid.bidder,
+        price,
+    });
+
+    Ok(())
+}
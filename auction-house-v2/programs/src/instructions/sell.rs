@@ -1,7 +1,8 @@
 use crate::constants::*;
 use crate::errors::AuctionHouseV2Errors;
-use crate::state::AuctionHouseV2Data;
-use crate::ID as PROGRAM_ID;
+use crate::events::Listed;
+use crate::state::{AuctionHouseV2Data, TradeSide, TradeState};
+use crate::utils::assert_valid_expiry;
 use anchor_lang::prelude::*;
 use anchor_lang::{
     accounts::{account::Account, program::Program, unchecked_account::UncheckedAccount},
@@ -9,7 +10,7 @@ use anchor_lang::{
 };
 use anchor_spl::token::Mint;
 use mpl_bubblegum::instructions::DelegateCpiBuilder;
-use mpl_utils::create_or_allocate_account_raw;
+use mpl_bubblegum::utils::get_asset_id;
 #[derive(Accounts)]
 #[instruction(seller_price:u64)]
 pub struct SellInstruction<'info> {
@@ -34,9 +35,10 @@ pub struct SellInstruction<'info> {
     /// CHECK: Verified in CPI
     pub previous_leaf_delegate: UncheckedAccount<'info>,
 
-    /// CHECK: Account seeds checked in constraints
     #[account(
-        mut,
+        init,
+        payer=owner,
+        space=TRADE_STATE_SIZE,
         seeds=[
             TRADE_STATE.as_ref(),
             owner.key().as_ref(),
@@ -46,7 +48,7 @@ pub struct SellInstruction<'info> {
         ],
         bump
     )]
-    pub seller_trade_state: UncheckedAccount<'info>,
+    pub seller_trade_state: Box<Account<'info, TradeState>>,
 
     /// CHECK: Verified in CPI
     pub asset_id: UncheckedAccount<'info>,
@@ -80,13 +82,14 @@ pub fn sell<'b, 'a>(
     creator_hash: [u8; 32],
     nonce: u64,
     index: u32,
+    expiry: Option<i64>,
+    allowed_buyer: Option<Pubkey>,
 ) -> Result<()> {
     let auction_house = &ctx.accounts.auction_house.to_account_info();
     let asset_id = &ctx.accounts.asset_id.to_account_info();
     let merkle_tree = &ctx.accounts.merkle_tree.to_account_info();
     let owner = &ctx.accounts.owner.to_account_info();
     let previous_leaf_delegate = &ctx.accounts.previous_leaf_delegate.to_account_info();
-    let seller_trade_state_info = &ctx.accounts.seller_trade_state.to_account_info();
     let program_as_signer = &ctx.accounts.program_as_signer.to_account_info();
     let compression_program = &ctx.accounts.compression_program.to_account_info();
     let system_program = &ctx.accounts.system_program.to_account_info();
@@ -94,11 +97,18 @@ pub fn sell<'b, 'a>(
     let log_wrapper = &ctx.accounts.log_wrapper.to_account_info();
     let bubblegum_program = &ctx.accounts.bubblegum_program.to_account_info();
     let remaining_accounts = ctx.remaining_accounts;
-    let seller_trade_state_bump = ctx
+    let seller_trade_state_bump = *ctx
         .bumps
         .get("seller_trade_state")
         .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
 
+    if get_asset_id(merkle_tree.key, nonce) != asset_id.key() {
+        return Err(AuctionHouseV2Errors::AssetIdMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    assert_valid_expiry(expiry, now)?;
+
     let mut builder = DelegateCpiBuilder::new(&bubblegum_program);
     builder
         .leaf_owner(&owner)
@@ -119,27 +129,30 @@ pub fn sell<'b, 'a>(
     }
     builder.invoke()?;
 
-    if seller_trade_state_info.data_is_empty() {
-        let seller_trade_state_seeds = [
-            TRADE_STATE.as_ref(),
-            owner.key.as_ref(),
-            auction_house.key.as_ref(),
-            asset_id.key.as_ref(),
-            &seller_price.to_le_bytes(),
-            &[*seller_trade_state_bump],
-        ];
-        create_or_allocate_account_raw(
-            PROGRAM_ID,
-            &seller_trade_state_info,
-            &system_program,
-            &owner,
-            TRADE_STATE_SIZE,
-            &seller_trade_state_seeds,
-        )?;
-    }
-
-    let data = &mut seller_trade_state_info.data.borrow_mut();
-    data[0] = *seller_trade_state_bump;
+    let seller_trade_state = &mut ctx.accounts.seller_trade_state;
+    seller_trade_state.wallet = owner.key();
+    seller_trade_state.auction_house = auction_house.key();
+    seller_trade_state.asset_id = asset_id.key();
+    seller_trade_state.merkle_tree = merkle_tree.key();
+    seller_trade_state.leaf_index = index;
+    seller_trade_state.price = seller_price;
+    seller_trade_state.side = TradeSide::Ask;
+    seller_trade_state.created_at = now;
+    seller_trade_state.bump = seller_trade_state_bump;
+    seller_trade_state.expiry = expiry;
+    seller_trade_state.allowed_buyer = allowed_buyer;
+
+    emit!(Listed {
+        trade_state: seller_trade_state.key(),
+        auction_house: seller_trade_state.auction_house,
+        seller: seller_trade_state.wallet,
+        asset_id: seller_trade_state.asset_id,
+        merkle_tree: seller_trade_state.merkle_tree,
+        leaf_index: index,
+        price: seller_price,
+        expiry,
+        allowed_buyer,
+    });
 
     Ok(())
 }
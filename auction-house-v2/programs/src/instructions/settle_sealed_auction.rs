@@ -0,0 +1,247 @@
+use crate::events::SaleExecuted;
+use crate::settlement::{
+    auction_house_seeds, escrow_seeds, program_as_signer_seeds, LeafTransfer, PaymentSource,
+    SaleAccounts,
+};
+use crate::state::{AuctionHouseV2Data, SealedAuction, SealedBid};
+use crate::utils::{hash_creators, hash_metadata, transfer_from_escrow};
+use crate::MetadataArgs;
+use crate::{constants::*, errors::AuctionHouseV2Errors, utils::cmp_bytes};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+pub struct SettleSealedAuctionInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouseV2Data>>,
+
+    /// CHECK: verified in auction_house seeds constraints
+    pub auction_house_authority: AccountInfo<'info>,
+
+    pub treasury_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[TREASURY.as_bytes(),auction_house.key().as_ref()],bump)]
+    pub treasury_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: verified in auction constraints
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+
+    /// CHECK: verified in the logic
+    #[account(mut)]
+    pub seller_receipt_account: AccountInfo<'info>,
+
+    /// CHECK: verified in auction constraints, mutated in downstream program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[SEALED_AUCTION.as_ref(), auction_house.key().as_ref(), sealed_auction.asset_id.as_ref()],
+        has_one=auction_house,
+        has_one=seller,
+        has_one=merkle_tree,
+        close=seller,
+        bump=sealed_auction.bump
+    )]
+    pub sealed_auction: Box<Account<'info, SealedAuction>>,
+
+    /// CHECK: verified against the auction highest bidder
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+
+    /// CHECK: Wallet for native auction house, buyer token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub buyer_receipt_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[SEALED_BID.as_ref(), sealed_auction.key().as_ref(), buyer.key().as_ref()],
+        close=buyer,
+        bump=sealed_bid.bump
+    )]
+    pub sealed_bid: Box<Account<'info, SealedBid>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),sealed_bid.key().as_ref()],bump)]
+    pub bid_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(seeds=[PROGRAM.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in CPI
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Verified in CPI
+    pub log_wrapper: UncheckedAccount<'info>,
+    /* Remaining Accounts
+       - ...Creator Accounts
+       - ...Cnft proofs in the remaining accounts
+    */
+}
+
+pub fn settle_sealed_auction<'a>(
+    ctx: Context<'_, '_, '_, 'a, SettleSealedAuctionInstruction<'a>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    metadata: MetadataArgs,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let merkle_tree_info = &ctx.accounts.merkle_tree.to_account_info();
+    let seller_info = &ctx.accounts.seller.to_account_info();
+    let buyer_info = &ctx.accounts.buyer.to_account_info();
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let auction = &ctx.accounts.sealed_auction;
+    let buyer_receipt_info = &ctx.accounts.buyer_receipt_account.to_account_info();
+    let sealed_bid = &ctx.accounts.sealed_bid;
+    let bid_escrow = &ctx.accounts.bid_escrow.to_account_info();
+    let program_as_signer_info = &ctx.accounts.program_as_signer.to_account_info();
+    let compression_program_info = &ctx.accounts.compression_program.to_account_info();
+    let system_program_info = &ctx.accounts.system_program.to_account_info();
+    let tree_config_info = &ctx.accounts.tree_config.to_account_info();
+    let log_wrapper_info = &ctx.accounts.log_wrapper.to_account_info();
+    let auction_house_info = &ctx.accounts.auction_house.to_account_info();
+    let bubblegum_program_info = &ctx.accounts.bubblegum_program.to_account_info();
+    let token_program_info = &ctx.accounts.token_program.to_account_info();
+    let remaining_accounts = ctx.remaining_accounts;
+
+    let hashed_metadata = hash_metadata(&metadata)?;
+    if !cmp_bytes(&data_hash, &hashed_metadata, 32) {
+        return Err(AuctionHouseV2Errors::MetadataHashMismatch.into());
+    }
+    if !cmp_bytes(&creator_hash, &hash_creators(&metadata.creators), 32) {
+        return Err(AuctionHouseV2Errors::CreatorHashMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if now < auction.reveal_end_time {
+        return Err(AuctionHouseV2Errors::AuctionNotEnded.into());
+    }
+
+    match auction.highest_bidder {
+        Some(highest_bidder) if highest_bidder == buyer_info.key() => {}
+        Some(_) => return Err(AuctionHouseV2Errors::PublicKeyMismatch.into()),
+        None => return Err(AuctionHouseV2Errors::AuctionHasNoBids.into()),
+    }
+
+    let sealed_bid_key = sealed_bid.key();
+    let bid_escrow_bump = ctx
+        .bumps
+        .get("bid_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let bid_escrow_seeds = escrow_seeds(auction_house_info.key, &sealed_bid_key, bid_escrow_bump);
+
+    let program_as_signer_bump = ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let program_as_signer_seeds = program_as_signer_seeds(program_as_signer_bump);
+
+    let remaining_accounts_iter = &mut remaining_accounts.iter();
+
+    // native escrow signs for itself, token escrow is owned by the auction house
+    let payment_source = PaymentSource::escrow(bid_escrow, bid_escrow_seeds, auction_house);
+
+    let sale_accounts = SaleAccounts::from_house(
+        auction_house,
+        &ctx.accounts.auction_house_authority,
+        &ctx.accounts.auction_house_fee_account,
+        &ctx.accounts.treasury_mint.to_account_info(),
+        &ctx.accounts.treasury_account,
+        seller_info,
+        &ctx.accounts.seller_receipt_account,
+        buyer_info,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+    )?;
+    let price = auction.clearing_price();
+    let payouts = sale_accounts.pay(
+        &payment_source,
+        price,
+        auction_house.seller_fee_basis_points,
+        auction_house.royalty_policy.royalty_basis_points(
+            auction_house.seller_fee_basis_points,
+            metadata.seller_fee_basis_points,
+            None,
+        ),
+        sale_accounts.is_primary_sale(&metadata),
+        &metadata.creators,
+        remaining_accounts_iter,
+    )?;
+
+    // transfer nft to the highest bidder
+    let leaf_transfer = LeafTransfer {
+        bubblegum_program: bubblegum_program_info,
+        tree_config: tree_config_info,
+        merkle_tree: merkle_tree_info,
+        log_wrapper: log_wrapper_info,
+        compression_program: compression_program_info,
+        system_program: system_program_info,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index: auction.leaf_index,
+    };
+    leaf_transfer.invoke(
+        seller_info,
+        program_as_signer_info,
+        buyer_info,
+        remaining_accounts_iter,
+        &[&program_as_signer_seeds],
+    )?;
+
+    // return what is left of the deposit and close the escrow
+    transfer_from_escrow(
+        bid_escrow,
+        &bid_escrow_seeds,
+        auction_house_info,
+        &auction_house_seeds(auction_house),
+        buyer_info,
+        buyer_receipt_info,
+        &treasury_mint.key(),
+        system_program_info,
+        token_program_info,
+        &Rent::get()?,
+        sealed_bid.deposit.saturating_sub(price),
+    )?;
+
+    emit!(SaleExecuted {
+        auction_house: auction_house.key(),
+        seller: seller_info.key(),
+        buyer: buyer_info.key(),
+        asset_id: auction.asset_id,
+        merkle_tree: merkle_tree_info.key(),
+        leaf_index: auction.leaf_index,
+        price,
+        auction_house_fee: payouts.auction_house_fee,
+        royalty_basis_points: payouts.royalty_basis_points,
+        primary_sale: payouts.primary_sale,
+        creator_royalties: payouts.creator_royalties,
+        seller_proceeds: payouts.seller_proceeds,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use crate::events::TraitOfferPlaced;
+use crate::offers::OfferEscrow;
+use crate::state::{AuctionHouseV2Data, TraitOffer};
+use crate::{constants::*, errors::AuctionHouseV2Errors};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(buyer_price:u64, eligible_assets_root:[u8; 32])]
+pub struct TraitOfferInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// CHECK: Validated in CPI
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),bidder.key().as_ref()],bump)]
+    pub buyer_escrow: UncheckedAccount<'info>,
+
+    #[account(init,payer=bidder,space=TRAIT_OFFER_SIZE,seeds=[
+        TRAIT_OFFER.as_ref(),
+        bidder.key().as_ref(),
+        auction_house.key().as_ref(),
+        eligible_assets_root.as_ref(),
+        buyer_price.to_le_bytes().as_ref()
+        ],bump)]
+    pub trait_offer: Box<Account<'info, TraitOffer>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn trait_offer(
+    ctx: Context<TraitOfferInstruction>,
+    buyer_price: u64,
+    eligible_assets_root: [u8; 32],
+    quantity: u32,
+    expiry: Option<i64>,
+) -> Result<()> {
+    let bidder = &ctx.accounts.bidder.to_account_info();
+    let trait_offer_bump = *ctx
+        .bumps
+        .get("trait_offer")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let buyer_escrow_bump = ctx
+        .bumps
+        .get("buyer_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    let offer_escrow = OfferEscrow {
+        auction_house: &ctx.accounts.auction_house,
+        wallet: bidder,
+        buyer_escrow: &ctx.accounts.buyer_escrow.to_account_info(),
+        buyer_escrow_bump,
+        treasury_mint: &ctx.accounts.treasury_mint.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+        token_program: &ctx.accounts.token_program.to_account_info(),
+        rent: &ctx.accounts.rent,
+    };
+    let now = offer_escrow.place(
+        &ctx.accounts.payment_account.to_account_info(),
+        buyer_price,
+        quantity,
+        expiry,
+    )?;
+
+    let auction_house_key = ctx.accounts.auction_house.key();
+    let trait_offer = &mut ctx.accounts.trait_offer;
+    trait_offer.wallet = bidder.key();
+    trait_offer.auction_house = auction_house_key;
+    trait_offer.eligible_assets_root = eligible_assets_root;
+    trait_offer.price = buyer_price;
+    trait_offer.quantity = quantity;
+    trait_offer.created_at = now;
+    trait_offer.bump = trait_offer_bump;
+    trait_offer.expiry = expiry;
+
+    emit!(TraitOfferPlaced {
+        trait_offer: trait_offer.key(),
+        auction_house: auction_house_key,
+        buyer: bidder.key(),
+        eligible_assets_root,
+        price: buyer_price,
+        quantity,
+        expiry,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::state::{AuctionHouseV2Data, RoyaltyPolicy};
+use crate::utils::create_treasury_withdrawal_account;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token::Token;
+
+#[derive(Accounts)]
+pub struct UpdateAuctionHouseInstruction<'info> {
+    #[account(
+        mut,
+        seeds=[AUCTION_HOUSE.as_ref(),authority.key().as_ref(),treasury_mint.key().as_ref()],
+        has_one=authority,
+        has_one=treasury_mint,
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Verified in auction house seeds
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: User can use whatever they want for updating.
+    #[account(mut)]
+    pub treasury_withdrawal_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: User can use whatever they want for updating.
+    pub treasury_withdrawal_owner: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: User can use whatever they want for updating.
+    pub fee_withdrawal_account: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn update_auction_house(
+    ctx: Context<UpdateAuctionHouseInstruction>,
+    seller_fee_basis_points: Option<u16>,
+    requires_sign_off: Option<bool>,
+    royalty_policy: Option<RoyaltyPolicy>,
+) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let payer = &ctx.accounts.payer;
+    let system_program = &ctx.accounts.system_program;
+    let token_program = &ctx.accounts.token_program;
+    let associated_token_program = &ctx.accounts.associated_token_program;
+
+    if let Some(seller_fee_basis_points) = seller_fee_basis_points {
+        if seller_fee_basis_points > 10000 {
+            return Err(AuctionHouseV2Errors::InvalidSellerFeeBasisPoints.into());
+        }
+        auction_house.seller_fee_basis_points = seller_fee_basis_points;
+    }
+
+    if let Some(requires_sign_off) = requires_sign_off {
+        auction_house.requires_sign_off = requires_sign_off;
+    }
+
+    // a new fee has to leave room for the royalty cap as much as a new policy,
+    // legacy auction houses without a cap can still update everything else
+    let updates_royalties = seller_fee_basis_points.is_some() || royalty_policy.is_some();
+    if let Some(royalty_policy) = royalty_policy {
+        auction_house.royalty_policy = royalty_policy;
+    }
+    if updates_royalties
+        && !auction_house
+            .royalty_policy
+            .is_valid(auction_house.seller_fee_basis_points)
+    {
+        return Err(AuctionHouseV2Errors::InvalidRoyaltyPolicy.into());
+    }
+
+    if let Some(treasury_withdrawal_account) = &ctx.accounts.treasury_withdrawal_account {
+        let is_native = treasury_mint.key() == native_mint::id();
+        if !is_native {
+            let treasury_withdrawal_owner = ctx
+                .accounts
+                .treasury_withdrawal_owner
+                .as_ref()
+                .ok_or(AuctionHouseV2Errors::TreasuryWithdrawalOwnerRequired)?;
+            // Recreate treasury withdrawal ATA if the owner changed
+            create_treasury_withdrawal_account(
+                treasury_withdrawal_account,
+                treasury_withdrawal_owner,
+                treasury_mint,
+                payer,
+                system_program,
+                token_program,
+                associated_token_program,
+            )?;
+        }
+        auction_house.treasury_withdrawal_account = treasury_withdrawal_account.key();
+    }
+
+    if let Some(fee_withdrawal_account) = &ctx.accounts.fee_withdrawal_account {
+        auction_house.fee_withdrawal_account = fee_withdrawal_account.key();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::ListingPriceUpdated;
+use crate::state::{AuctionHouseV2Data, ListingReceipt, TradeSide, TradeState};
+use crate::utils::update_receipt;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+#[instruction(seller_price:u64, new_seller_price:u64)]
+pub struct UpdateListingPriceInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in auction house seeds
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Verified in trade state seeds constraints
+    pub asset_id: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            TRADE_STATE.as_ref(),
+            owner.key().as_ref(),
+            auction_house.key().as_ref(),
+            asset_id.key().as_ref(),
+            seller_price.to_le_bytes().as_ref()
+        ],
+        constraint = seller_trade_state.side == TradeSide::Ask @ AuctionHouseV2Errors::InvalidSellerTradeState,
+        close = owner,
+        bump = seller_trade_state.bump
+    )]
+    pub seller_trade_state: Box<Account<'info, TradeState>>,
+
+    #[account(
+        init,
+        payer=owner,
+        space=TRADE_STATE_SIZE,
+        seeds=[
+            TRADE_STATE.as_ref(),
+            owner.key().as_ref(),
+            auction_house.key().as_ref(),
+            asset_id.key().as_ref(),
+            new_seller_price.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub new_seller_trade_state: Box<Account<'info, TradeState>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Receipt of the order, seeds checked in constraints and updated when it was printed
+    #[account(
+        mut,
+        seeds=[
+            LISTING_RECEIPT.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            seller_trade_state.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub listing_receipt: UncheckedAccount<'info>,
+}
+
+pub fn update_listing_price(
+    ctx: Context<UpdateListingPriceInstruction>,
+    _seller_price: u64,
+    new_seller_price: u64,
+) -> Result<()> {
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let new_seller_trade_state_bump = *ctx
+        .bumps
+        .get("new_seller_trade_state")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    if seller_trade_state.is_expired(now) {
+        return Err(AuctionHouseV2Errors::OrderExpired.into());
+    }
+
+    // the leaf stays delegated to the program, only the order moves
+    let new_seller_trade_state = &mut ctx.accounts.new_seller_trade_state;
+    new_seller_trade_state.wallet = seller_trade_state.wallet;
+    new_seller_trade_state.auction_house = seller_trade_state.auction_house;
+    new_seller_trade_state.asset_id = seller_trade_state.asset_id;
+    new_seller_trade_state.merkle_tree = seller_trade_state.merkle_tree;
+    new_seller_trade_state.leaf_index = seller_trade_state.leaf_index;
+    new_seller_trade_state.price = new_seller_price;
+    new_seller_trade_state.side = TradeSide::Ask;
+    new_seller_trade_state.created_at = now;
+    new_seller_trade_state.bump = new_seller_trade_state_bump;
+    new_seller_trade_state.expiry = seller_trade_state.expiry;
+    new_seller_trade_state.allowed_buyer = seller_trade_state.allowed_buyer;
+
+    update_receipt(
+        &ctx.accounts.listing_receipt,
+        |listing_receipt: &mut ListingReceipt| {
+            listing_receipt.canceled_at = Some(now);
+        },
+    )?;
+
+    emit!(ListingPriceUpdated {
+        trade_state: seller_trade_state.key(),
+        new_trade_state: new_seller_trade_state.key(),
+        auction_house: seller_trade_state.auction_house,
+        seller: seller_trade_state.wallet,
+        asset_id: seller_trade_state.asset_id,
+        price: seller_trade_state.price,
+        new_price: new_seller_price,
+    });
+
+    Ok(())
+}
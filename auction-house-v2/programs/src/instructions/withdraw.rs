@@ -0,0 +1,66 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::settlement::{auction_house_seeds, escrow_seeds};
+use crate::state::AuctionHouseV2Data;
+use crate::utils::transfer_from_escrow;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+pub struct WithdrawInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Wallet for native auction house, payment token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),wallet.key().as_ref()],bump)]
+    pub buyer_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn withdraw(ctx: Context<WithdrawInstruction>, amount: u64) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let wallet = &ctx.accounts.wallet.to_account_info();
+    let receipt_account = &ctx.accounts.receipt_account.to_account_info();
+    let buyer_escrow = &ctx.accounts.buyer_escrow.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let rent = &ctx.accounts.rent;
+    let buyer_escrow_bump = ctx
+        .bumps
+        .get("buyer_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    transfer_from_escrow(
+        buyer_escrow,
+        &escrow_seeds(&auction_house.key(), wallet.key, buyer_escrow_bump),
+        &auction_house.to_account_info(),
+        &auction_house_seeds(auction_house),
+        wallet,
+        receipt_account,
+        &treasury_mint.key(),
+        system_program,
+        token_program,
+        rent,
+        amount,
+    )?;
+
+    Ok(())
+}
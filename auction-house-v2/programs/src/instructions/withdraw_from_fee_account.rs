@@ -0,0 +1,77 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::state::AuctionHouseV2Data;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::system_instruction::transfer;
+
+#[derive(Accounts)]
+pub struct WithdrawFromFeeAccountInstruction<'info> {
+    #[account(
+        seeds=[AUCTION_HOUSE.as_ref(),authority.key().as_ref(),treasury_mint.key().as_ref()],
+        has_one=authority,
+        has_one=fee_withdrawal_account,
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Verified in auction house seeds
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[FEE.as_bytes(),auction_house.key().as_ref()],bump=auction_house.fee_account_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in auction house constraints
+    #[account(mut)]
+    pub fee_withdrawal_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn withdraw_from_fee_account(
+    ctx: Context<WithdrawFromFeeAccountInstruction>,
+    amount: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account.to_account_info();
+    let fee_withdrawal_account = &ctx.accounts.fee_withdrawal_account.to_account_info();
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+    let auction_house_key = auction_house.key();
+
+    // keep the fee account rent exempt
+    let withdrawable_funds = auction_house_fee_account
+        .lamports()
+        .checked_sub(rent.minimum_balance(auction_house_fee_account.data_len()))
+        .ok_or(AuctionHouseV2Errors::NotEnoughFunds)?;
+    if withdrawable_funds < amount {
+        return Err(AuctionHouseV2Errors::NotEnoughFunds.into());
+    }
+
+    let auction_house_fee_account_seeds = [
+        FEE.as_bytes(),
+        auction_house_key.as_ref(),
+        &[auction_house.fee_account_bump],
+    ];
+    let withdraw_instruction = transfer(
+        auction_house_fee_account.key,
+        fee_withdrawal_account.key,
+        amount,
+    );
+    invoke_signed(
+        &withdraw_instruction,
+        &[
+            auction_house_fee_account.clone(),
+            fee_withdrawal_account.clone(),
+            system_program.to_account_info(),
+        ],
+        &[&auction_house_fee_account_seeds],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::state::AuctionHouseV2Data;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::system_instruction::transfer;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token::Token;
+
+#[derive(Accounts)]
+pub struct WithdrawFromTreasuryInstruction<'info> {
+    #[account(
+        seeds=[AUCTION_HOUSE.as_ref(),authority.key().as_ref(),treasury_mint.key().as_ref()],
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=treasury_withdrawal_account,
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Verified in auction house seeds
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[TREASURY.as_bytes(),auction_house.key().as_ref()],bump=auction_house.treasury_bump)]
+    pub treasury_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified in auction house constraints
+    #[account(mut)]
+    pub treasury_withdrawal_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn withdraw_from_treasury(
+    ctx: Context<WithdrawFromTreasuryInstruction>,
+    amount: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let authority = &ctx.accounts.authority;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let treasury_account = &ctx.accounts.treasury_account.to_account_info();
+    let treasury_withdrawal_account = &ctx.accounts.treasury_withdrawal_account.to_account_info();
+    let system_program = &ctx.accounts.system_program;
+    let token_program = &ctx.accounts.token_program;
+    let rent = &ctx.accounts.rent;
+
+    let auction_house_key = auction_house.key();
+    let authority_key = authority.key();
+    let treasury_mint_key = treasury_mint.key();
+    let is_native = treasury_mint_key == native_mint::id();
+
+    if is_native {
+        // keep the treasury rent exempt
+        let withdrawable_funds = treasury_account
+            .lamports()
+            .checked_sub(rent.minimum_balance(treasury_account.data_len()))
+            .ok_or(AuctionHouseV2Errors::NotEnoughFunds)?;
+        if withdrawable_funds < amount {
+            return Err(AuctionHouseV2Errors::NotEnoughFunds.into());
+        }
+
+        let treasury_seeds = [
+            TREASURY.as_bytes(),
+            auction_house_key.as_ref(),
+            &[auction_house.treasury_bump],
+        ];
+        let withdraw_instruction = transfer(
+            treasury_account.key,
+            treasury_withdrawal_account.key,
+            amount,
+        );
+        invoke_signed(
+            &withdraw_instruction,
+            &[
+                treasury_account.clone(),
+                treasury_withdrawal_account.clone(),
+                system_program.to_account_info(),
+            ],
+            &[&treasury_seeds],
+        )?;
+    } else {
+        let auction_house_seeds = [
+            AUCTION_HOUSE.as_ref(),
+            authority_key.as_ref(),
+            treasury_mint_key.as_ref(),
+            &[auction_house.bump],
+        ];
+        let withdraw_instruction = spl_token::instruction::transfer(
+            token_program.key,
+            treasury_account.key,
+            treasury_withdrawal_account.key,
+            &auction_house_key,
+            &[],
+            amount,
+        )?;
+        invoke_signed(
+            &withdraw_instruction,
+            &[
+                token_program.to_account_info(),
+                treasury_account.clone(),
+                auction_house.to_account_info(),
+                treasury_withdrawal_account.clone(),
+            ],
+            &[&auction_house_seeds],
+        )?;
+    }
+
+    Ok(())
+}
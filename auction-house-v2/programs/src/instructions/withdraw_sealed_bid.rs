@@ -0,0 +1,118 @@
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::SealedBidWithdrawn;
+use crate::settlement::{auction_house_seeds, escrow_seeds};
+use crate::state::{AuctionHouseV2Data, SealedAuction, SealedBid};
+use crate::utils::transfer_from_escrow;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+pub struct WithdrawSealedBidInstruction<'info> {
+    #[account(seeds=[AUCTION_HOUSE.as_ref(),auction_house_authority.key().as_ref(),treasury_mint.key().as_ref()],bump=auction_house.bump)]
+    pub auction_house: Account<'info, AuctionHouseV2Data>,
+
+    /// CHECK: Verified in the auction house seeds contraints
+    pub auction_house_authority: UncheckedAccount<'info>,
+
+    pub treasury_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// CHECK: Wallet for native auction house, bidder token account otherwise. Verified in the logic
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified against the sealed bid, may already be closed once the auction is settled
+    #[account(mut, address = sealed_bid.auction)]
+    pub sealed_auction: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[SEALED_BID.as_ref(), sealed_auction.key().as_ref(), bidder.key().as_ref()],
+        close=bidder,
+        bump=sealed_bid.bump
+    )]
+    pub sealed_bid: Box<Account<'info, SealedBid>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(mut,seeds=[ESCROW.as_ref(),auction_house.key().as_ref(),sealed_bid.key().as_ref()],bump)]
+    pub bid_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn withdraw_sealed_bid(ctx: Context<WithdrawSealedBidInstruction>) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let bidder = &ctx.accounts.bidder.to_account_info();
+    let receipt_account = &ctx.accounts.receipt_account.to_account_info();
+    let sealed_auction_info = &ctx.accounts.sealed_auction.to_account_info();
+    let sealed_bid = &ctx.accounts.sealed_bid;
+    let bid_escrow = &ctx.accounts.bid_escrow.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let rent = &ctx.accounts.rent;
+
+    let now = Clock::get()?.unix_timestamp;
+    if now < sealed_bid.reveal_end_time {
+        return Err(AuctionHouseV2Errors::AuctionNotEnded.into());
+    }
+
+    // the auction is closed once settled or cancelled, which also closes the
+    // winning bid, so only an open auction can still be owed this deposit
+    if !sealed_auction_info.data_is_empty() && sealed_auction_info.owner == ctx.program_id {
+        let mut sealed_auction = Account::<SealedAuction>::try_from(sealed_auction_info)?;
+        if sealed_auction.reveal_end_time == sealed_bid.reveal_end_time
+            && sealed_auction.highest_bidder == Some(bidder.key())
+        {
+            // the seller has until the settlement deadline to settle, afterwards
+            // the winner can walk away and the seller can cancel the auction
+            let settlement_deadline = sealed_bid
+                .reveal_end_time
+                .checked_add(AUCTION_SETTLEMENT_GRACE_PERIOD)
+                .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+            if now < settlement_deadline {
+                return Err(AuctionHouseV2Errors::WinningBidCannotBeWithdrawn.into());
+            }
+            sealed_auction.highest_bidder = None;
+            sealed_auction.highest_bid = 0;
+            sealed_auction.second_highest_bid = 0;
+            sealed_auction.exit(ctx.program_id)?;
+        }
+    }
+
+    let sealed_bid_key = sealed_bid.key();
+    let bid_escrow_bump = ctx
+        .bumps
+        .get("bid_escrow")
+        .ok_or(AuctionHouseV2Errors::BumpSeedNotInHashMap)?;
+
+    transfer_from_escrow(
+        bid_escrow,
+        &escrow_seeds(&auction_house.key(), &sealed_bid_key, bid_escrow_bump),
+        &auction_house.to_account_info(),
+        &auction_house_seeds(auction_house),
+        bidder,
+        receipt_account,
+        &treasury_mint.key(),
+        system_program,
+        token_program,
+        rent,
+        sealed_bid.deposit,
+    )?;
+
+    emit!(SealedBidWithdrawn {
+        sealed_bid: sealed_bid_key,
+        auction: sealed_bid.auction,
+        bidder: sealed_bid.bidder,
+        deposit: sealed_bid.deposit,
+    });
+
+    Ok(())
+}
@@ -3,8 +3,13 @@ declare_id!("AHV2XGm1jVAZp3NtwdVyHkbskbxk3oMfn73SXBkejUQb");
 use anchor_lang::prelude::*;
 mod constants;
 mod errors;
+pub use errors::*;
+mod events;
+pub use events::*;
 mod instructions;
 use instructions::*;
+mod offers;
+mod settlement;
 mod state;
 pub use state::*;
 mod utils;
@@ -15,8 +20,49 @@ pub mod auction_house_v2 {
         ctx: Context<CreateInstruction>,
         seller_fee_basis_points: u16,
         requires_sign_off: bool,
+        royalty_policy: RoyaltyPolicy,
     ) -> Result<()> {
-        instructions::create(ctx, seller_fee_basis_points, requires_sign_off)
+        instructions::create(
+            ctx,
+            seller_fee_basis_points,
+            requires_sign_off,
+            royalty_policy,
+        )
+    }
+
+    pub fn update_auction_house(
+        ctx: Context<UpdateAuctionHouseInstruction>,
+        seller_fee_basis_points: Option<u16>,
+        requires_sign_off: Option<bool>,
+        royalty_policy: Option<RoyaltyPolicy>,
+    ) -> Result<()> {
+        instructions::update_auction_house(
+            ctx,
+            seller_fee_basis_points,
+            requires_sign_off,
+            royalty_policy,
+        )
+    }
+
+    pub fn withdraw_from_treasury(
+        ctx: Context<WithdrawFromTreasuryInstruction>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_from_treasury(ctx, amount)
+    }
+
+    pub fn deposit_to_fee_account(
+        ctx: Context<DepositToFeeAccountInstruction>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_to_fee_account(ctx, amount)
+    }
+
+    pub fn withdraw_from_fee_account(
+        ctx: Context<WithdrawFromFeeAccountInstruction>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_from_fee_account(ctx, amount)
     }
 
     pub fn sell<'b, 'a>(
@@ -27,6 +73,8 @@ pub mod auction_house_v2 {
         creator_hash: [u8; 32],
         nonce: u64,
         index: u32,
+        expiry: Option<i64>,
+        allowed_buyer: Option<Pubkey>,
     ) -> Result<()> {
         instructions::sell(
             ctx,
@@ -36,37 +84,463 @@ pub mod auction_house_v2 {
             creator_hash,
             nonce,
             index,
+            expiry,
+            allowed_buyer,
         )
     }
 
-    pub fn bid(ctx: Context<BidInstruction>, buyer_price: u64) -> Result<()> {
-        instructions::bid(ctx, buyer_price)
+    pub fn update_listing_price(
+        ctx: Context<UpdateListingPriceInstruction>,
+        seller_price: u64,
+        new_seller_price: u64,
+    ) -> Result<()> {
+        instructions::update_listing_price(ctx, seller_price, new_seller_price)
+    }
+
+    pub fn bid(
+        ctx: Context<BidInstruction>,
+        buyer_price: u64,
+        leaf_index: u32,
+        expiry: Option<i64>,
+    ) -> Result<()> {
+        instructions::bid(ctx, buyer_price, leaf_index, expiry)
+    }
+
+    pub fn deposit(ctx: Context<DepositInstruction>, amount: u64) -> Result<()> {
+        instructions::deposit(ctx, amount)
+    }
+
+    pub fn withdraw(ctx: Context<WithdrawInstruction>, amount: u64) -> Result<()> {
+        instructions::withdraw(ctx, amount)
     }
 
     pub fn execute_sale<'a>(
         ctx: Context<'_, '_, '_, 'a, ExecuteSaleInstruction<'a>>,
         buyer_price: u64,
+        seller_price: u64,
         root: [u8; 32],
         data_hash: [u8; 32],
         creator_hash: [u8; 32],
         nonce: u64,
         index: u32,
-        royalty_basis_points: u16,
         metadata: MetadataArgs,
+        buyer_royalty_basis_points: Option<u16>,
     ) -> Result<()> {
         instructions::execute_sale(
             ctx,
             buyer_price,
+            seller_price,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            metadata,
+            buyer_royalty_basis_points,
+        )
+    }
+
+    pub fn buy<'a>(
+        ctx: Context<'_, '_, '_, 'a, BuyInstruction<'a>>,
+        buyer_price: u64,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+        metadata: MetadataArgs,
+        buyer_royalty_basis_points: Option<u16>,
+    ) -> Result<()> {
+        instructions::buy(
+            ctx,
+            buyer_price,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            metadata,
+            buyer_royalty_basis_points,
+        )
+    }
+
+    pub fn accept_offer<'a>(
+        ctx: Context<'_, '_, '_, 'a, AcceptOfferInstruction<'a>>,
+        buyer_price: u64,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+        metadata: MetadataArgs,
+    ) -> Result<()> {
+        instructions::accept_offer(
+            ctx,
+            buyer_price,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            metadata,
+        )
+    }
+
+    pub fn collection_offer(
+        ctx: Context<CollectionOfferInstruction>,
+        buyer_price: u64,
+        quantity: u32,
+        expiry: Option<i64>,
+    ) -> Result<()> {
+        instructions::collection_offer(ctx, buyer_price, quantity, expiry)
+    }
+
+    pub fn accept_collection_offer<'a>(
+        ctx: Context<'_, '_, '_, 'a, AcceptCollectionOfferInstruction<'a>>,
+        buyer_price: u64,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+        metadata: MetadataArgs,
+    ) -> Result<()> {
+        instructions::accept_collection_offer(
+            ctx,
+            buyer_price,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            metadata,
+        )
+    }
+
+    pub fn cancel_collection_offer(
+        ctx: Context<CancelCollectionOfferInstruction>,
+        buyer_price: u64,
+        refund_escrow: bool,
+    ) -> Result<()> {
+        instructions::cancel_collection_offer(ctx, buyer_price, refund_escrow)
+    }
+
+    pub fn trait_offer(
+        ctx: Context<TraitOfferInstruction>,
+        buyer_price: u64,
+        eligible_assets_root: [u8; 32],
+        quantity: u32,
+        expiry: Option<i64>,
+    ) -> Result<()> {
+        instructions::trait_offer(ctx, buyer_price, eligible_assets_root, quantity, expiry)
+    }
+
+    pub fn accept_trait_offer<'a>(
+        ctx: Context<'_, '_, '_, 'a, AcceptTraitOfferInstruction<'a>>,
+        buyer_price: u64,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+        metadata: MetadataArgs,
+        eligibility_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::accept_trait_offer(
+            ctx,
+            buyer_price,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            metadata,
+            eligibility_proof,
+        )
+    }
+
+    pub fn cancel_trait_offer(
+        ctx: Context<CancelTraitOfferInstruction>,
+        buyer_price: u64,
+        refund_escrow: bool,
+    ) -> Result<()> {
+        instructions::cancel_trait_offer(ctx, buyer_price, refund_escrow)
+    }
+
+    pub fn create_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, CreateAuctionInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+        start_time: i64,
+        end_time: i64,
+        reserve_price: u64,
+        min_bid_increment: u64,
+        extension_window: Option<i64>,
+    ) -> Result<()> {
+        instructions::create_auction(
+            ctx,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            start_time,
+            end_time,
+            reserve_price,
+            min_bid_increment,
+            extension_window,
+        )
+    }
+
+    pub fn place_auction_bid(ctx: Context<PlaceAuctionBidInstruction>, amount: u64) -> Result<()> {
+        instructions::place_auction_bid(ctx, amount)
+    }
+
+    pub fn refund_auction_bid(ctx: Context<RefundAuctionBidInstruction>) -> Result<()> {
+        instructions::refund_auction_bid(ctx)
+    }
+
+    pub fn settle_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, SettleAuctionInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        metadata: MetadataArgs,
+    ) -> Result<()> {
+        instructions::settle_auction(ctx, root, data_hash, creator_hash, nonce, metadata)
+    }
+
+    pub fn cancel_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, CancelAuctionInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::cancel_auction(ctx, root, data_hash, creator_hash, nonce)
+    }
+
+    pub fn create_dutch_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, CreateDutchAuctionInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+        start_time: i64,
+        end_time: i64,
+        start_price: u64,
+        end_price: u64,
+        curve: PriceCurve,
+    ) -> Result<()> {
+        instructions::create_dutch_auction(
+            ctx,
             root,
             data_hash,
             creator_hash,
             nonce,
             index,
-            royalty_basis_points,
+            start_time,
+            end_time,
+            start_price,
+            end_price,
+            curve,
+        )
+    }
+
+    pub fn buy_dutch_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, BuyDutchAuctionInstruction<'a>>,
+        max_price: u64,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        metadata: MetadataArgs,
+    ) -> Result<()> {
+        instructions::buy_dutch_auction(
+            ctx,
+            max_price,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
             metadata,
         )
     }
 
+    pub fn cancel_dutch_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, CancelDutchAuctionInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::cancel_dutch_auction(ctx, root, data_hash, creator_hash, nonce)
+    }
+
+    pub fn create_sealed_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, CreateSealedAuctionInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+        commit_end_time: i64,
+        reveal_end_time: i64,
+        reserve_price: u64,
+        second_price: bool,
+    ) -> Result<()> {
+        instructions::create_sealed_auction(
+            ctx,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            commit_end_time,
+            reveal_end_time,
+            reserve_price,
+            second_price,
+        )
+    }
+
+    pub fn commit_sealed_bid(
+        ctx: Context<CommitSealedBidInstruction>,
+        commitment: [u8; 32],
+        deposit: u64,
+    ) -> Result<()> {
+        instructions::commit_sealed_bid(ctx, commitment, deposit)
+    }
+
+    pub fn reveal_sealed_bid(
+        ctx: Context<RevealSealedBidInstruction>,
+        price: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_sealed_bid(ctx, price, salt)
+    }
+
+    pub fn withdraw_sealed_bid(ctx: Context<WithdrawSealedBidInstruction>) -> Result<()> {
+        instructions::withdraw_sealed_bid(ctx)
+    }
+
+    pub fn settle_sealed_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, SettleSealedAuctionInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        metadata: MetadataArgs,
+    ) -> Result<()> {
+        instructions::settle_sealed_auction(ctx, root, data_hash, creator_hash, nonce, metadata)
+    }
+
+    pub fn cancel_sealed_auction<'a>(
+        ctx: Context<'_, '_, '_, 'a, CancelSealedAuctionInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::cancel_sealed_auction(ctx, root, data_hash, creator_hash, nonce)
+    }
+
+    pub fn create_bundle(
+        ctx: Context<CreateBundleInstruction>,
+        bundle_id: u64,
+        price: u64,
+        item_count: u8,
+    ) -> Result<()> {
+        instructions::create_bundle(ctx, bundle_id, price, item_count)
+    }
+
+    pub fn add_bundle_item<'a>(
+        ctx: Context<'_, '_, '_, 'a, AddBundleItemInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+    ) -> Result<()> {
+        instructions::add_bundle_item(ctx, root, data_hash, creator_hash, nonce, index)
+    }
+
+    pub fn remove_bundle_item<'a>(
+        ctx: Context<'_, '_, '_, 'a, RemoveBundleItemInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::remove_bundle_item(ctx, root, data_hash, creator_hash, nonce)
+    }
+
+    pub fn cancel_bundle(ctx: Context<CancelBundleInstruction>) -> Result<()> {
+        instructions::cancel_bundle(ctx)
+    }
+
+    pub fn buy_bundle<'a>(
+        ctx: Context<'_, '_, '_, 'a, BuyBundleInstruction<'a>>,
+        items: Vec<BundleItemArgs>,
+    ) -> Result<()> {
+        instructions::buy_bundle(ctx, items)
+    }
+
+    pub fn create_swap_order<'a>(
+        ctx: Context<'_, '_, '_, 'a, CreateSwapOrderInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+        requested: SwapTarget,
+        maker_payment: u64,
+        taker_payment: u64,
+        expiry: Option<i64>,
+    ) -> Result<()> {
+        instructions::create_swap_order(
+            ctx,
+            root,
+            data_hash,
+            creator_hash,
+            nonce,
+            index,
+            requested,
+            maker_payment,
+            taker_payment,
+            expiry,
+        )
+    }
+
+    pub fn accept_swap_order<'a>(
+        ctx: Context<'_, '_, '_, 'a, AcceptSwapOrderInstruction<'a>>,
+        maker_leaf: SwapLeafArgs,
+        maker_metadata: Option<MetadataArgs>,
+        maker_proof_length: u8,
+        taker_leaf: SwapLeafArgs,
+        taker_metadata: Option<MetadataArgs>,
+    ) -> Result<()> {
+        instructions::accept_swap_order(
+            ctx,
+            maker_leaf,
+            maker_metadata,
+            maker_proof_length,
+            taker_leaf,
+            taker_metadata,
+        )
+    }
+
+    pub fn cancel_swap_order<'a>(
+        ctx: Context<'_, '_, '_, 'a, CancelSwapOrderInstruction<'a>>,
+        maker_leaf: Option<SwapLeafArgs>,
+    ) -> Result<()> {
+        instructions::cancel_swap_order(ctx, maker_leaf)
+    }
+
     pub fn cancel<'a>(
         ctx: Context<'_, '_, '_, 'a, CancelInstruction<'a>>,
         seller_price: u64,
@@ -86,4 +560,38 @@ pub mod auction_house_v2 {
             index,
         )
     }
+
+    pub fn cancel_bid(
+        ctx: Context<CancelBidInstruction>,
+        buyer_price: u64,
+        refund_escrow: bool,
+    ) -> Result<()> {
+        instructions::cancel_bid(ctx, buyer_price, refund_escrow)
+    }
+
+    pub fn close_expired_listing<'a>(
+        ctx: Context<'_, '_, '_, 'a, CloseExpiredListingInstruction<'a>>,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::close_expired_listing(ctx, root, data_hash, creator_hash, nonce)
+    }
+
+    pub fn close_expired_bid(ctx: Context<CloseExpiredBidInstruction>) -> Result<()> {
+        instructions::close_expired_bid(ctx)
+    }
+
+    pub fn print_listing_receipt(ctx: Context<PrintListingReceiptInstruction>) -> Result<()> {
+        instructions::print_listing_receipt(ctx)
+    }
+
+    pub fn print_bid_receipt(ctx: Context<PrintBidReceiptInstruction>) -> Result<()> {
+        instructions::print_bid_receipt(ctx)
+    }
+
+    pub fn print_purchase_receipt(ctx: Context<PrintPurchaseReceiptInstruction>) -> Result<()> {
+        instructions::print_purchase_receipt(ctx)
+    }
 }
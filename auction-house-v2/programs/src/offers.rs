@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::utils::get_asset_id;
+use std::slice::Iter;
+
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::SaleExecuted;
+use crate::settlement::{
+    auction_house_seeds, escrow_seeds, LeafTransfer, PaymentSource, SaleAccounts,
+};
+use crate::state::{AuctionHouseV2Data, CollectionOffer, TraitOffer};
+use crate::utils::{
+    assert_valid_expiry, cmp_bytes, escrow_balance, hash_creators, hash_metadata,
+    transfer_from_escrow, transfer_to_escrow,
+};
+use crate::MetadataArgs;
+
+/// Offer to buy up to a quantity of assets out of a set at one price each.
+///
+/// Collection and trait offers only differ in the assets they can be filled
+/// with, so they are placed, filled and cancelled by the same routines.
+pub trait MultiAssetOffer {
+    /// Number of assets left to fill
+    fn quantity(&self) -> u32;
+
+    fn set_quantity(&mut self, quantity: u32);
+
+    fn expiry(&self) -> Option<i64>;
+
+    fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expiry(), Some(expiry) if now >= expiry)
+    }
+}
+
+impl MultiAssetOffer for CollectionOffer {
+    fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    fn set_quantity(&mut self, quantity: u32) {
+        self.quantity = quantity;
+    }
+
+    fn expiry(&self) -> Option<i64> {
+        self.expiry
+    }
+}
+
+impl MultiAssetOffer for TraitOffer {
+    fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    fn set_quantity(&mut self, quantity: u32) {
+        self.quantity = quantity;
+    }
+
+    fn expiry(&self) -> Option<i64> {
+        self.expiry
+    }
+}
+
+/// Escrow of the wallet placing an offer, holding the price of every asset
+/// left to fill.
+pub struct OfferEscrow<'a, 'info> {
+    pub auction_house: &'a Account<'info, AuctionHouseV2Data>,
+    pub wallet: &'a AccountInfo<'info>,
+    pub buyer_escrow: &'a AccountInfo<'info>,
+    pub buyer_escrow_bump: &'a u8,
+    pub treasury_mint: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub rent: &'a Rent,
+}
+
+impl<'a, 'info> OfferEscrow<'a, 'info> {
+    fn is_native(&self) -> bool {
+        self.treasury_mint.key() == spl_token::native_mint::ID
+    }
+
+    /// Checks the terms of a new offer and tops up the escrow so it covers
+    /// `quantity` assets at `price`, returning the creation time of the offer.
+    pub fn place(
+        &self,
+        payment_account: &AccountInfo<'info>,
+        price: u64,
+        quantity: u32,
+        expiry: Option<i64>,
+    ) -> Result<i64> {
+        if quantity == 0 {
+            return Err(AuctionHouseV2Errors::InvalidQuantity.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        assert_valid_expiry(expiry, now)?;
+
+        // escrow has to cover every asset the offer can be filled with
+        let total_price = price
+            .checked_mul(quantity.into())
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+        let escrow_funds = escrow_balance(self.buyer_escrow, self.is_native(), self.rent)?;
+        if escrow_funds < total_price {
+            let required_funds = total_price
+                .checked_sub(escrow_funds)
+                .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+            let auction_house_info = self.auction_house.to_account_info();
+            transfer_to_escrow(
+                self.wallet,
+                payment_account,
+                self.buyer_escrow,
+                &escrow_seeds(
+                    auction_house_info.key,
+                    self.wallet.key,
+                    self.buyer_escrow_bump,
+                ),
+                &auction_house_info,
+                self.treasury_mint,
+                self.system_program,
+                self.token_program,
+                self.rent,
+                required_funds,
+            )?;
+        }
+
+        Ok(now)
+    }
+
+    /// Refunds the price of the assets left to fill of a cancelled offer,
+    /// the escrow may also back other orders of the wallet.
+    pub fn refund(
+        &self,
+        receipt_account: &AccountInfo<'info>,
+        price: u64,
+        quantity: u32,
+    ) -> Result<()> {
+        let unfilled_amount = price
+            .checked_mul(quantity.into())
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+        let refund_amount =
+            escrow_balance(self.buyer_escrow, self.is_native(), self.rent)?.min(unfilled_amount);
+        if refund_amount > 0 {
+            let auction_house_info = self.auction_house.to_account_info();
+            transfer_from_escrow(
+                self.buyer_escrow,
+                &escrow_seeds(
+                    auction_house_info.key,
+                    self.wallet.key,
+                    self.buyer_escrow_bump,
+                ),
+                &auction_house_info,
+                &auction_house_seeds(self.auction_house),
+                self.wallet,
+                receipt_account,
+                self.treasury_mint.key,
+                self.system_program,
+                self.token_program,
+                self.rent,
+                refund_amount,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Sale of one asset to the wallet of an offer, paid from its escrow.
+pub struct OfferFill<'a, 'info> {
+    pub auction_house: &'a Account<'info, AuctionHouseV2Data>,
+    pub buyer: &'a AccountInfo<'info>,
+    pub buyer_escrow: &'a AccountInfo<'info>,
+    pub buyer_escrow_bump: &'a u8,
+    pub sale_accounts: SaleAccounts<'a, 'info>,
+    pub previous_leaf_delegate: &'a AccountInfo<'info>,
+    pub leaf_transfer: LeafTransfer<'a, 'info>,
+}
+
+impl<'a, 'info> OfferFill<'a, 'info> {
+    /// Sells the leaf described by `metadata` to the wallet of `offer` once
+    /// `check_eligibility` accepted its asset id, and closes the offer when
+    /// its last asset is filled.
+    pub fn fill<T>(
+        self,
+        offer: &mut Account<'info, T>,
+        price: u64,
+        metadata: &MetadataArgs,
+        check_eligibility: impl FnOnce(&T, &Pubkey) -> Result<()>,
+        remaining_accounts: &mut Iter<AccountInfo<'info>>,
+    ) -> Result<()>
+    where
+        T: MultiAssetOffer + AccountSerialize + AccountDeserialize + Owner + Clone,
+    {
+        let leaf_transfer = &self.leaf_transfer;
+        let hashed_metadata = hash_metadata(metadata)?;
+        if !cmp_bytes(&leaf_transfer.data_hash, &hashed_metadata, 32) {
+            return Err(AuctionHouseV2Errors::MetadataHashMismatch.into());
+        }
+        if !cmp_bytes(
+            &leaf_transfer.creator_hash,
+            &hash_creators(&metadata.creators),
+            32,
+        ) {
+            return Err(AuctionHouseV2Errors::CreatorHashMismatch.into());
+        }
+
+        // the data hash and the asset id are checked against the leaf in the
+        // transfer cpi, so the asset is eligible as the metadata describes it
+        let asset_id = get_asset_id(leaf_transfer.merkle_tree.key, leaf_transfer.nonce);
+        check_eligibility(offer, &asset_id)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        if offer.is_expired(now) {
+            return Err(AuctionHouseV2Errors::OrderExpired.into());
+        }
+
+        let sale_accounts = &self.sale_accounts;
+        if escrow_balance(self.buyer_escrow, sale_accounts.is_native(), &Rent::get()?)? < price {
+            return Err(AuctionHouseV2Errors::NotEnoughFunds.into());
+        }
+
+        // native escrow signs for itself, token escrow is owned by the auction house
+        let auction_house_info = self.auction_house.to_account_info();
+        let payment_source = PaymentSource::escrow(
+            self.buyer_escrow,
+            escrow_seeds(
+                auction_house_info.key,
+                self.buyer.key,
+                self.buyer_escrow_bump,
+            ),
+            self.auction_house,
+        );
+        let payouts = sale_accounts.pay(
+            &payment_source,
+            price,
+            self.auction_house.seller_fee_basis_points,
+            self.auction_house.royalty_policy.royalty_basis_points(
+                self.auction_house.seller_fee_basis_points,
+                metadata.seller_fee_basis_points,
+                None,
+            ),
+            sale_accounts.is_primary_sale(metadata),
+            &metadata.creators,
+            remaining_accounts,
+        )?;
+
+        // seller signs as the leaf owner so the leaf moves without delegating it first
+        leaf_transfer.invoke(
+            &sale_accounts.seller,
+            self.previous_leaf_delegate,
+            self.buyer,
+            remaining_accounts,
+            &[],
+        )?;
+
+        // offer stays open until every asset has been filled
+        let quantity = offer
+            .quantity()
+            .checked_sub(1)
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+        offer.set_quantity(quantity);
+        if quantity == 0 {
+            offer.close(self.buyer.clone())?;
+        }
+
+        emit!(SaleExecuted {
+            auction_house: auction_house_info.key(),
+            seller: sale_accounts.seller.key(),
+            buyer: self.buyer.key(),
+            asset_id,
+            merkle_tree: leaf_transfer.merkle_tree.key(),
+            leaf_index: leaf_transfer.index,
+            price,
+            auction_house_fee: payouts.auction_house_fee,
+            royalty_basis_points: payouts.royalty_basis_points,
+            primary_sale: payouts.primary_sale,
+            creator_royalties: payouts.creator_royalties,
+            seller_proceeds: payouts.seller_proceeds,
+        });
+
+        Ok(())
+    }
+}
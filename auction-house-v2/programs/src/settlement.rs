@@ -0,0 +1,423 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction::transfer};
+use mpl_bubblegum::instructions::TransferCpiBuilder;
+use spl_associated_token_account::instruction::create_associated_token_account;
+use std::slice::{self, Iter};
+
+use crate::constants::*;
+use crate::errors::AuctionHouseV2Errors;
+use crate::events::CreatorRoyalty;
+use crate::state::AuctionHouseV2Data;
+use crate::utils::{check_if_ata_valid, get_fee_payer};
+use crate::{Creator, MetadataArgs};
+
+/// Signer seeds of the auction house pda.
+pub fn auction_house_seeds(auction_house: &AuctionHouseV2Data) -> [&[u8]; 4] {
+    [
+        AUCTION_HOUSE.as_bytes(),
+        auction_house.authority.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        slice::from_ref(&auction_house.bump),
+    ]
+}
+
+/// Signer seeds of an escrow pda holding the funds of `owner`, which is the
+/// buyer wallet for bids and the order account for auctions and swaps.
+pub fn escrow_seeds<'s>(
+    auction_house: &'s Pubkey,
+    owner: &'s Pubkey,
+    bump: &'s u8,
+) -> [&'s [u8]; 4] {
+    [
+        ESCROW.as_bytes(),
+        auction_house.as_ref(),
+        owner.as_ref(),
+        slice::from_ref(bump),
+    ]
+}
+
+/// Signer seeds of the program delegate of listed leaves.
+pub fn program_as_signer_seeds(bump: &u8) -> [&[u8]; 3] {
+    [PROGRAM.as_bytes(), SIGNER.as_bytes(), slice::from_ref(bump)]
+}
+
+/// Account the buyer funds are taken from when settling a sale.
+///
+/// For an escrow this is the escrow pda signing for native transfers or
+/// the auction house signing for token transfers, for direct purchases it is
+/// the buyer wallet or its payment token account with the buyer as signer.
+pub struct PaymentSource<'a, 'info> {
+    pub account: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub signer_seeds: Option<[&'a [u8]; 4]>,
+}
+
+impl<'a, 'info> PaymentSource<'a, 'info> {
+    /// Pays from the wallet of a buyer signing the transaction, native
+    /// payments have to come straight from the wallet.
+    pub fn wallet(
+        payment_account: &AccountInfo<'info>,
+        buyer: &AccountInfo<'info>,
+        auction_house: &AuctionHouseV2Data,
+    ) -> Result<Self> {
+        if auction_house.treasury_mint == spl_token::native_mint::id()
+            && payment_account.key != buyer.key
+        {
+            return Err(AuctionHouseV2Errors::PublicKeyMismatch.into());
+        }
+        Ok(PaymentSource {
+            account: payment_account.clone(),
+            authority: buyer.clone(),
+            signer_seeds: None,
+        })
+    }
+
+    /// Pays from an escrow of the auction house, a native escrow signs for
+    /// itself and a token escrow is owned by the auction house.
+    pub fn escrow(
+        escrow: &AccountInfo<'info>,
+        escrow_seeds: [&'a [u8]; 4],
+        auction_house: &'a Account<'info, AuctionHouseV2Data>,
+    ) -> Self {
+        if auction_house.treasury_mint == spl_token::native_mint::id() {
+            PaymentSource {
+                account: escrow.clone(),
+                authority: escrow.clone(),
+                signer_seeds: Some(escrow_seeds),
+            }
+        } else {
+            PaymentSource {
+                account: escrow.clone(),
+                authority: auction_house.to_account_info(),
+                signer_seeds: Some(auction_house_seeds(auction_house)),
+            }
+        }
+    }
+}
+
+/// Accounts used to pay the auction house, creators and seller of a sale.
+pub struct SaleAccounts<'a, 'info> {
+    pub treasury_mint: AccountInfo<'info>,
+    pub treasury_account: AccountInfo<'info>,
+    pub seller: AccountInfo<'info>,
+    pub seller_receipt_account: AccountInfo<'info>,
+    pub fee_payer: AccountInfo<'info>,
+    pub fee_payer_seeds: Option<[&'a [u8]; 3]>,
+    pub system_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+}
+
+/// Amounts paid out by a settled sale.
+pub struct SalePayouts {
+    pub auction_house_fee: u64,
+    pub royalty_basis_points: u16,
+    pub primary_sale: bool,
+    pub creator_royalties: Vec<CreatorRoyalty>,
+    pub seller_proceeds: u64,
+}
+
+impl<'a, 'info> SaleAccounts<'a, 'info> {
+    /// Collects the accounts of a sale on `auction_house`.
+    ///
+    /// Token accounts of the seller and creators are paid for by the fee
+    /// account when the authority signs, otherwise by the seller or the buyer.
+    pub fn from_house(
+        auction_house: &'a Account<'info, AuctionHouseV2Data>,
+        auction_house_authority: &AccountInfo<'info>,
+        auction_house_fee_account: &AccountInfo<'info>,
+        treasury_mint: &AccountInfo<'info>,
+        treasury_account: &AccountInfo<'info>,
+        seller: &AccountInfo<'info>,
+        seller_receipt_account: &AccountInfo<'info>,
+        buyer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        associated_token_program: &AccountInfo<'info>,
+    ) -> Result<Self> {
+        let auction_house_key: &'info Pubkey = auction_house.to_account_info().key;
+        let fee_account_seeds = [
+            FEE.as_bytes(),
+            auction_house_key.as_ref(),
+            slice::from_ref(&auction_house.fee_account_bump),
+        ];
+        let (fee_payer, fee_payer_seeds) = get_fee_payer(
+            Box::new(auction_house.clone()),
+            auction_house_fee_account.clone(),
+            &fee_account_seeds,
+            auction_house_authority.clone(),
+            seller.clone(),
+            buyer.clone(),
+        )?;
+        let fee_account_signs = !fee_payer_seeds.is_empty();
+
+        Ok(SaleAccounts {
+            treasury_mint: treasury_mint.clone(),
+            treasury_account: treasury_account.clone(),
+            seller: seller.clone(),
+            seller_receipt_account: seller_receipt_account.clone(),
+            fee_payer,
+            fee_payer_seeds: fee_account_signs.then_some(fee_account_seeds),
+            system_program: system_program.clone(),
+            token_program: token_program.clone(),
+            associated_token_program: associated_token_program.clone(),
+        })
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.treasury_mint.key() == spl_token::native_mint::id()
+    }
+
+    /// Only a verified creator selling a leaf whose primary sale has not
+    /// happened makes a primary sale, anyone else is reselling the leaf.
+    pub fn is_primary_sale(&self, metadata: &MetadataArgs) -> bool {
+        !metadata.primary_sale_happened
+            && metadata
+                .creators
+                .iter()
+                .any(|creator| creator.verified && creator.address == self.seller.key())
+    }
+
+    fn transfer(
+        &self,
+        source: &PaymentSource<'_, 'info>,
+        destination: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let signer_seeds = source.signer_seeds.as_ref().map(|seeds| [&seeds[..]]);
+        let signer_seeds: &[&[&[u8]]] = match &signer_seeds {
+            Some(signer_seeds) => signer_seeds,
+            None => &[],
+        };
+        if self.is_native() {
+            invoke_signed(
+                &transfer(source.account.key, destination.key, amount),
+                &[
+                    source.account.clone(),
+                    destination.clone(),
+                    self.system_program.clone(),
+                ],
+                signer_seeds,
+            )?;
+        } else {
+            invoke_signed(
+                &spl_token::instruction::transfer(
+                    self.token_program.key,
+                    source.account.key,
+                    destination.key,
+                    source.authority.key,
+                    &[],
+                    amount,
+                )?,
+                &[
+                    self.token_program.clone(),
+                    source.account.clone(),
+                    source.authority.clone(),
+                    destination.clone(),
+                ],
+                signer_seeds,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn create_ata_if_needed(
+        &self,
+        wallet: &AccountInfo<'info>,
+        token_account: &AccountInfo<'info>,
+    ) -> Result<()> {
+        if token_account.data_is_empty() {
+            let create_ata_instruction = create_associated_token_account(
+                self.fee_payer.key,
+                wallet.key,
+                self.treasury_mint.key,
+                self.token_program.key,
+            );
+            let fee_payer_seeds = self.fee_payer_seeds.as_ref().map(|seeds| [&seeds[..]]);
+            let fee_signer_seeds: &[&[&[u8]]] = match &fee_payer_seeds {
+                Some(fee_payer_seeds) => fee_payer_seeds,
+                None => &[],
+            };
+            invoke_signed(
+                &create_ata_instruction,
+                &[
+                    self.fee_payer.clone(),
+                    wallet.clone(),
+                    self.treasury_mint.clone(),
+                    self.token_program.clone(),
+                    token_account.clone(),
+                    self.associated_token_program.clone(),
+                    self.system_program.clone(),
+                ],
+                fee_signer_seeds,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Pays the auction house fee, the creator royalties and the seller out of
+    /// `price`.
+    ///
+    /// On a primary sale the creators split everything left after the auction
+    /// house fee by their shares instead of a royalty, the seller only keeps
+    /// what rounding leaves over.
+    ///
+    /// Creator accounts are read from `remaining_accounts` and have to match
+    /// the creator addresses, followed by their token accounts for non native
+    /// auction houses.
+    pub fn pay(
+        &self,
+        source: &PaymentSource<'_, 'info>,
+        price: u64,
+        seller_fee_basis_points: u16,
+        royalty_basis_points: u16,
+        primary_sale: bool,
+        creators: &[Creator],
+        remaining_accounts: &mut Iter<AccountInfo<'info>>,
+    ) -> Result<SalePayouts> {
+        let is_native = self.is_native();
+
+        // pay auction house fees
+        let auction_house_fee = price
+            .checked_mul(seller_fee_basis_points.into())
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?
+            .checked_div(10000)
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+        self.transfer(source, &self.treasury_account, auction_house_fee)?;
+
+        // pay creator royalties
+        let mut seller_proceeds = price
+            .checked_sub(auction_house_fee)
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+        let total_royalties = if primary_sale {
+            seller_proceeds
+        } else {
+            price
+                .checked_mul(royalty_basis_points.into())
+                .ok_or(AuctionHouseV2Errors::NumericOverflow)?
+                .checked_div(10000)
+                .ok_or(AuctionHouseV2Errors::NumericOverflow)?
+        };
+
+        let mut creator_royalties = Vec::with_capacity(creators.len());
+        for creator in creators.iter() {
+            let share = total_royalties
+                .checked_mul(creator.share.into())
+                .ok_or(AuctionHouseV2Errors::NumericOverflow)?
+                .checked_div(100)
+                .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+            seller_proceeds = seller_proceeds
+                .checked_sub(share)
+                .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+            creator_royalties.push(CreatorRoyalty {
+                creator: creator.address,
+                amount: share,
+            });
+
+            let creator_info = next_account_info(remaining_accounts)?;
+            if creator_info.key() != creator.address {
+                return Err(AuctionHouseV2Errors::CreatorMismatch.into());
+            }
+            if is_native {
+                if share > 0 {
+                    self.transfer(source, creator_info, share)?;
+                }
+            } else {
+                let creator_token_account = next_account_info(remaining_accounts)?;
+                if share > 0 {
+                    // create current creator token account if not initialised
+                    self.create_ata_if_needed(creator_info, creator_token_account)?;
+                    check_if_ata_valid(
+                        creator_token_account,
+                        creator_info.key,
+                        self.treasury_mint.key,
+                    )?;
+
+                    // transfer royalty tokens to creator token account
+                    self.transfer(source, creator_token_account, share)?;
+                }
+            }
+        }
+
+        // transfer funds to seller
+        if is_native {
+            self.transfer(source, &self.seller, seller_proceeds)?;
+        } else {
+            // create seller receipt ATA
+            self.create_ata_if_needed(&self.seller, &self.seller_receipt_account)?;
+
+            let loaded_seller_token_account = check_if_ata_valid(
+                &self.seller_receipt_account,
+                self.seller.key,
+                self.treasury_mint.key,
+            )?;
+
+            // check if seller token account have a delegate
+            if loaded_seller_token_account.delegate.is_some() {
+                return Err(AuctionHouseV2Errors::SellerTokenAccountCannotHaveDelegate.into());
+            }
+
+            self.transfer(source, &self.seller_receipt_account, seller_proceeds)?;
+        }
+
+        Ok(SalePayouts {
+            auction_house_fee,
+            royalty_basis_points,
+            primary_sale,
+            creator_royalties,
+            seller_proceeds,
+        })
+    }
+}
+
+/// Bubblegum accounts and leaf arguments needed to transfer a compressed nft.
+pub struct LeafTransfer<'a, 'info> {
+    pub bubblegum_program: &'a AccountInfo<'info>,
+    pub tree_config: &'a AccountInfo<'info>,
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub log_wrapper: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+impl<'a, 'info> LeafTransfer<'a, 'info> {
+    /// Transfers the leaf to `new_leaf_owner`.
+    ///
+    /// The leaf delegate authorizes the transfer when it signs with
+    /// `signer_seeds`, otherwise the leaf owner has to sign the transaction.
+    pub fn invoke(
+        &self,
+        leaf_owner: &AccountInfo<'info>,
+        leaf_delegate: &AccountInfo<'info>,
+        new_leaf_owner: &AccountInfo<'info>,
+        proof: &mut Iter<AccountInfo<'info>>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let delegate_signs = !signer_seeds.is_empty();
+        let mut builder = TransferCpiBuilder::new(self.bubblegum_program);
+        builder
+            .leaf_owner(leaf_owner, !delegate_signs)
+            .leaf_delegate(leaf_delegate, delegate_signs)
+            .new_leaf_owner(new_leaf_owner)
+            .tree_config(self.tree_config)
+            .merkle_tree(self.merkle_tree)
+            .log_wrapper(self.log_wrapper)
+            .compression_program(self.compression_program)
+            .system_program(self.system_program)
+            .root(self.root)
+            .data_hash(self.data_hash)
+            .creator_hash(self.creator_hash)
+            .nonce(self.nonce)
+            .index(self.index);
+        for info in proof {
+            builder.add_remaining_account(info, false, false);
+        }
+        builder.invoke_signed(signer_seeds)?;
+        Ok(())
+    }
+}
@@ -13,6 +13,384 @@ pub struct AuctionHouseV2Data {
     pub bump: u8,
     pub treasury_bump: u8,
     pub fee_account_bump: u8,
+    pub royalty_policy: RoyaltyPolicy,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum RoyaltyMode {
+    /// Creators get the royalty of the metadata
+    Enforced,
+    /// Buyer chooses the royalty, the policy minimum is the floor
+    Optional,
+    /// Royalty of the metadata is a floor the buyer can add to
+    CreatorMinimum,
+}
+
+/// Bounds the royalty paid to creators on every sale of an auction house.
+/// Auction houses created before the policy decode it as `Enforced` with both
+/// bounds at 0, a `max_basis_points` of 0 leaves the royalty uncapped.
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RoyaltyPolicy {
+    pub mode: RoyaltyMode,
+    pub min_basis_points: u16,
+    pub max_basis_points: u16,
+}
+
+impl RoyaltyPolicy {
+    /// Checks the bounds against the fee of the auction house so that the fee
+    /// and the highest royalty never take more than the sale price
+    pub fn is_valid(&self, seller_fee_basis_points: u16) -> bool {
+        self.min_basis_points <= self.max_basis_points()
+            && u32::from(seller_fee_basis_points) + u32::from(self.max_basis_points()) <= 10000
+    }
+
+    pub fn max_basis_points(&self) -> u16 {
+        if self.max_basis_points == 0 {
+            10000
+        } else {
+            self.max_basis_points
+        }
+    }
+
+    /// Royalty paid for a leaf with `seller_fee_basis_points` on an auction
+    /// house taking `fee_basis_points`, the royalty chosen by the buyer is only
+    /// used by the modes that let the buyer choose. The royalty never exceeds
+    /// what the fee leaves of the sale price, so uncapped legacy policies
+    /// still settle.
+    pub fn royalty_basis_points(
+        &self,
+        fee_basis_points: u16,
+        seller_fee_basis_points: u16,
+        buyer_basis_points: Option<u16>,
+    ) -> u16 {
+        let max_basis_points = self
+            .max_basis_points()
+            .min(10000u16.saturating_sub(fee_basis_points));
+        let creator_basis_points = seller_fee_basis_points
+            .max(self.min_basis_points)
+            .min(max_basis_points);
+        match self.mode {
+            RoyaltyMode::Enforced => creator_basis_points,
+            RoyaltyMode::Optional => buyer_basis_points
+                .unwrap_or(creator_basis_points)
+                .max(self.min_basis_points)
+                .min(max_basis_points),
+            RoyaltyMode::CreatorMinimum => buyer_basis_points
+                .unwrap_or(creator_basis_points)
+                .max(creator_basis_points)
+                .min(max_basis_points),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TradeSide {
+    Bid,
+    Ask,
+}
+
+#[account]
+pub struct TradeState {
+    pub wallet: Pubkey,
+    pub auction_house: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub price: u64,
+    pub side: TradeSide,
+    pub created_at: i64,
+    pub bump: u8,
+    /// Unix timestamp after which the order can no longer be executed
+    pub expiry: Option<i64>,
+    /// Only wallet that can buy a private listing
+    pub allowed_buyer: Option<Pubkey>,
+}
+
+impl TradeState {
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expiry, Some(expiry) if now >= expiry)
+    }
+
+    pub fn accepts_buyer(&self, buyer: &Pubkey) -> bool {
+        !matches!(self.allowed_buyer, Some(allowed_buyer) if allowed_buyer != *buyer)
+    }
+}
+
+/// Offer to buy up to `quantity` assets of a verified collection at `price` each
+#[account]
+pub struct CollectionOffer {
+    pub wallet: Pubkey,
+    pub auction_house: Pubkey,
+    pub collection: Pubkey,
+    pub price: u64,
+    /// Number of assets left to fill
+    pub quantity: u32,
+    pub created_at: i64,
+    pub bump: u8,
+    /// Unix timestamp after which the offer can no longer be filled
+    pub expiry: Option<i64>,
+}
+
+/// Offer to buy up to `quantity` assets out of a curated set at `price` each
+#[account]
+pub struct TraitOffer {
+    pub wallet: Pubkey,
+    pub auction_house: Pubkey,
+    /// Merkle root of the keccak hashes of the eligible asset ids
+    pub eligible_assets_root: [u8; 32],
+    pub price: u64,
+    /// Number of assets left to fill
+    pub quantity: u32,
+    pub created_at: i64,
+    pub bump: u8,
+    /// Unix timestamp after which the offer can no longer be filled
+    pub expiry: Option<i64>,
+}
+
+/// Timed english auction of a single leaf, bids are held in the auction escrow
+#[account]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub auction_house: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Minimum amount of the first bid
+    pub reserve_price: u64,
+    /// Minimum amount every bid has to raise the highest bid by
+    pub min_bid_increment: u64,
+    /// Bids placed within this many seconds of the end push the end back to
+    /// this many seconds after the bid
+    pub extension_window: Option<i64>,
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+    pub bump: u8,
+}
+
+impl Auction {
+    pub fn is_active(&self, now: i64) -> bool {
+        now >= self.start_time && now < self.end_time
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PriceCurve {
+    /// Price drops continuously from the start price to the end price
+    Linear,
+    /// Price drops along the linear curve once every `step_duration` seconds
+    Stepped { step_duration: i64 },
+}
+
+/// Listing whose price declines from `start_price` to `end_price` over time
+#[account]
+pub struct DutchAuction {
+    pub seller: Pubkey,
+    pub auction_house: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub start_price: u64,
+    /// Floor price the listing stays at once the auction has ended
+    pub end_price: u64,
+    pub curve: PriceCurve,
+    pub bump: u8,
+}
+
+impl DutchAuction {
+    /// Price of the listing at `now`, creation guarantees the start price is
+    /// at least the end price and the end time is after the start time.
+    pub fn current_price(&self, now: i64) -> u64 {
+        if now <= self.start_time {
+            return self.start_price;
+        }
+        if now >= self.end_time {
+            return self.end_price;
+        }
+        let elapsed = match self.curve {
+            PriceCurve::Linear => now - self.start_time,
+            PriceCurve::Stepped { step_duration } => {
+                (now - self.start_time) / step_duration * step_duration
+            }
+        };
+        let duration = self.end_time - self.start_time;
+        let price_drop =
+            (self.start_price - self.end_price) as u128 * elapsed as u128 / duration as u128;
+        self.start_price - price_drop as u64
+    }
+}
+
+/// Commit-reveal auction of a single leaf, bids are hidden until the commit
+/// phase closes and deposits are held in an escrow per sealed bid
+#[account]
+pub struct SealedAuction {
+    pub seller: Pubkey,
+    pub auction_house: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub commit_end_time: i64,
+    pub reveal_end_time: i64,
+    pub reserve_price: u64,
+    /// Winner pays the second highest revealed price instead of its own
+    pub second_price: bool,
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+    pub second_highest_bid: u64,
+    pub bump: u8,
+}
+
+impl SealedAuction {
+    /// Price the highest bidder pays, never below the reserve price.
+    pub fn clearing_price(&self) -> u64 {
+        if self.second_price {
+            self.second_highest_bid.max(self.reserve_price)
+        } else {
+            self.highest_bid
+        }
+    }
+}
+
+#[account]
+pub struct SealedBid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    /// keccak hash of the little endian price followed by the salt, the bidder
+    /// and the sealed auction
+    pub commitment: [u8; 32],
+    pub deposit: u64,
+    pub revealed_price: Option<u64>,
+    /// Reveal end of the auction the bid was committed to
+    pub reveal_end_time: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone)]
+pub struct BundleItem {
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+}
+
+/// Listing of several leaves sold together for a single price. Items are
+/// delegated one per transaction until the bundle holds `item_count` of them.
+#[account]
+pub struct Bundle {
+    pub seller: Pubkey,
+    pub auction_house: Pubkey,
+    pub bundle_id: u64,
+    pub price: u64,
+    pub item_count: u8,
+    pub items: Vec<BundleItem>,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Bundle {
+    pub fn is_complete(&self) -> bool {
+        self.items.len() == self.item_count as usize
+    }
+}
+
+/// Leaf data needed to settle one item of a bundle
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone)]
+pub struct BundleItemArgs {
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub metadata: MetadataArgs,
+    /// Number of proof accounts of this item in the remaining accounts
+    pub proof_length: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SwapTarget {
+    /// Only this asset is accepted in exchange
+    Asset { asset_id: Pubkey },
+    /// Any asset of this verified collection is accepted in exchange
+    Collection { collection: Pubkey },
+}
+
+/// Order to exchange a delegated leaf for another one, `maker_payment` is held
+/// in an escrow per order while `taker_payment` is paid on acceptance
+#[account]
+pub struct SwapOrder {
+    pub maker: Pubkey,
+    pub auction_house: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub requested: SwapTarget,
+    pub maker_payment: u64,
+    pub taker_payment: u64,
+    pub created_at: i64,
+    pub expiry: Option<i64>,
+    pub bump: u8,
+}
+
+impl SwapOrder {
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expiry, Some(expiry) if now >= expiry)
+    }
+}
+
+/// Leaf data needed to transfer one side of a swap
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone)]
+pub struct SwapLeafArgs {
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+#[account]
+pub struct ListingReceipt {
+    pub trade_state: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub price: u64,
+    pub created_at: i64,
+    pub canceled_at: Option<i64>,
+    pub purchased_at: Option<i64>,
+    pub purchase_receipt: Option<Pubkey>,
+    pub bump: u8,
+}
+
+#[account]
+pub struct BidReceipt {
+    pub trade_state: Pubkey,
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub price: u64,
+    pub created_at: i64,
+    pub canceled_at: Option<i64>,
+    pub purchased_at: Option<i64>,
+    pub purchase_receipt: Option<Pubkey>,
+    pub bump: u8,
+}
+
+#[account]
+pub struct PurchaseReceipt {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub price: u64,
+    pub created_at: i64,
+    pub bump: u8,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone)]
@@ -1,17 +1,18 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_memory::sol_memcmp;
 use anchor_lang::solana_program::{
     keccak,
-    program::invoke_signed,
+    program::{invoke, invoke_signed},
     program_pack::{IsInitialized, Pack},
+    system_instruction,
 };
-use anchor_lang::{solana_program::program_memory::sol_memcmp, system_program};
 use anchor_spl::token::spl_token::instruction::initialize_account3;
 use anchor_spl::token_interface::spl_token_2022::cmp_pubkeys;
 use mpl_utils::create_or_allocate_account_raw;
+use spl_associated_token_account::instruction::create_associated_token_account;
 
-use crate::constants::TRADE_STATE;
 use crate::errors::AuctionHouseV2Errors;
-use crate::{AuctionHouseV2Data, MetadataArgs, ID};
+use crate::{AuctionHouseV2Data, Creator, MetadataArgs};
 
 pub fn cmp_bytes(a: &[u8], b: &[u8], size: usize) -> bool {
     sol_memcmp(a, b, size) == 0
@@ -34,17 +35,6 @@ pub fn check_if_ata_valid(
     Ok(loaded_token_account)
 }
 
-pub fn close<'info>(info: AccountInfo<'info>, sol_destination: AccountInfo<'info>) -> Result<()> {
-    // Transfer tokens from the account to the sol_destination.
-    let dest_starting_lamports = sol_destination.lamports();
-    **sol_destination.lamports.borrow_mut() =
-        dest_starting_lamports.checked_add(info.lamports()).unwrap();
-    **info.lamports.borrow_mut() = 0;
-
-    info.assign(&system_program::ID);
-    info.realloc(0, false).map_err(Into::into)
-}
-
 pub fn create_program_associated_token_account<'info>(
     account: &AccountInfo<'info>,
     payer: &AccountInfo<'info>,
@@ -82,22 +72,229 @@ pub fn create_program_associated_token_account<'info>(
     Ok(())
 }
 
-pub fn assert_valid_trade_state(
-    trade_state: &Pubkey,
-    wallet: &Pubkey,
-    auction_house: &Pubkey,
-    asset_id: &Pubkey,
-    price: [u8; 8],
-) -> bool {
-    let seeds = [
-        TRADE_STATE.as_ref(),
-        wallet.as_ref(),
-        auction_house.as_ref(),
-        asset_id.as_ref(),
-        price.as_ref(),
-    ];
-    let (derived_trade_state_key, _bump) = Pubkey::find_program_address(&seeds, &ID);
-    return cmp_pubkeys(&derived_trade_state_key, trade_state);
+/// Creates the associated token account of `treasury_withdrawal_owner` for the
+/// treasury mint if it doesn't exist yet, and validates it.
+pub fn create_treasury_withdrawal_account<'info>(
+    treasury_withdrawal_account: &AccountInfo<'info>,
+    treasury_withdrawal_owner: &AccountInfo<'info>,
+    treasury_mint: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    associated_token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if treasury_withdrawal_account.data_is_empty() {
+        let create_ata_instruction = create_associated_token_account(
+            payer.key,
+            treasury_withdrawal_owner.key,
+            treasury_mint.key,
+            token_program.key,
+        );
+        invoke(
+            &create_ata_instruction,
+            &[
+                payer.clone(),
+                treasury_withdrawal_owner.clone(),
+                treasury_withdrawal_account.clone(),
+                treasury_mint.clone(),
+                token_program.clone(),
+                system_program.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+        check_if_ata_valid(
+            treasury_withdrawal_account,
+            treasury_withdrawal_owner.key,
+            treasury_mint.key,
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns the funds held by the buyer escrow that can be spent on orders.
+///
+/// The rent reserve of a native escrow is not counted.
+pub fn escrow_balance(buyer_escrow: &AccountInfo, is_native: bool, rent: &Rent) -> Result<u64> {
+    if is_native {
+        return Ok(buyer_escrow
+            .lamports()
+            .saturating_sub(rent.minimum_balance(buyer_escrow.data_len())));
+    }
+    if buyer_escrow.data_is_empty() {
+        return Ok(0);
+    }
+    let escrow_data = spl_token::state::Account::unpack(&buyer_escrow.data.borrow())?;
+    if !escrow_data.is_initialized() {
+        return Err(AuctionHouseV2Errors::AccountNotInitialized.into());
+    }
+    Ok(escrow_data.amount)
+}
+
+/// Transfers `amount` from the buyer into the buyer escrow.
+///
+/// Native escrows are topped up with the rent reserve when needed, token
+/// escrows are created as a token account owned by the auction house.
+pub fn transfer_to_escrow<'info>(
+    wallet: &AccountInfo<'info>,
+    payment_account: &AccountInfo<'info>,
+    buyer_escrow: &AccountInfo<'info>,
+    escrow_signer_seeds: &[&[u8]],
+    auction_house: &AccountInfo<'info>,
+    treasury_mint: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    rent: &Rent,
+    amount: u64,
+) -> Result<()> {
+    let is_native = cmp_pubkeys(treasury_mint.key, &spl_token::native_mint::ID);
+    if is_native {
+        let required_funds = rent
+            .minimum_balance(buyer_escrow.data_len())
+            .saturating_sub(buyer_escrow.lamports())
+            .checked_add(amount)
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+
+        let total_buyer_funds = wallet
+            .lamports()
+            .checked_sub(rent.minimum_balance(wallet.data_len()))
+            .ok_or(AuctionHouseV2Errors::NumericOverflow)?;
+
+        if total_buyer_funds < required_funds {
+            return Err(AuctionHouseV2Errors::NotEnoughFunds.into());
+        }
+
+        let transfer_instruction_accounts =
+            [wallet.clone(), buyer_escrow.clone(), system_program.clone()];
+        let transfer_instruction =
+            system_instruction::transfer(wallet.key, buyer_escrow.key, required_funds);
+        invoke(&transfer_instruction, &transfer_instruction_accounts)?;
+    } else {
+        // create token account for the escrow
+        if buyer_escrow.data_is_empty() {
+            create_program_associated_token_account(
+                buyer_escrow,
+                wallet,
+                auction_house.clone(),
+                treasury_mint.clone(),
+                system_program,
+                token_program.clone(),
+                escrow_signer_seeds,
+            )?;
+        }
+
+        // transfer the required amount to escrow
+        let transfer_instruction_accounts = [
+            wallet.clone(),
+            buyer_escrow.clone(),
+            token_program.clone(),
+            payment_account.clone(),
+            system_program.clone(),
+        ];
+        let transfer_instruction = spl_token::instruction::transfer(
+            token_program.key,
+            payment_account.key,
+            buyer_escrow.key,
+            wallet.key,
+            &[wallet.key],
+            amount,
+        )?;
+        invoke(&transfer_instruction, &transfer_instruction_accounts)?;
+    }
+    Ok(())
+}
+
+/// Transfers `amount` from the buyer escrow back to the buyer.
+///
+/// Native escrows are signed by the escrow pda and send lamports to the wallet,
+/// token escrows are signed by the auction house and send tokens to the
+/// `receipt_account`. The escrow is closed once it has been emptied.
+pub fn transfer_from_escrow<'info>(
+    buyer_escrow: &AccountInfo<'info>,
+    escrow_signer_seeds: &[&[u8]],
+    auction_house: &AccountInfo<'info>,
+    auction_house_signer_seeds: &[&[u8]],
+    wallet: &AccountInfo<'info>,
+    receipt_account: &AccountInfo<'info>,
+    treasury_mint: &Pubkey,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    rent: &Rent,
+    amount: u64,
+) -> Result<()> {
+    let is_native = cmp_pubkeys(treasury_mint, &spl_token::native_mint::ID);
+    if is_native {
+        if !cmp_pubkeys(receipt_account.key, wallet.key) {
+            return Err(AuctionHouseV2Errors::PublicKeyMismatch.into());
+        }
+        let escrow_lamports = buyer_escrow.lamports();
+        let available_funds =
+            escrow_lamports.saturating_sub(rent.minimum_balance(buyer_escrow.data_len()));
+        if amount > available_funds {
+            return Err(AuctionHouseV2Errors::NotEnoughFunds.into());
+        }
+        // release the rent reserve as well once the escrow is emptied
+        let amount = if amount == available_funds {
+            escrow_lamports
+        } else {
+            amount
+        };
+        invoke_signed(
+            &system_instruction::transfer(buyer_escrow.key, wallet.key, amount),
+            &[buyer_escrow.clone(), wallet.clone(), system_program.clone()],
+            &[escrow_signer_seeds],
+        )?;
+    } else {
+        check_if_ata_valid(receipt_account, wallet.key, treasury_mint)?;
+        let escrow_data = spl_token::state::Account::unpack(&buyer_escrow.data.borrow())?;
+        let remaining_amount = escrow_data
+            .amount
+            .checked_sub(amount)
+            .ok_or(AuctionHouseV2Errors::NotEnoughFunds)?;
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                buyer_escrow.key,
+                receipt_account.key,
+                auction_house.key,
+                &[],
+                amount,
+            )?,
+            &[
+                token_program.clone(),
+                buyer_escrow.clone(),
+                auction_house.clone(),
+                receipt_account.clone(),
+            ],
+            &[auction_house_signer_seeds],
+        )?;
+        if remaining_amount == 0 {
+            invoke_signed(
+                &spl_token::instruction::close_account(
+                    token_program.key,
+                    buyer_escrow.key,
+                    wallet.key,
+                    auction_house.key,
+                    &[],
+                )?,
+                &[
+                    token_program.clone(),
+                    buyer_escrow.clone(),
+                    wallet.clone(),
+                    auction_house.clone(),
+                ],
+                &[auction_house_signer_seeds],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Validates an optional order expiry against the current unix timestamp.
+pub fn assert_valid_expiry(expiry: Option<i64>, now: i64) -> Result<()> {
+    if matches!(expiry, Some(expiry) if expiry <= now) {
+        return Err(AuctionHouseV2Errors::InvalidExpiry.into());
+    }
+    Ok(())
 }
 
 /// Computes the hash of the metadata.
@@ -114,6 +311,46 @@ pub fn hash_metadata(metadata: &MetadataArgs) -> Result<[u8; 32]> {
     .to_bytes())
 }
 
+/// Computes the creator hash of a leaf the same way Bubblegum does, from the
+/// address, verified flag and share of every creator.
+pub fn hash_creators(creators: &[Creator]) -> [u8; 32] {
+    let creator_data = creators
+        .iter()
+        .map(|creator| {
+            [
+                creator.address.as_ref(),
+                &[creator.verified as u8],
+                &[creator.share],
+            ]
+            .concat()
+        })
+        .collect::<Vec<_>>();
+    keccak::hashv(
+        creator_data
+            .iter()
+            .map(|data| data.as_slice())
+            .collect::<Vec<_>>()
+            .as_ref(),
+    )
+    .to_bytes()
+}
+
+/// Verifies that `asset_id` is a leaf of the merkle tree with `root`.
+///
+/// Leaves are the keccak hash of the asset id and pairs of nodes are hashed in
+/// sorted order, so the proof only holds the sibling hashes.
+pub fn verify_asset_inclusion(proof: &[[u8; 32]], root: &[u8; 32], asset_id: &Pubkey) -> bool {
+    let leaf = keccak::hashv(&[asset_id.as_ref()]).to_bytes();
+    let computed_root = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    cmp_bytes(&computed_root, root, 32)
+}
+
 pub fn get_fee_payer<'a, 'b>(
     auction_house: Box<Account<AuctionHouseV2Data>>,
     auction_house_fee_account: AccountInfo<'a>,
@@ -141,3 +378,21 @@ pub fn get_fee_payer<'a, 'b>(
     }
     Ok((payer, seeds))
 }
+
+/// Applies `update` to the receipt of an order when one was printed.
+///
+/// Receipts are passed at their derived address so a printed receipt can't be skipped.
+pub fn update_receipt<'info, T>(
+    receipt: &AccountInfo<'info>,
+    update: impl FnOnce(&mut T),
+) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    if receipt.data_is_empty() {
+        return Ok(());
+    }
+    let mut receipt_account = Account::<T>::try_from(receipt)?;
+    update(&mut receipt_account);
+    receipt_account.exit(&crate::ID)
+}
@@ -0,0 +1,219 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::AcceptOfferInstruction as AcceptOfferAccounts,
+    accounts::BidInstruction as BidAccounts,
+    accounts::PrintBidReceiptInstruction as PrintBidReceiptAccounts,
+    instruction::AcceptOffer as AcceptOfferInstruction, instruction::Bid as BidInstruction,
+    instruction::PrintBidReceipt as PrintBidReceiptInstruction, AuctionHouseV2Errors, BidReceipt,
+    TradeState, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    fetch_program_account, find_auction_house, find_escrow, find_order_receipt, find_trade_state,
+    mint_market_leaf, process_instructions, Leaf, Tree, BID_RECEIPT, PURCHASE_RECEIPT,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+
+struct Offer {
+    authority: Keypair,
+    seller: Keypair,
+    buyer: Keypair,
+    creator: Keypair,
+    tree: Tree,
+    leaf: Leaf,
+    trade_state: Pubkey,
+    bid_receipt: Pubkey,
+}
+
+/// Places a bid of `PRICE` on a leaf paying 5% royalties on a native auction
+/// house taking 5% fees, and prints its receipt
+async fn place_offer(ctx: &mut ProgramTestContext) -> Offer {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [seller, buyer, creator] = create_wallets(ctx, 1_000_000_000).await;
+    let accounts = find_auction_house(&authority.pubkey(), &native_mint::id());
+    let auction_house = accounts.auction_house;
+
+    let (tree, leaf) =
+        mint_market_leaf(ctx, &seller.pubkey(), &seller.pubkey(), &creator, true).await;
+
+    let trade_state = find_trade_state(&buyer.pubkey(), &auction_house, &leaf.asset_id, PRICE);
+    let bid_instruction = Instruction {
+        program_id: ID,
+        accounts: BidAccounts {
+            auction_house,
+            auction_house_authority: authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            bidder: buyer.pubkey(),
+            payment_account: buyer.pubkey(),
+            asset_id: leaf.asset_id,
+            merkle_tree: tree.merkle_tree,
+            buyer_escrow: find_escrow(&auction_house, &buyer.pubkey()),
+            buyer_trade_state: trade_state,
+            auction_house_fee_account: accounts.fee_account,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: BidInstruction {
+            buyer_price: PRICE,
+            leaf_index: leaf.index,
+            expiry: None,
+        }
+        .data(),
+    };
+    process_instructions(ctx, &[bid_instruction], &[&buyer])
+        .await
+        .unwrap();
+
+    let created_at = fetch_program_account::<TradeState>(ctx, trade_state)
+        .await
+        .created_at;
+    let bid_receipt = find_order_receipt(BID_RECEIPT, &trade_state, created_at);
+    let payer = ctx.payer.pubkey();
+    let print_bid_receipt_instruction = Instruction {
+        program_id: ID,
+        accounts: PrintBidReceiptAccounts {
+            buyer_trade_state: trade_state,
+            receipt: bid_receipt,
+            payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: PrintBidReceiptInstruction {}.data(),
+    };
+    process_instructions(ctx, &[print_bid_receipt_instruction], &[])
+        .await
+        .unwrap();
+
+    Offer {
+        authority,
+        seller,
+        buyer,
+        creator,
+        tree,
+        leaf,
+        trade_state,
+        bid_receipt,
+    }
+}
+
+fn accept_offer_instruction(
+    offer: &Offer,
+    metadata: auction_house_v2::MetadataArgs,
+) -> Instruction {
+    let accounts = find_auction_house(&offer.authority.pubkey(), &native_mint::id());
+    let mut account_metas = AcceptOfferAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: offer.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        tree_config: offer.tree.tree_config,
+        seller: offer.seller.pubkey(),
+        seller_receipt_account: offer.seller.pubkey(),
+        merkle_tree: offer.tree.merkle_tree,
+        buyer: offer.buyer.pubkey(),
+        buyer_escrow: find_escrow(&accounts.auction_house, &offer.buyer.pubkey()),
+        buyer_trade_state: offer.trade_state,
+        asset_id: offer.leaf.asset_id,
+        auction_house_fee_account: accounts.fee_account,
+        previous_leaf_delegate: offer.leaf.delegate,
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+        bid_receipt: offer.bid_receipt,
+    }
+    .to_account_metas(None);
+    account_metas.push(AccountMeta::new(offer.creator.pubkey(), false));
+    account_metas.extend(offer.tree.proof(offer.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: AcceptOfferInstruction {
+            buyer_price: PRICE,
+            root: offer.tree.root(),
+            data_hash: offer.leaf.data_hash(),
+            creator_hash: offer.leaf.creator_hash(),
+            nonce: offer.leaf.nonce,
+            index: offer.leaf.index,
+            metadata,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn accept_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let offer = place_offer(&mut ctx).await;
+    let creator_balance = ctx
+        .banks_client
+        .get_balance(offer.creator.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[accept_offer_instruction(&offer, offer.leaf.metadata_args())],
+        &[&offer.seller],
+    )
+    .await
+    .unwrap();
+
+    let trade_state = ctx
+        .banks_client
+        .get_account(offer.trade_state)
+        .await
+        .unwrap();
+    assert!(trade_state.is_none());
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(offer.creator.pubkey())
+            .await
+            .unwrap(),
+        creator_balance + PRICE / 20
+    );
+
+    let receipt = fetch_program_account::<BidReceipt>(&mut ctx, offer.bid_receipt).await;
+    let (purchase_receipt, _) = Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT.as_bytes(),
+            offer.seller.pubkey().as_ref(),
+            offer.bid_receipt.as_ref(),
+        ],
+        &ID,
+    );
+    assert!(receipt.purchased_at.is_some());
+    assert_eq!(receipt.purchase_receipt, Some(purchase_receipt));
+}
+
+#[tokio::test]
+async fn reject_accept_offer_with_altered_royalties() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let offer = place_offer(&mut ctx).await;
+
+    let mut metadata = offer.leaf.metadata_args();
+    metadata.seller_fee_basis_points = 0;
+    let result = process_instructions(
+        &mut ctx,
+        &[accept_offer_instruction(&offer, metadata)],
+        &[&offer.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::MetadataHashMismatch.into());
+}
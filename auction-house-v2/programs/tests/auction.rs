@@ -0,0 +1,578 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::CancelAuctionInstruction as CancelAuctionAccounts,
+    accounts::CreateAuctionInstruction as CreateAuctionAccounts,
+    accounts::PlaceAuctionBidInstruction as PlaceAuctionBidAccounts,
+    accounts::RefundAuctionBidInstruction as RefundAuctionBidAccounts,
+    accounts::SettleAuctionInstruction as SettleAuctionAccounts,
+    instruction::CancelAuction as CancelAuctionInstruction,
+    instruction::CreateAuction as CreateAuctionInstruction,
+    instruction::PlaceAuctionBid as PlaceAuctionBidInstruction,
+    instruction::RefundAuctionBid as RefundAuctionBidInstruction,
+    instruction::SettleAuction as SettleAuctionInstruction, Auction, AuctionHouseV2Errors, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    current_timestamp, fetch_program_account, find_auction_house, find_escrow,
+    find_program_as_signer, mint_market_leaf, process_instructions, warp_to_timestamp, Leaf, Tree,
+    AUCTION, AUCTION_SETTLEMENT_GRACE_PERIOD,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+const MIN_BID_INCREMENT: u64 = PRICE / 10;
+const DURATION: i64 = 60 * 60;
+
+struct Market {
+    authority: Keypair,
+    seller: Keypair,
+    creator: Keypair,
+    tree: Tree,
+    leaf: Leaf,
+}
+
+/// Mints a leaf of the seller paying 5% royalties on a native auction house
+/// taking 5% fees
+async fn create_market(ctx: &mut ProgramTestContext) -> Market {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [seller, creator] = create_wallets(ctx, 1_000_000_000).await;
+    let (tree, leaf) =
+        mint_market_leaf(ctx, &seller.pubkey(), &seller.pubkey(), &creator, true).await;
+    Market {
+        authority,
+        seller,
+        creator,
+        tree,
+        leaf,
+    }
+}
+
+fn find_auction(market: &Market) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    Pubkey::find_program_address(
+        &[
+            AUCTION.as_ref(),
+            auction_house.as_ref(),
+            market.leaf.asset_id.as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn find_auction_escrow(market: &Market) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    find_escrow(&auction_house, &find_auction(market))
+}
+
+fn create_auction_instruction(
+    market: &Market,
+    start_time: i64,
+    min_bid_increment: u64,
+) -> Instruction {
+    let mut account_metas = CreateAuctionAccounts {
+        auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+            .auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        tree_config: market.tree.tree_config,
+        owner: market.seller.pubkey(),
+        merkle_tree: market.tree.merkle_tree,
+        previous_leaf_delegate: market.leaf.delegate,
+        auction: find_auction(market),
+        asset_id: market.leaf.asset_id,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: CreateAuctionInstruction {
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+            index: market.leaf.index,
+            start_time,
+            end_time: start_time + DURATION,
+            reserve_price: PRICE,
+            min_bid_increment,
+            extension_window: None,
+        }
+        .data(),
+    }
+}
+
+/// Starts an auction of the market leaf ending in `DURATION` seconds,
+/// returning its end time
+async fn start_auction(ctx: &mut ProgramTestContext, market: &mut Market) -> i64 {
+    let start_time = current_timestamp(ctx).await;
+    process_instructions(
+        ctx,
+        &[create_auction_instruction(
+            market,
+            start_time,
+            MIN_BID_INCREMENT,
+        )],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+    market
+        .tree
+        .delegate(&mut market.leaf, &find_program_as_signer());
+    start_time + DURATION
+}
+
+fn place_auction_bid_instruction(
+    market: &Market,
+    bidder: &Pubkey,
+    previous_bidder: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: PlaceAuctionBidAccounts {
+            auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+                .auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            bidder: *bidder,
+            payment_account: *bidder,
+            auction: find_auction(market),
+            auction_escrow: find_auction_escrow(market),
+            previous_bidder: *previous_bidder,
+            previous_bidder_receipt_account: *previous_bidder,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: PlaceAuctionBidInstruction { amount }.data(),
+    }
+}
+
+fn settle_auction_instruction(market: &Market, buyer: &Pubkey) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let mut account_metas = SettleAuctionAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        tree_config: market.tree.tree_config,
+        seller: market.seller.pubkey(),
+        seller_receipt_account: market.seller.pubkey(),
+        merkle_tree: market.tree.merkle_tree,
+        auction: find_auction(market),
+        auction_escrow: find_auction_escrow(market),
+        buyer: *buyer,
+        buyer_receipt_account: *buyer,
+        auction_house_fee_account: accounts.fee_account,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.push(AccountMeta::new(market.creator.pubkey(), false));
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: SettleAuctionInstruction {
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+            metadata: market.leaf.metadata_args(),
+        }
+        .data(),
+    }
+}
+
+fn refund_auction_bid_instruction(market: &Market, bidder: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: RefundAuctionBidAccounts {
+            auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+                .auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            bidder: *bidder,
+            receipt_account: *bidder,
+            auction: find_auction(market),
+            auction_escrow: find_auction_escrow(market),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: RefundAuctionBidInstruction {}.data(),
+    }
+}
+
+fn cancel_auction_instruction(market: &Market) -> Instruction {
+    let mut account_metas = CancelAuctionAccounts {
+        auction: find_auction(market),
+        seller: market.seller.pubkey(),
+        tree_config: market.tree.tree_config,
+        merkle_tree: market.tree.merkle_tree,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: CancelAuctionInstruction {
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn create_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+
+    let end_time = start_auction(&mut ctx, &mut market).await;
+
+    let auction = fetch_program_account::<Auction>(&mut ctx, find_auction(&market)).await;
+    assert_eq!(auction.seller, market.seller.pubkey());
+    assert_eq!(auction.asset_id, market.leaf.asset_id);
+    assert_eq!(auction.end_time, end_time);
+    assert_eq!(auction.reserve_price, PRICE);
+    assert_eq!(auction.min_bid_increment, MIN_BID_INCREMENT);
+    assert_eq!(auction.highest_bidder, None);
+}
+
+#[tokio::test]
+async fn reject_auction_without_bid_increment() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let start_time = current_timestamp(&mut ctx).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[create_auction_instruction(&market, start_time, 0)],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidBidIncrement.into());
+}
+
+#[tokio::test]
+async fn place_auction_bid() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_auction(&mut ctx, &mut market).await;
+    let [first_bidder, second_bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &first_bidder.pubkey(),
+            &first_bidder.pubkey(),
+            PRICE,
+        )],
+        &[&first_bidder],
+    )
+    .await
+    .unwrap();
+    let first_bidder_balance = ctx
+        .banks_client
+        .get_balance(first_bidder.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &second_bidder.pubkey(),
+            &first_bidder.pubkey(),
+            PRICE + MIN_BID_INCREMENT,
+        )],
+        &[&second_bidder],
+    )
+    .await
+    .unwrap();
+
+    let auction = fetch_program_account::<Auction>(&mut ctx, find_auction(&market)).await;
+    assert_eq!(auction.highest_bidder, Some(second_bidder.pubkey()));
+    assert_eq!(auction.highest_bid, PRICE + MIN_BID_INCREMENT);
+    // outbid bidder gets its bid back
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(first_bidder.pubkey())
+            .await
+            .unwrap(),
+        first_bidder_balance + PRICE
+    );
+}
+
+#[tokio::test]
+async fn reject_auction_bid_below_increment() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_auction(&mut ctx, &mut market).await;
+    let [first_bidder, second_bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &first_bidder.pubkey(),
+            &first_bidder.pubkey(),
+            PRICE,
+        )],
+        &[&first_bidder],
+    )
+    .await
+    .unwrap();
+
+    let result = process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &second_bidder.pubkey(),
+            &first_bidder.pubkey(),
+            PRICE + MIN_BID_INCREMENT - 1,
+        )],
+        &[&second_bidder],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::BidTooLow.into());
+}
+
+#[tokio::test]
+async fn settle_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    let end_time = start_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            &bidder.pubkey(),
+            PRICE,
+        )],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+    let creator_balance = ctx
+        .banks_client
+        .get_balance(market.creator.pubkey())
+        .await
+        .unwrap();
+    warp_to_timestamp(&mut ctx, end_time).await;
+
+    process_instructions(
+        &mut ctx,
+        &[settle_auction_instruction(&market, &bidder.pubkey())],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.creator.pubkey())
+            .await
+            .unwrap(),
+        creator_balance + PRICE / 20
+    );
+    // auction and its drained escrow are both closed
+    for address in [find_auction(&market), find_auction_escrow(&market)] {
+        let account = ctx.banks_client.get_account(address).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn reject_settle_auction_before_its_end() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            &bidder.pubkey(),
+            PRICE,
+        )],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+
+    let result = process_instructions(
+        &mut ctx,
+        &[settle_auction_instruction(&market, &bidder.pubkey())],
+        &[],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::AuctionNotEnded.into());
+}
+
+#[tokio::test]
+async fn refund_auction_bid() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    let end_time = start_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            &bidder.pubkey(),
+            PRICE,
+        )],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+    let bidder_balance = ctx.banks_client.get_balance(bidder.pubkey()).await.unwrap();
+    warp_to_timestamp(&mut ctx, end_time + AUCTION_SETTLEMENT_GRACE_PERIOD).await;
+
+    process_instructions(
+        &mut ctx,
+        &[refund_auction_bid_instruction(&market, &bidder.pubkey())],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+
+    let refunded = ctx.banks_client.get_balance(bidder.pubkey()).await.unwrap() - bidder_balance;
+    assert!(refunded >= PRICE);
+    let escrow = ctx
+        .banks_client
+        .get_account(find_auction_escrow(&market))
+        .await
+        .unwrap();
+    assert!(escrow.is_none());
+
+    // without bids left the seller gets the cnft back
+    process_instructions(
+        &mut ctx,
+        &[cancel_auction_instruction(&market)],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn reject_refund_auction_bid_within_grace_period() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    let end_time = start_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            &bidder.pubkey(),
+            PRICE,
+        )],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+    warp_to_timestamp(&mut ctx, end_time).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[refund_auction_bid_instruction(&market, &bidder.pubkey())],
+        &[&bidder],
+    )
+    .await;
+    assert_custom_error(
+        result,
+        AuctionHouseV2Errors::SettlementGracePeriodNotOver.into(),
+    );
+}
+
+#[tokio::test]
+async fn cancel_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_auction(&mut ctx, &mut market).await;
+
+    process_instructions(
+        &mut ctx,
+        &[cancel_auction_instruction(&market)],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+
+    let auction = ctx
+        .banks_client
+        .get_account(find_auction(&market))
+        .await
+        .unwrap();
+    assert!(auction.is_none());
+}
+
+#[tokio::test]
+async fn reject_cancel_auction_with_bids() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[place_auction_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            &bidder.pubkey(),
+            PRICE,
+        )],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+
+    let result = process_instructions(
+        &mut ctx,
+        &[cancel_auction_instruction(&market)],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::AuctionHasBids.into());
+}
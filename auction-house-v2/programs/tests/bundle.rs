@@ -0,0 +1,424 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::AddBundleItemInstruction as AddBundleItemAccounts,
+    accounts::BuyBundleInstruction as BuyBundleAccounts,
+    accounts::CancelBundleInstruction as CancelBundleAccounts,
+    accounts::CreateBundleInstruction as CreateBundleAccounts,
+    accounts::RemoveBundleItemInstruction as RemoveBundleItemAccounts,
+    instruction::AddBundleItem as AddBundleItemInstruction,
+    instruction::BuyBundle as BuyBundleInstruction,
+    instruction::CancelBundle as CancelBundleInstruction,
+    instruction::CreateBundle as CreateBundleInstruction,
+    instruction::RemoveBundleItem as RemoveBundleItemInstruction, AuctionHouseV2Errors, Bundle,
+    BundleItemArgs, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    fetch_program_account, find_auction_house, find_program_as_signer, mint_market_leaf,
+    process_instructions, Leaf, Tree, BUNDLE, MAX_BUNDLE_ITEMS,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+const BUNDLE_ID: u64 = 1;
+
+struct Market {
+    authority: Keypair,
+    seller: Keypair,
+    creator: Keypair,
+    /// Leaves of the seller, each minted to its own tree
+    items: Vec<(Tree, Leaf)>,
+}
+
+/// Mints two leaves of the seller paying 5% royalties on a native auction
+/// house taking 5% fees
+async fn create_market(ctx: &mut ProgramTestContext) -> Market {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [seller, creator] = create_wallets(ctx, 1_000_000_000).await;
+    let mut items = Vec::with_capacity(2);
+    for _ in 0..2 {
+        items.push(mint_market_leaf(ctx, &seller.pubkey(), &seller.pubkey(), &creator, true).await);
+    }
+    Market {
+        authority,
+        seller,
+        creator,
+        items,
+    }
+}
+
+fn find_bundle(market: &Market) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    Pubkey::find_program_address(
+        &[
+            BUNDLE.as_ref(),
+            market.seller.pubkey().as_ref(),
+            auction_house.as_ref(),
+            BUNDLE_ID.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn create_bundle_instruction(market: &Market, item_count: u8) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: CreateBundleAccounts {
+            auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+                .auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            seller: market.seller.pubkey(),
+            bundle: find_bundle(market),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: CreateBundleInstruction {
+            bundle_id: BUNDLE_ID,
+            price: PRICE,
+            item_count,
+        }
+        .data(),
+    }
+}
+
+fn add_bundle_item_instruction(market: &Market, item: usize) -> Instruction {
+    let (tree, leaf) = &market.items[item];
+    let mut account_metas = AddBundleItemAccounts {
+        bundle: find_bundle(market),
+        seller: market.seller.pubkey(),
+        tree_config: tree.tree_config,
+        merkle_tree: tree.merkle_tree,
+        previous_leaf_delegate: leaf.delegate,
+        asset_id: leaf.asset_id,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(tree.proof(leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: AddBundleItemInstruction {
+            root: tree.root(),
+            data_hash: leaf.data_hash(),
+            creator_hash: leaf.creator_hash(),
+            nonce: leaf.nonce,
+            index: leaf.index,
+        }
+        .data(),
+    }
+}
+
+fn remove_bundle_item_instruction(market: &Market, item: usize) -> Instruction {
+    let (tree, leaf) = &market.items[item];
+    let mut account_metas = RemoveBundleItemAccounts {
+        bundle: find_bundle(market),
+        seller: market.seller.pubkey(),
+        tree_config: tree.tree_config,
+        merkle_tree: tree.merkle_tree,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(tree.proof(leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: RemoveBundleItemInstruction {
+            root: tree.root(),
+            data_hash: leaf.data_hash(),
+            creator_hash: leaf.creator_hash(),
+            nonce: leaf.nonce,
+        }
+        .data(),
+    }
+}
+
+fn cancel_bundle_instruction(market: &Market) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: CancelBundleAccounts {
+            bundle: find_bundle(market),
+            seller: market.seller.pubkey(),
+        }
+        .to_account_metas(None),
+        data: CancelBundleInstruction {}.data(),
+    }
+}
+
+fn buy_bundle_instruction(market: &Market, buyer: &Pubkey, item_count: usize) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let mut account_metas = BuyBundleAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        seller: market.seller.pubkey(),
+        seller_receipt_account: market.seller.pubkey(),
+        bundle: find_bundle(market),
+        buyer: *buyer,
+        payment_account: *buyer,
+        auction_house_fee_account: accounts.fee_account,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    let mut items = Vec::with_capacity(item_count);
+    for (tree, leaf) in &market.items[..item_count] {
+        let proof = tree.proof(leaf.index);
+        account_metas.push(AccountMeta::new_readonly(tree.tree_config, false));
+        account_metas.push(AccountMeta::new(tree.merkle_tree, false));
+        account_metas.push(AccountMeta::new(market.creator.pubkey(), false));
+        items.push(BundleItemArgs {
+            root: tree.root(),
+            data_hash: leaf.data_hash(),
+            creator_hash: leaf.creator_hash(),
+            nonce: leaf.nonce,
+            metadata: leaf.metadata_args(),
+            proof_length: proof.len() as u8,
+        });
+        account_metas.extend(proof);
+    }
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: BuyBundleInstruction { items }.data(),
+    }
+}
+
+/// Creates a bundle of `item_count` items and adds the first `added` leaves
+/// of the market to it
+async fn list_bundle(
+    ctx: &mut ProgramTestContext,
+    market: &mut Market,
+    item_count: u8,
+    added: usize,
+) {
+    process_instructions(
+        ctx,
+        &[create_bundle_instruction(market, item_count)],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+    for item in 0..added {
+        process_instructions(
+            ctx,
+            &[add_bundle_item_instruction(market, item)],
+            &[&market.seller],
+        )
+        .await
+        .unwrap();
+        let (tree, leaf) = &mut market.items[item];
+        tree.delegate(leaf, &find_program_as_signer());
+    }
+}
+
+#[tokio::test]
+async fn create_bundle() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+
+    process_instructions(
+        &mut ctx,
+        &[create_bundle_instruction(&market, 2)],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+
+    let bundle = fetch_program_account::<Bundle>(&mut ctx, find_bundle(&market)).await;
+    assert_eq!(bundle.seller, market.seller.pubkey());
+    assert_eq!(bundle.price, PRICE);
+    assert_eq!(bundle.item_count, 2);
+    assert!(bundle.items.is_empty());
+}
+
+#[tokio::test]
+async fn reject_bundle_above_the_maximum_items() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[create_bundle_instruction(&market, MAX_BUNDLE_ITEMS + 1)],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidBundleSize.into());
+}
+
+#[tokio::test]
+async fn add_bundle_item() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+
+    list_bundle(&mut ctx, &mut market, 2, 1).await;
+
+    let bundle = fetch_program_account::<Bundle>(&mut ctx, find_bundle(&market)).await;
+    let (tree, leaf) = &market.items[0];
+    assert_eq!(bundle.items.len(), 1);
+    assert_eq!(bundle.items[0].asset_id, leaf.asset_id);
+    assert_eq!(bundle.items[0].merkle_tree, tree.merkle_tree);
+    assert_eq!(bundle.items[0].leaf_index, leaf.index);
+}
+
+#[tokio::test]
+async fn reject_bundle_item_added_twice() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    list_bundle(&mut ctx, &mut market, 2, 1).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[add_bundle_item_instruction(&market, 0)],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::DuplicateBundleItem.into());
+}
+
+#[tokio::test]
+async fn remove_bundle_item() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    list_bundle(&mut ctx, &mut market, 2, 1).await;
+
+    process_instructions(
+        &mut ctx,
+        &[remove_bundle_item_instruction(&market, 0)],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+
+    let bundle = fetch_program_account::<Bundle>(&mut ctx, find_bundle(&market)).await;
+    assert!(bundle.items.is_empty());
+}
+
+#[tokio::test]
+async fn reject_remove_of_leaf_outside_the_bundle() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    list_bundle(&mut ctx, &mut market, 2, 1).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[remove_bundle_item_instruction(&market, 1)],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::BundleItemNotFound.into());
+}
+
+#[tokio::test]
+async fn cancel_bundle() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    list_bundle(&mut ctx, &mut market, 2, 0).await;
+
+    process_instructions(
+        &mut ctx,
+        &[cancel_bundle_instruction(&market)],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+
+    let bundle = ctx
+        .banks_client
+        .get_account(find_bundle(&market))
+        .await
+        .unwrap();
+    assert!(bundle.is_none());
+}
+
+#[tokio::test]
+async fn reject_cancel_of_bundle_holding_items() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    list_bundle(&mut ctx, &mut market, 2, 1).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[cancel_bundle_instruction(&market)],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::BundleNotEmpty.into());
+}
+
+#[tokio::test]
+async fn buy_bundle() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    list_bundle(&mut ctx, &mut market, 2, 2).await;
+    let [buyer] = create_wallets(&mut ctx, 1_000_000_000).await;
+    let creator_balance = ctx
+        .banks_client
+        .get_balance(market.creator.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[buy_bundle_instruction(&market, &buyer.pubkey(), 2)],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+
+    // royalties are paid on each half of the price
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.creator.pubkey())
+            .await
+            .unwrap(),
+        creator_balance + PRICE / 20
+    );
+    let bundle = ctx
+        .banks_client
+        .get_account(find_bundle(&market))
+        .await
+        .unwrap();
+    assert!(bundle.is_none());
+}
+
+#[tokio::test]
+async fn reject_buy_of_incomplete_bundle() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    list_bundle(&mut ctx, &mut market, 2, 1).await;
+    let [buyer] = create_wallets(&mut ctx, 1_000_000_000).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[buy_bundle_instruction(&market, &buyer.pubkey(), 1)],
+        &[&buyer],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::BundleIncomplete.into());
+}
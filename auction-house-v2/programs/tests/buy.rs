@@ -0,0 +1,275 @@
+use anchor_lang::{system_program, AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::BuyInstruction as BuyAccounts,
+    accounts::PrintListingReceiptInstruction as PrintListingReceiptAccounts,
+    instruction::Buy as BuyInstruction,
+    instruction::PrintListingReceipt as PrintListingReceiptInstruction, AuctionHouseV2Errors,
+    ListingReceipt, RoyaltyMode, RoyaltyPolicy, TradeState, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_auction_house_instruction,
+    find_auction_house, find_program_as_signer, find_trade_state, leaf_metadata,
+    process_instructions, sell_instruction, Leaf, Tree, LISTING_RECEIPT, PURCHASE_RECEIPT,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+
+struct Listing {
+    authority: Keypair,
+    seller: Keypair,
+    creator: Keypair,
+    tree: Tree,
+    leaf: Leaf,
+    trade_state: Pubkey,
+    listing_receipt: Pubkey,
+}
+
+/// Lists a leaf paying 5% royalties for `PRICE` on a native auction house
+/// taking 5% fees, and prints its receipt
+async fn list(ctx: &mut ProgramTestContext, allowed_buyer: Option<Pubkey>) -> Listing {
+    let authority = Keypair::new();
+    let seller = Keypair::new();
+    let creator = Keypair::new();
+    let payer = ctx.payer.pubkey();
+    process_instructions(
+        ctx,
+        &[
+            system_instruction::transfer(&payer, &authority.pubkey(), 1_000_000_000),
+            system_instruction::transfer(&payer, &seller.pubkey(), 1_000_000_000),
+            system_instruction::transfer(&payer, &creator.pubkey(), 1_000_000_000),
+            create_auction_house_instruction(
+                &authority.pubkey(),
+                &native_mint::id(),
+                &authority.pubkey(),
+                &authority.pubkey(),
+                &authority.pubkey(),
+                500,
+                RoyaltyPolicy {
+                    mode: RoyaltyMode::Enforced,
+                    min_basis_points: 0,
+                    max_basis_points: 9500,
+                },
+            ),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let mut tree = Tree::create(ctx).await;
+    let mut metadata = leaf_metadata(500, &[(creator.pubkey(), 100)]);
+    metadata.primary_sale_happened = true;
+    let mut leaf = tree
+        .mint(
+            ctx,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            metadata,
+            &[&creator],
+        )
+        .await;
+
+    process_instructions(
+        ctx,
+        &[sell_instruction(
+            &authority.pubkey(),
+            &native_mint::id(),
+            &tree,
+            &leaf,
+            PRICE,
+            None,
+            allowed_buyer,
+        )],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    tree.delegate(&mut leaf, &find_program_as_signer());
+
+    let auction_house = find_auction_house(&authority.pubkey(), &native_mint::id()).auction_house;
+    let trade_state = find_trade_state(&seller.pubkey(), &auction_house, &leaf.asset_id, PRICE);
+    let trade_state_account = ctx
+        .banks_client
+        .get_account(trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let created_at = TradeState::try_deserialize(&mut trade_state_account.data.as_ref())
+        .unwrap()
+        .created_at;
+    let (listing_receipt, _) = Pubkey::find_program_address(
+        &[
+            LISTING_RECEIPT.as_bytes(),
+            trade_state.as_ref(),
+            created_at.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    );
+    process_instructions(
+        ctx,
+        &[Instruction {
+            program_id: ID,
+            accounts: PrintListingReceiptAccounts {
+                seller_trade_state: trade_state,
+                receipt: listing_receipt,
+                payer,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: PrintListingReceiptInstruction {}.data(),
+        }],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    Listing {
+        authority,
+        seller,
+        creator,
+        tree,
+        leaf,
+        trade_state,
+        listing_receipt,
+    }
+}
+
+fn buy_instruction(listing: &Listing, buyer: &Pubkey) -> Instruction {
+    let accounts = find_auction_house(&listing.authority.pubkey(), &native_mint::id());
+    let mut account_metas = BuyAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: listing.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        tree_config: listing.tree.tree_config,
+        seller: listing.seller.pubkey(),
+        seller_receipt_account: listing.seller.pubkey(),
+        merkle_tree: listing.tree.merkle_tree,
+        seller_trade_state: listing.trade_state,
+        buyer: *buyer,
+        payment_account: *buyer,
+        asset_id: listing.leaf.asset_id,
+        auction_house_fee_account: accounts.fee_account,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+        listing_receipt: listing.listing_receipt,
+    }
+    .to_account_metas(None);
+    account_metas.push(AccountMeta::new(listing.creator.pubkey(), false));
+    account_metas.extend(listing.tree.proof(listing.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: BuyInstruction {
+            buyer_price: PRICE,
+            root: listing.tree.root(),
+            data_hash: listing.leaf.data_hash(),
+            creator_hash: listing.leaf.creator_hash(),
+            nonce: listing.leaf.nonce,
+            index: listing.leaf.index,
+            metadata: listing.leaf.metadata_args(),
+            buyer_royalty_basis_points: None,
+        }
+        .data(),
+    }
+}
+
+async fn fund_buyer(ctx: &mut ProgramTestContext) -> Keypair {
+    let buyer = Keypair::new();
+    let payer = ctx.payer.pubkey();
+    process_instructions(
+        ctx,
+        &[system_instruction::transfer(
+            &payer,
+            &buyer.pubkey(),
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    buyer
+}
+
+#[tokio::test]
+async fn buy() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let listing = list(&mut ctx, None).await;
+    let buyer = fund_buyer(&mut ctx).await;
+    let creator_balance = ctx
+        .banks_client
+        .get_balance(listing.creator.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[buy_instruction(&listing, &buyer.pubkey())],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+
+    let trade_state = ctx
+        .banks_client
+        .get_account(listing.trade_state)
+        .await
+        .unwrap();
+    assert!(trade_state.is_none());
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(listing.creator.pubkey())
+            .await
+            .unwrap(),
+        creator_balance + PRICE / 20
+    );
+
+    let receipt_account = ctx
+        .banks_client
+        .get_account(listing.listing_receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt = ListingReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    let (purchase_receipt, _) = Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT.as_bytes(),
+            listing.listing_receipt.as_ref(),
+            buyer.pubkey().as_ref(),
+        ],
+        &ID,
+    );
+    assert!(receipt.purchased_at.is_some());
+    assert_eq!(receipt.purchase_receipt, Some(purchase_receipt));
+}
+
+#[tokio::test]
+async fn reject_buy_by_another_buyer_than_the_allowed_one() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let listing = list(&mut ctx, Some(Pubkey::new_unique())).await;
+    let buyer = fund_buyer(&mut ctx).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[buy_instruction(&listing, &buyer.pubkey())],
+        &[&buyer],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::BuyerNotAllowed.into());
+}
@@ -0,0 +1,366 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::AcceptCollectionOfferInstruction as AcceptCollectionOfferAccounts,
+    accounts::CancelCollectionOfferInstruction as CancelCollectionOfferAccounts,
+    accounts::CollectionOfferInstruction as CollectionOfferAccounts,
+    instruction::AcceptCollectionOffer as AcceptCollectionOfferInstruction,
+    instruction::CancelCollectionOffer as CancelCollectionOfferInstruction,
+    instruction::CollectionOffer as CollectionOfferInstruction, AuctionHouseV2Errors,
+    CollectionOffer, ID,
+};
+use mpl_bubblegum::{
+    programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID},
+    types::Collection,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    fetch_program_account, find_auction_house, find_escrow, leaf_metadata, process_instructions,
+    Leaf, Tree, COLLECTION_OFFER,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+
+struct Market {
+    authority: Keypair,
+    buyer: Keypair,
+    collection: Pubkey,
+}
+
+async fn create_market(ctx: &mut ProgramTestContext) -> Market {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [buyer] = create_wallets(ctx, 1_000_000_000).await;
+    Market {
+        authority,
+        buyer,
+        collection: Pubkey::new_unique(),
+    }
+}
+
+fn find_collection_offer(market: &Market) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    Pubkey::find_program_address(
+        &[
+            COLLECTION_OFFER.as_ref(),
+            market.buyer.pubkey().as_ref(),
+            auction_house.as_ref(),
+            market.collection.as_ref(),
+            PRICE.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn collection_offer_instruction(market: &Market, quantity: u32) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    Instruction {
+        program_id: ID,
+        accounts: CollectionOfferAccounts {
+            auction_house: accounts.auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            bidder: market.buyer.pubkey(),
+            payment_account: market.buyer.pubkey(),
+            collection: market.collection,
+            buyer_escrow: find_escrow(&accounts.auction_house, &market.buyer.pubkey()),
+            collection_offer: find_collection_offer(market),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: CollectionOfferInstruction {
+            buyer_price: PRICE,
+            quantity,
+            expiry: None,
+        }
+        .data(),
+    }
+}
+
+fn cancel_collection_offer_instruction(market: &Market, wallet: &Pubkey) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    Instruction {
+        program_id: ID,
+        accounts: CancelCollectionOfferAccounts {
+            auction_house: accounts.auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            collection: market.collection,
+            wallet: *wallet,
+            receipt_account: *wallet,
+            buyer_escrow: find_escrow(&accounts.auction_house, wallet),
+            collection_offer: find_collection_offer(market),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: CancelCollectionOfferInstruction {
+            buyer_price: PRICE,
+            refund_escrow: true,
+        }
+        .data(),
+    }
+}
+
+fn accept_collection_offer_instruction(
+    market: &Market,
+    seller: &Keypair,
+    creator: &Pubkey,
+    tree: &Tree,
+    leaf: &Leaf,
+) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let mut account_metas = AcceptCollectionOfferAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        tree_config: tree.tree_config,
+        seller: seller.pubkey(),
+        seller_receipt_account: seller.pubkey(),
+        merkle_tree: tree.merkle_tree,
+        buyer: market.buyer.pubkey(),
+        buyer_escrow: find_escrow(&accounts.auction_house, &market.buyer.pubkey()),
+        collection_offer: find_collection_offer(market),
+        collection: market.collection,
+        auction_house_fee_account: accounts.fee_account,
+        previous_leaf_delegate: leaf.delegate,
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.push(AccountMeta::new(*creator, false));
+    account_metas.extend(tree.proof(leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: AcceptCollectionOfferInstruction {
+            buyer_price: PRICE,
+            root: tree.root(),
+            data_hash: leaf.data_hash(),
+            creator_hash: leaf.creator_hash(),
+            nonce: leaf.nonce,
+            index: leaf.index,
+            metadata: leaf.metadata_args(),
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn place_collection_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+
+    process_instructions(
+        &mut ctx,
+        &[collection_offer_instruction(&market, 2)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let collection_offer =
+        fetch_program_account::<CollectionOffer>(&mut ctx, find_collection_offer(&market)).await;
+    assert_eq!(collection_offer.wallet, market.buyer.pubkey());
+    assert_eq!(collection_offer.collection, market.collection);
+    assert_eq!(collection_offer.price, PRICE);
+    assert_eq!(collection_offer.quantity, 2);
+
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    let escrow_balance = ctx
+        .banks_client
+        .get_balance(find_escrow(&auction_house, &market.buyer.pubkey()))
+        .await
+        .unwrap();
+    assert!(escrow_balance >= 2 * PRICE);
+}
+
+#[tokio::test]
+async fn reject_collection_offer_without_quantity() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[collection_offer_instruction(&market, 0)],
+        &[&market.buyer],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidQuantity.into());
+}
+
+#[tokio::test]
+async fn cancel_collection_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    process_instructions(
+        &mut ctx,
+        &[collection_offer_instruction(&market, 2)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+    let buyer_balance = ctx
+        .banks_client
+        .get_balance(market.buyer.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[cancel_collection_offer_instruction(
+            &market,
+            &market.buyer.pubkey(),
+        )],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let collection_offer = ctx
+        .banks_client
+        .get_account(find_collection_offer(&market))
+        .await
+        .unwrap();
+    assert!(collection_offer.is_none());
+    let refunded = ctx
+        .banks_client
+        .get_balance(market.buyer.pubkey())
+        .await
+        .unwrap()
+        - buyer_balance;
+    assert!(refunded >= 2 * PRICE);
+}
+
+#[tokio::test]
+async fn reject_cancel_of_another_wallet_collection_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let [other_wallet] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[collection_offer_instruction(&market, 2)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let result = process_instructions(
+        &mut ctx,
+        &[cancel_collection_offer_instruction(
+            &market,
+            &other_wallet.pubkey(),
+        )],
+        &[&other_wallet],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::ConstraintSeeds.into());
+}
+
+#[tokio::test]
+async fn accept_collection_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let [seller, creator] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[collection_offer_instruction(&market, 2)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let mut tree = Tree::create(&mut ctx).await;
+    let mut metadata = leaf_metadata(500, &[(creator.pubkey(), 100)]);
+    metadata.primary_sale_happened = true;
+    metadata.collection = Some(Collection {
+        verified: true,
+        key: market.collection,
+    });
+    let leaf = tree
+        .forge(&mut ctx, &seller.pubkey(), &seller.pubkey(), metadata)
+        .await;
+
+    process_instructions(
+        &mut ctx,
+        &[accept_collection_offer_instruction(
+            &market,
+            &seller,
+            &creator.pubkey(),
+            &tree,
+            &leaf,
+        )],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    // the offer stays open for the second asset
+    let collection_offer =
+        fetch_program_account::<CollectionOffer>(&mut ctx, find_collection_offer(&market)).await;
+    assert_eq!(collection_offer.quantity, 1);
+}
+
+#[tokio::test]
+async fn reject_accept_collection_offer_with_unverified_collection() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let [seller, creator] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[collection_offer_instruction(&market, 1)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let mut tree = Tree::create(&mut ctx).await;
+    let mut metadata = leaf_metadata(500, &[(creator.pubkey(), 100)]);
+    metadata.collection = Some(Collection {
+        verified: false,
+        key: market.collection,
+    });
+    let leaf = tree
+        .mint(
+            &mut ctx,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            metadata,
+            &[&creator],
+        )
+        .await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[accept_collection_offer_instruction(
+            &market,
+            &seller,
+            &creator.pubkey(),
+            &tree,
+            &leaf,
+        )],
+        &[&seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::CollectionMismatch.into());
+}
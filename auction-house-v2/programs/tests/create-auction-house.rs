@@ -3,11 +3,15 @@ use anchor_lang::{system_program, InstructionData, ToAccountMetas};
 use anchor_spl::token::spl_token::native_mint;
 use auction_house_v2::{
     accounts::CreateInstruction as CreateAuctionHouseAccounts,
-    instruction::Create as CreateAuctionHouseInstruction, AuctionHouseV2Data, ID,
+    instruction::Create as CreateAuctionHouseInstruction, AuctionHouseV2Data, AuctionHouseV2Errors,
+    RoyaltyMode, RoyaltyPolicy, ID,
 };
 use solana_program::{instruction::Instruction, pubkey::Pubkey};
 use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
-use utils::{auction_house_program_test, AUCTION_HOUSE, FEE, TREASURY};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_auction_house_instruction,
+    process_instructions, AUCTION_HOUSE, FEE, TREASURY,
+};
 
 pub mod utils;
 
@@ -50,6 +54,11 @@ async fn create_native_auction_house() {
     let create_auction_house_data = CreateAuctionHouseInstruction {
         seller_fee_basis_points: 500,
         requires_sign_off: false,
+        royalty_policy: RoyaltyPolicy {
+            mode: RoyaltyMode::Enforced,
+            min_basis_points: 0,
+            max_basis_points: 9500,
+        },
     }
     .data();
 
@@ -72,6 +81,10 @@ async fn create_native_auction_house() {
         AuctionHouseV2Data::try_deserialize(&mut auction_house_account.data.as_ref()).unwrap();
     assert_eq!(auction_house_data.requires_sign_off, false);
     assert_eq!(auction_house_data.seller_fee_basis_points, 500);
+    assert_eq!(
+        auction_house_data.royalty_policy.mode,
+        RoyaltyMode::Enforced
+    );
     assert_eq!(auction_house_data.authority, payer_pubkey);
     assert_eq!(auction_house_data.fee_account, fee_account);
     assert_eq!(auction_house_data.fee_withdrawal_account, fee_account);
@@ -82,3 +95,49 @@ async fn create_native_auction_house() {
     assert_eq!(auction_house_data.fee_account_bump, fee_account_bump);
     assert_eq!(auction_house_data.treasury_bump, treasury_bump);
 }
+
+#[tokio::test]
+async fn reject_royalty_cap_above_the_rest_of_the_fee() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+
+    // a 5% fee leaves at most 95% to creators
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &payer_pubkey,
+        &native_mint::id(),
+        &payer_pubkey,
+        &payer_pubkey,
+        &payer_pubkey,
+        500,
+        RoyaltyPolicy {
+            mode: RoyaltyMode::Enforced,
+            min_basis_points: 0,
+            max_basis_points: 10000,
+        },
+    );
+    let result = process_instructions(&mut ctx, &[create_auction_house_instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidRoyaltyPolicy.into());
+}
+
+#[tokio::test]
+async fn reject_uncapped_royalty_policy_with_a_fee() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+
+    // an uncapped royalty could take all of the sale price
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &payer_pubkey,
+        &native_mint::id(),
+        &payer_pubkey,
+        &payer_pubkey,
+        &payer_pubkey,
+        500,
+        RoyaltyPolicy {
+            mode: RoyaltyMode::Enforced,
+            min_basis_points: 0,
+            max_basis_points: 0,
+        },
+    );
+    let result = process_instructions(&mut ctx, &[create_auction_house_instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidRoyaltyPolicy.into());
+}
@@ -0,0 +1,346 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::BuyDutchAuctionInstruction as BuyDutchAuctionAccounts,
+    accounts::CancelDutchAuctionInstruction as CancelDutchAuctionAccounts,
+    accounts::CreateDutchAuctionInstruction as CreateDutchAuctionAccounts,
+    instruction::BuyDutchAuction as BuyDutchAuctionInstruction,
+    instruction::CancelDutchAuction as CancelDutchAuctionInstruction,
+    instruction::CreateDutchAuction as CreateDutchAuctionInstruction, AuctionHouseV2Errors,
+    DutchAuction, PriceCurve, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    current_timestamp, fetch_program_account, find_auction_house, find_program_as_signer,
+    mint_market_leaf, process_instructions, warp_to_timestamp, Leaf, Tree, DUTCH_AUCTION,
+};
+
+pub mod utils;
+
+const START_PRICE: u64 = 200_000_000;
+const END_PRICE: u64 = 100_000_000;
+const DURATION: i64 = 60 * 60;
+
+struct Market {
+    authority: Keypair,
+    seller: Keypair,
+    creator: Keypair,
+    tree: Tree,
+    leaf: Leaf,
+}
+
+/// Mints a leaf of the seller paying 5% royalties on a native auction house
+/// taking 5% fees
+async fn create_market(ctx: &mut ProgramTestContext) -> Market {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [seller, creator] = create_wallets(ctx, 1_000_000_000).await;
+    let (tree, leaf) =
+        mint_market_leaf(ctx, &seller.pubkey(), &seller.pubkey(), &creator, true).await;
+    Market {
+        authority,
+        seller,
+        creator,
+        tree,
+        leaf,
+    }
+}
+
+fn find_dutch_auction(market: &Market) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    Pubkey::find_program_address(
+        &[
+            DUTCH_AUCTION.as_ref(),
+            auction_house.as_ref(),
+            market.leaf.asset_id.as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn create_dutch_auction_instruction(
+    market: &Market,
+    start_time: i64,
+    start_price: u64,
+    end_price: u64,
+) -> Instruction {
+    let mut account_metas = CreateDutchAuctionAccounts {
+        auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+            .auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        tree_config: market.tree.tree_config,
+        owner: market.seller.pubkey(),
+        merkle_tree: market.tree.merkle_tree,
+        previous_leaf_delegate: market.leaf.delegate,
+        dutch_auction: find_dutch_auction(market),
+        asset_id: market.leaf.asset_id,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: CreateDutchAuctionInstruction {
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+            index: market.leaf.index,
+            start_time,
+            end_time: start_time + DURATION,
+            start_price,
+            end_price,
+            curve: PriceCurve::Linear,
+        }
+        .data(),
+    }
+}
+
+/// Lists the market leaf from `START_PRICE` down to `END_PRICE` over
+/// `DURATION` seconds, returning the start time
+async fn start_dutch_auction(ctx: &mut ProgramTestContext, market: &mut Market) -> i64 {
+    let start_time = current_timestamp(ctx).await;
+    process_instructions(
+        ctx,
+        &[create_dutch_auction_instruction(
+            market,
+            start_time,
+            START_PRICE,
+            END_PRICE,
+        )],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+    market
+        .tree
+        .delegate(&mut market.leaf, &find_program_as_signer());
+    start_time
+}
+
+fn buy_dutch_auction_instruction(market: &Market, buyer: &Pubkey, max_price: u64) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let mut account_metas = BuyDutchAuctionAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        tree_config: market.tree.tree_config,
+        seller: market.seller.pubkey(),
+        seller_receipt_account: market.seller.pubkey(),
+        merkle_tree: market.tree.merkle_tree,
+        dutch_auction: find_dutch_auction(market),
+        buyer: *buyer,
+        payment_account: *buyer,
+        auction_house_fee_account: accounts.fee_account,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.push(AccountMeta::new(market.creator.pubkey(), false));
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: BuyDutchAuctionInstruction {
+            max_price,
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+            metadata: market.leaf.metadata_args(),
+        }
+        .data(),
+    }
+}
+
+fn cancel_dutch_auction_instruction(market: &Market, seller: &Pubkey) -> Instruction {
+    let mut account_metas = CancelDutchAuctionAccounts {
+        dutch_auction: find_dutch_auction(market),
+        seller: *seller,
+        tree_config: market.tree.tree_config,
+        merkle_tree: market.tree.merkle_tree,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: CancelDutchAuctionInstruction {
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn create_dutch_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+
+    let start_time = start_dutch_auction(&mut ctx, &mut market).await;
+
+    let dutch_auction =
+        fetch_program_account::<DutchAuction>(&mut ctx, find_dutch_auction(&market)).await;
+    assert_eq!(dutch_auction.seller, market.seller.pubkey());
+    assert_eq!(dutch_auction.asset_id, market.leaf.asset_id);
+    assert_eq!(dutch_auction.start_time, start_time);
+    assert_eq!(dutch_auction.end_time, start_time + DURATION);
+    assert_eq!(dutch_auction.start_price, START_PRICE);
+    assert_eq!(dutch_auction.end_price, END_PRICE);
+    assert_eq!(dutch_auction.curve, PriceCurve::Linear);
+}
+
+#[tokio::test]
+async fn reject_dutch_auction_with_rising_price() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let start_time = current_timestamp(&mut ctx).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[create_dutch_auction_instruction(
+            &market,
+            start_time,
+            END_PRICE,
+            START_PRICE,
+        )],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidAuctionPrices.into());
+}
+
+#[tokio::test]
+async fn buy_dutch_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    let start_time = start_dutch_auction(&mut ctx, &mut market).await;
+    let [buyer] = create_wallets(&mut ctx, 1_000_000_000).await;
+    let creator_balance = ctx
+        .banks_client
+        .get_balance(market.creator.pubkey())
+        .await
+        .unwrap();
+    warp_to_timestamp(&mut ctx, start_time + DURATION / 2).await;
+
+    // halfway through the price has dropped halfway to the end price
+    let price = (START_PRICE + END_PRICE) / 2;
+    process_instructions(
+        &mut ctx,
+        &[buy_dutch_auction_instruction(
+            &market,
+            &buyer.pubkey(),
+            price,
+        )],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.creator.pubkey())
+            .await
+            .unwrap(),
+        creator_balance + price / 20
+    );
+    let dutch_auction = ctx
+        .banks_client
+        .get_account(find_dutch_auction(&market))
+        .await
+        .unwrap();
+    assert!(dutch_auction.is_none());
+}
+
+#[tokio::test]
+async fn reject_buy_dutch_auction_above_max_price() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_dutch_auction(&mut ctx, &mut market).await;
+    let [buyer] = create_wallets(&mut ctx, 1_000_000_000).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[buy_dutch_auction_instruction(
+            &market,
+            &buyer.pubkey(),
+            END_PRICE,
+        )],
+        &[&buyer],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::PriceAboveMaximum.into());
+}
+
+#[tokio::test]
+async fn cancel_dutch_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_dutch_auction(&mut ctx, &mut market).await;
+
+    process_instructions(
+        &mut ctx,
+        &[cancel_dutch_auction_instruction(
+            &market,
+            &market.seller.pubkey(),
+        )],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+
+    let dutch_auction = ctx
+        .banks_client
+        .get_account(find_dutch_auction(&market))
+        .await
+        .unwrap();
+    assert!(dutch_auction.is_none());
+}
+
+#[tokio::test]
+async fn reject_cancel_of_another_seller_dutch_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_dutch_auction(&mut ctx, &mut market).await;
+    let [other_wallet] = create_wallets(&mut ctx, 1_000_000_000).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[cancel_dutch_auction_instruction(
+            &market,
+            &other_wallet.pubkey(),
+        )],
+        &[&other_wallet],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::ConstraintHasOne.into());
+}
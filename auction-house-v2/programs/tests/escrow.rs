@@ -0,0 +1,269 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::DepositInstruction as DepositAccounts,
+    accounts::WithdrawInstruction as WithdrawAccounts, instruction::Deposit as DepositInstruction,
+    instruction::Withdraw as WithdrawInstruction, AuctionHouseV2Errors, RoyaltyMode, RoyaltyPolicy,
+    ID,
+};
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey, sysvar};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signer::Signer, system_instruction};
+use spl_associated_token_account::get_associated_token_address;
+use utils::{
+    assert_custom_error, auction_house_program_test, create_auction_house_instruction, create_mint,
+    find_auction_house, find_escrow, process_instructions,
+};
+
+pub mod utils;
+
+const ROYALTY_POLICY: RoyaltyPolicy = RoyaltyPolicy {
+    mode: RoyaltyMode::Enforced,
+    min_basis_points: 0,
+    max_basis_points: 9500,
+};
+
+fn withdraw_instruction(
+    authority: &Pubkey,
+    treasury_mint: &Pubkey,
+    wallet: &Pubkey,
+    receipt_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let auction_house = find_auction_house(authority, treasury_mint).auction_house;
+    Instruction {
+        program_id: ID,
+        accounts: WithdrawAccounts {
+            auction_house,
+            auction_house_authority: *authority,
+            treasury_mint: *treasury_mint,
+            wallet: *wallet,
+            receipt_account: *receipt_account,
+            buyer_escrow: find_escrow(&auction_house, wallet),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: WithdrawInstruction { amount }.data(),
+    }
+}
+
+fn deposit_instruction(
+    authority: &Pubkey,
+    treasury_mint: &Pubkey,
+    wallet: &Pubkey,
+    payment_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let auction_house = find_auction_house(authority, treasury_mint).auction_house;
+    Instruction {
+        program_id: ID,
+        accounts: DepositAccounts {
+            auction_house,
+            auction_house_authority: *authority,
+            treasury_mint: *treasury_mint,
+            wallet: *wallet,
+            payment_account: *payment_account,
+            buyer_escrow: find_escrow(&auction_house, wallet),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: DepositInstruction { amount }.data(),
+    }
+}
+
+/// Creates an auction house of the payer trading `treasury_mint`
+async fn create_auction_house(ctx: &mut ProgramTestContext, treasury_mint: &Pubkey) {
+    let payer = ctx.payer.pubkey();
+    let withdrawal_account = if *treasury_mint == native_mint::id() {
+        payer
+    } else {
+        get_associated_token_address(&payer, treasury_mint)
+    };
+    process_instructions(
+        ctx,
+        &[create_auction_house_instruction(
+            &payer,
+            treasury_mint,
+            &withdrawal_account,
+            &payer,
+            &payer,
+            500,
+            ROYALTY_POLICY,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn withdraw_from_native_escrow() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    create_auction_house(&mut ctx, &native_mint::id()).await;
+    let auction_house = find_auction_house(&payer, &native_mint::id()).auction_house;
+    let escrow = find_escrow(&auction_house, &payer);
+
+    process_instructions(
+        &mut ctx,
+        &[
+            system_instruction::transfer(&payer, &escrow, 1_000_000_000),
+            withdraw_instruction(&payer, &native_mint::id(), &payer, &payer, 400_000_000),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+    let remaining = ctx.banks_client.get_balance(escrow).await.unwrap();
+    assert_eq!(remaining, 600_000_000);
+
+    // withdrawing everything above the rent reserve empties the escrow
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let available = 600_000_000 - rent.minimum_balance(0);
+    let result = process_instructions(
+        &mut ctx,
+        &[withdraw_instruction(
+            &payer,
+            &native_mint::id(),
+            &payer,
+            &payer,
+            available + 1,
+        )],
+        &[],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::NotEnoughFunds.into());
+    process_instructions(
+        &mut ctx,
+        &[withdraw_instruction(
+            &payer,
+            &native_mint::id(),
+            &payer,
+            &payer,
+            available,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let remaining = ctx.banks_client.get_balance(escrow).await.unwrap();
+    assert_eq!(remaining, 0);
+}
+
+#[tokio::test]
+async fn reject_native_escrow_withdrawal_to_another_wallet() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    create_auction_house(&mut ctx, &native_mint::id()).await;
+    let auction_house = find_auction_house(&payer, &native_mint::id()).auction_house;
+    let escrow = find_escrow(&auction_house, &payer);
+
+    let result = process_instructions(
+        &mut ctx,
+        &[
+            system_instruction::transfer(&payer, &escrow, 1_000_000_000),
+            withdraw_instruction(
+                &payer,
+                &native_mint::id(),
+                &payer,
+                &Pubkey::new_unique(),
+                400_000_000,
+            ),
+        ],
+        &[],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::PublicKeyMismatch.into());
+}
+
+#[tokio::test]
+async fn deposit_to_native_escrow() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    create_auction_house(&mut ctx, &native_mint::id()).await;
+    let auction_house = find_auction_house(&payer, &native_mint::id()).auction_house;
+    let escrow = find_escrow(&auction_house, &payer);
+
+    process_instructions(
+        &mut ctx,
+        &[deposit_instruction(
+            &payer,
+            &native_mint::id(),
+            &payer,
+            &payer,
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // the first deposit also funds the rent reserve of the escrow
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let deposited = ctx.banks_client.get_balance(escrow).await.unwrap();
+    assert_eq!(deposited, 1_000_000_000 + rent.minimum_balance(0));
+}
+
+#[tokio::test]
+async fn deposit_to_and_withdraw_from_token_escrow() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    let treasury_mint = create_mint(&mut ctx, &payer).await;
+    create_auction_house(&mut ctx, &treasury_mint).await;
+    let auction_house = find_auction_house(&payer, &treasury_mint).auction_house;
+    let escrow = find_escrow(&auction_house, &payer);
+    // the treasury withdrawal account of the payer doubles as its payment account
+    let payment_account = get_associated_token_address(&payer, &treasury_mint);
+
+    process_instructions(
+        &mut ctx,
+        &[
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &treasury_mint,
+                &payment_account,
+                &payer,
+                &[],
+                1_000,
+            )
+            .unwrap(),
+            deposit_instruction(&payer, &treasury_mint, &payer, &payment_account, 1_000),
+            withdraw_instruction(&payer, &treasury_mint, &payer, &payment_account, 400),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    for (token_account, amount) in [(payment_account, 400), (escrow, 600)] {
+        let account = ctx
+            .banks_client
+            .get_account(token_account)
+            .await
+            .unwrap()
+            .unwrap();
+        let token_account = spl_token::state::Account::unpack(&account.data).unwrap();
+        assert_eq!(token_account.amount, amount);
+    }
+
+    // the emptied escrow token account is closed
+    process_instructions(
+        &mut ctx,
+        &[withdraw_instruction(
+            &payer,
+            &treasury_mint,
+            &payer,
+            &payment_account,
+            600,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let escrow = ctx.banks_client.get_account(escrow).await.unwrap();
+    assert!(escrow.is_none());
+}
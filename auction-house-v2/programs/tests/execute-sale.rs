@@ -0,0 +1,308 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::BidInstruction as BidAccounts,
+    accounts::ExecuteSaleInstruction as ExecuteSaleAccounts, instruction::Bid as BidInstruction,
+    instruction::ExecuteSale as ExecuteSaleInstruction, AuctionHouseV2Errors, TradeState, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    fetch_program_account, find_auction_house, find_escrow, find_order_receipt,
+    find_program_as_signer, find_trade_state, mint_market_leaf, process_instructions,
+    sell_instruction, Leaf, Tree, BID_RECEIPT, LISTING_RECEIPT,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+
+struct Market {
+    authority: Keypair,
+    seller: Keypair,
+    buyer: Keypair,
+    creator: Keypair,
+    tree: Tree,
+    leaf: Leaf,
+}
+
+/// Lists a leaf paying 5% royalties for `PRICE` on a native auction house
+/// taking 5% fees, the seller is not a creator of the leaf
+async fn list(ctx: &mut ProgramTestContext, primary_sale_happened: bool) -> Market {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [seller, buyer, creator] = create_wallets(ctx, 1_000_000_000).await;
+    let (mut tree, mut leaf) = mint_market_leaf(
+        ctx,
+        &seller.pubkey(),
+        &seller.pubkey(),
+        &creator,
+        primary_sale_happened,
+    )
+    .await;
+
+    process_instructions(
+        ctx,
+        &[sell_instruction(
+            &authority.pubkey(),
+            &native_mint::id(),
+            &tree,
+            &leaf,
+            PRICE,
+            None,
+            None,
+        )],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    tree.delegate(&mut leaf, &find_program_as_signer());
+
+    Market {
+        authority,
+        seller,
+        buyer,
+        creator,
+        tree,
+        leaf,
+    }
+}
+
+/// Places a bid of `buyer_price` of the buyer on the listed leaf
+async fn bid(ctx: &mut ProgramTestContext, market: &Market, buyer_price: u64) {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let bid_instruction = Instruction {
+        program_id: ID,
+        accounts: BidAccounts {
+            auction_house: accounts.auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            bidder: market.buyer.pubkey(),
+            payment_account: market.buyer.pubkey(),
+            asset_id: market.leaf.asset_id,
+            merkle_tree: market.tree.merkle_tree,
+            buyer_escrow: find_escrow(&accounts.auction_house, &market.buyer.pubkey()),
+            buyer_trade_state: find_trade_state(
+                &market.buyer.pubkey(),
+                &accounts.auction_house,
+                &market.leaf.asset_id,
+                buyer_price,
+            ),
+            auction_house_fee_account: accounts.fee_account,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: BidInstruction {
+            buyer_price,
+            leaf_index: market.leaf.index,
+            expiry: None,
+        }
+        .data(),
+    };
+    process_instructions(ctx, &[bid_instruction], &[&market.buyer])
+        .await
+        .unwrap();
+}
+
+async fn execute_sale_instruction(
+    ctx: &mut ProgramTestContext,
+    market: &Market,
+    buyer_price: u64,
+) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let seller_trade_state = find_trade_state(
+        &market.seller.pubkey(),
+        &accounts.auction_house,
+        &market.leaf.asset_id,
+        PRICE,
+    );
+    let buyer_trade_state = find_trade_state(
+        &market.buyer.pubkey(),
+        &accounts.auction_house,
+        &market.leaf.asset_id,
+        buyer_price,
+    );
+    let (listing_receipt, bid_receipt) = find_sale_receipts(ctx, market, buyer_price).await;
+    let mut account_metas = ExecuteSaleAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        tree_config: market.tree.tree_config,
+        seller: market.seller.pubkey(),
+        seller_receipt_account: market.seller.pubkey(),
+        merkle_tree: market.tree.merkle_tree,
+        seller_trade_state,
+        buyer: market.buyer.pubkey(),
+        buyer_escrow: find_escrow(&accounts.auction_house, &market.buyer.pubkey()),
+        buyer_trade_state,
+        asset_id: market.leaf.asset_id,
+        auction_house_fee_account: accounts.fee_account,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+        listing_receipt,
+        bid_receipt,
+    }
+    .to_account_metas(None);
+    account_metas.push(AccountMeta::new(market.creator.pubkey(), false));
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: ExecuteSaleInstruction {
+            buyer_price,
+            seller_price: PRICE,
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+            index: market.leaf.index,
+            metadata: market.leaf.metadata_args(),
+            buyer_royalty_basis_points: None,
+        }
+        .data(),
+    }
+}
+
+/// Receipts of the listing and the bid of `buyer_price`
+async fn find_sale_receipts(
+    ctx: &mut ProgramTestContext,
+    market: &Market,
+    buyer_price: u64,
+) -> (Pubkey, Pubkey) {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    let seller_trade_state = find_trade_state(
+        &market.seller.pubkey(),
+        &auction_house,
+        &market.leaf.asset_id,
+        PRICE,
+    );
+    let buyer_trade_state = find_trade_state(
+        &market.buyer.pubkey(),
+        &auction_house,
+        &market.leaf.asset_id,
+        buyer_price,
+    );
+    let listed_at = fetch_program_account::<TradeState>(ctx, seller_trade_state)
+        .await
+        .created_at;
+    let bid_at = fetch_program_account::<TradeState>(ctx, buyer_trade_state)
+        .await
+        .created_at;
+    (
+        find_order_receipt(LISTING_RECEIPT, &seller_trade_state, listed_at),
+        find_order_receipt(BID_RECEIPT, &buyer_trade_state, bid_at),
+    )
+}
+
+#[tokio::test]
+async fn execute_sale_of_crossing_orders_at_the_ask() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = list(&mut ctx, true).await;
+    bid(&mut ctx, &market, PRICE + PRICE / 2).await;
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    let buyer_escrow = find_escrow(&auction_house, &market.buyer.pubkey());
+    let escrow_balance = ctx.banks_client.get_balance(buyer_escrow).await.unwrap();
+    let creator_balance = ctx
+        .banks_client
+        .get_balance(market.creator.pubkey())
+        .await
+        .unwrap();
+
+    let execute_sale_instruction =
+        execute_sale_instruction(&mut ctx, &market, PRICE + PRICE / 2).await;
+    process_instructions(&mut ctx, &[execute_sale_instruction], &[])
+        .await
+        .unwrap();
+
+    // the sale clears at the ask, the rest of the bid stays in the escrow
+    assert_eq!(
+        ctx.banks_client.get_balance(buyer_escrow).await.unwrap(),
+        escrow_balance - PRICE
+    );
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.creator.pubkey())
+            .await
+            .unwrap(),
+        creator_balance + PRICE / 20
+    );
+}
+
+#[tokio::test]
+async fn execute_sale_of_unsold_leaf_resold_by_another_seller() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = list(&mut ctx, false).await;
+    bid(&mut ctx, &market, PRICE).await;
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    let seller_trade_state = find_trade_state(
+        &market.seller.pubkey(),
+        &auction_house,
+        &market.leaf.asset_id,
+        PRICE,
+    );
+    let trade_state_rent = ctx
+        .banks_client
+        .get_balance(seller_trade_state)
+        .await
+        .unwrap();
+    let seller_balance = ctx
+        .banks_client
+        .get_balance(market.seller.pubkey())
+        .await
+        .unwrap();
+    let creator_balance = ctx
+        .banks_client
+        .get_balance(market.creator.pubkey())
+        .await
+        .unwrap();
+
+    let execute_sale_instruction = execute_sale_instruction(&mut ctx, &market, PRICE).await;
+    process_instructions(&mut ctx, &[execute_sale_instruction], &[])
+        .await
+        .unwrap();
+
+    // the seller is no creator, so the sale is no primary sale even though the
+    // leaf was never sold: the creator only takes royalties
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.seller.pubkey())
+            .await
+            .unwrap(),
+        seller_balance + trade_state_rent + PRICE - PRICE / 20 - PRICE / 20
+    );
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.creator.pubkey())
+            .await
+            .unwrap(),
+        creator_balance + PRICE / 20
+    );
+}
+
+#[tokio::test]
+async fn reject_execute_sale_with_bid_below_ask() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = list(&mut ctx, true).await;
+    bid(&mut ctx, &market, PRICE / 2).await;
+
+    let execute_sale_instruction = execute_sale_instruction(&mut ctx, &market, PRICE / 2).await;
+    let result = process_instructions(&mut ctx, &[execute_sale_instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::BidBelowAsk.into());
+}
@@ -0,0 +1,164 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::DepositToFeeAccountInstruction as DepositToFeeAccountAccounts,
+    accounts::WithdrawFromFeeAccountInstruction as WithdrawFromFeeAccountAccounts,
+    instruction::DepositToFeeAccount as DepositToFeeAccountInstruction,
+    instruction::WithdrawFromFeeAccount as WithdrawFromFeeAccountInstruction, AuctionHouseV2Errors,
+    RoyaltyMode, RoyaltyPolicy, ID,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey, sysvar};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{rent::Rent, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_auction_house_instruction,
+    find_auction_house, process_instructions,
+};
+
+pub mod utils;
+
+const ROYALTY_POLICY: RoyaltyPolicy = RoyaltyPolicy {
+    mode: RoyaltyMode::Enforced,
+    min_basis_points: 0,
+    max_basis_points: 9500,
+};
+
+fn deposit_to_fee_account_instruction(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = find_auction_house(authority, &native_mint::id());
+    Instruction {
+        program_id: ID,
+        accounts: DepositToFeeAccountAccounts {
+            auction_house: accounts.auction_house,
+            auction_house_authority: *authority,
+            treasury_mint: native_mint::id(),
+            auction_house_fee_account: accounts.fee_account,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: DepositToFeeAccountInstruction { amount }.data(),
+    }
+}
+
+fn withdraw_from_fee_account_instruction(
+    authority: &Pubkey,
+    fee_withdrawal_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = find_auction_house(authority, &native_mint::id());
+    Instruction {
+        program_id: ID,
+        accounts: WithdrawFromFeeAccountAccounts {
+            auction_house: accounts.auction_house,
+            authority: *authority,
+            treasury_mint: native_mint::id(),
+            auction_house_fee_account: accounts.fee_account,
+            fee_withdrawal_account: *fee_withdrawal_account,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: WithdrawFromFeeAccountInstruction { amount }.data(),
+    }
+}
+
+/// Creates a native auction house of the payer withdrawing its fee account
+/// to a new account, and deposits `lamports` to the fee account
+async fn fund_fee_account(ctx: &mut ProgramTestContext, lamports: u64) -> Pubkey {
+    let payer = ctx.payer.pubkey();
+    let fee_withdrawal_account = Pubkey::new_unique();
+    process_instructions(
+        ctx,
+        &[
+            create_auction_house_instruction(
+                &payer,
+                &native_mint::id(),
+                &payer,
+                &payer,
+                &fee_withdrawal_account,
+                500,
+                ROYALTY_POLICY,
+            ),
+            deposit_to_fee_account_instruction(&payer, &payer, lamports),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+    fee_withdrawal_account
+}
+
+#[tokio::test]
+async fn deposit_to_and_withdraw_from_fee_account() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    let fee_withdrawal_account = fund_fee_account(&mut ctx, 1_000_000_000).await;
+    let fee_account = find_auction_house(&payer, &native_mint::id()).fee_account;
+    let deposited = ctx.banks_client.get_balance(fee_account).await.unwrap();
+    assert_eq!(deposited, 1_000_000_000);
+
+    process_instructions(
+        &mut ctx,
+        &[withdraw_from_fee_account_instruction(
+            &payer,
+            &fee_withdrawal_account,
+            400_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let withdrawn = ctx
+        .banks_client
+        .get_balance(fee_withdrawal_account)
+        .await
+        .unwrap();
+    assert_eq!(withdrawn, 400_000_000);
+    let fee_account = ctx.banks_client.get_balance(fee_account).await.unwrap();
+    assert_eq!(fee_account, 600_000_000);
+}
+
+#[tokio::test]
+async fn reject_fee_account_withdrawal_below_rent_exempt() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    let fee_withdrawal_account = fund_fee_account(&mut ctx, 1_000_000_000).await;
+
+    let withdrawable = 1_000_000_000 - Rent::default().minimum_balance(0);
+    let result = process_instructions(
+        &mut ctx,
+        &[withdraw_from_fee_account_instruction(
+            &payer,
+            &fee_withdrawal_account,
+            withdrawable + 1,
+        )],
+        &[],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::NotEnoughFunds.into());
+}
+
+#[tokio::test]
+async fn reject_fee_account_withdrawal_to_another_account() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    fund_fee_account(&mut ctx, 1_000_000_000).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[withdraw_from_fee_account_instruction(
+            &payer,
+            &Pubkey::new_unique(),
+            1_000,
+        )],
+        &[],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::ConstraintHasOne.into());
+}
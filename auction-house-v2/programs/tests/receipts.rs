@@ -0,0 +1,535 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{system_program, AccountDeserialize, InstructionData, ToAccountMetas};
+use auction_house_v2::{
+    accounts::CloseExpiredBidInstruction as CloseExpiredBidAccounts,
+    accounts::PrintBidReceiptInstruction as PrintBidReceiptAccounts,
+    accounts::PrintListingReceiptInstruction as PrintListingReceiptAccounts,
+    accounts::PrintPurchaseReceiptInstruction as PrintPurchaseReceiptAccounts,
+    instruction::CloseExpiredBid as CloseExpiredBidInstruction,
+    instruction::PrintBidReceipt as PrintBidReceiptInstruction,
+    instruction::PrintListingReceipt as PrintListingReceiptInstruction,
+    instruction::PrintPurchaseReceipt as PrintPurchaseReceiptInstruction, AuctionHouseV2Errors,
+    BidReceipt, ListingReceipt, PurchaseReceipt, TradeSide, TradeState, ID,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::signer::Signer;
+use utils::{
+    add_program_account, assert_custom_error, auction_house_program_test, process_instructions,
+    BID_RECEIPT, BID_RECEIPT_SIZE, LISTING_RECEIPT, LISTING_RECEIPT_SIZE, PURCHASE_RECEIPT,
+    TRADE_STATE_SIZE,
+};
+
+pub mod utils;
+
+const CREATED_AT: i64 = 1_700_000_000;
+const PURCHASED_AT: i64 = 1_700_000_500;
+
+fn trade_state(side: TradeSide, expiry: Option<i64>) -> TradeState {
+    TradeState {
+        wallet: Pubkey::new_unique(),
+        auction_house: Pubkey::new_unique(),
+        asset_id: Pubkey::new_unique(),
+        merkle_tree: Pubkey::new_unique(),
+        leaf_index: 7,
+        price: 1_000_000,
+        side,
+        created_at: CREATED_AT,
+        bump: 255,
+        expiry,
+        allowed_buyer: None,
+    }
+}
+
+fn find_receipt(prefix: &str, trade_state_address: &Pubkey, trade_state: &TradeState) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            prefix.as_bytes(),
+            trade_state_address.as_ref(),
+            trade_state.created_at.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn print_listing_receipt_instruction(
+    seller_trade_state: &Pubkey,
+    receipt: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: PrintListingReceiptAccounts {
+            seller_trade_state: *seller_trade_state,
+            receipt: *receipt,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: PrintListingReceiptInstruction {}.data(),
+    }
+}
+
+fn print_bid_receipt_instruction(
+    buyer_trade_state: &Pubkey,
+    receipt: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: PrintBidReceiptAccounts {
+            buyer_trade_state: *buyer_trade_state,
+            receipt: *receipt,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: PrintBidReceiptInstruction {}.data(),
+    }
+}
+
+fn close_expired_bid_instruction(
+    buyer_trade_state: &Pubkey,
+    wallet: &Pubkey,
+    bid_receipt: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: CloseExpiredBidAccounts {
+            buyer_trade_state: *buyer_trade_state,
+            wallet: *wallet,
+            bid_receipt: *bid_receipt,
+        }
+        .to_account_metas(None),
+        data: CloseExpiredBidInstruction {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn print_listing_receipt() {
+    let mut program = auction_house_program_test();
+    let seller_trade_state_address = Pubkey::new_unique();
+    let seller_trade_state = trade_state(TradeSide::Ask, None);
+    add_program_account(
+        &mut program,
+        seller_trade_state_address,
+        &seller_trade_state,
+        TRADE_STATE_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let receipt = find_receipt(
+        LISTING_RECEIPT,
+        &seller_trade_state_address,
+        &seller_trade_state,
+    );
+
+    let instruction =
+        print_listing_receipt_instruction(&seller_trade_state_address, &receipt, &payer_pubkey);
+    process_instructions(&mut ctx, &[instruction], &[])
+        .await
+        .unwrap();
+
+    let receipt_account = ctx
+        .banks_client
+        .get_account(receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt_data = ListingReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    assert_eq!(receipt_data.trade_state, seller_trade_state_address);
+    assert_eq!(receipt_data.seller, seller_trade_state.wallet);
+    assert_eq!(receipt_data.asset_id, seller_trade_state.asset_id);
+    assert_eq!(receipt_data.price, seller_trade_state.price);
+    assert_eq!(receipt_data.created_at, CREATED_AT);
+    assert_eq!(receipt_data.canceled_at, None);
+    assert_eq!(receipt_data.purchased_at, None);
+    assert_eq!(receipt_data.purchase_receipt, None);
+}
+
+#[tokio::test]
+async fn reject_listing_receipt_for_bid() {
+    let mut program = auction_house_program_test();
+    let buyer_trade_state_address = Pubkey::new_unique();
+    let buyer_trade_state = trade_state(TradeSide::Bid, None);
+    add_program_account(
+        &mut program,
+        buyer_trade_state_address,
+        &buyer_trade_state,
+        TRADE_STATE_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let receipt = find_receipt(
+        LISTING_RECEIPT,
+        &buyer_trade_state_address,
+        &buyer_trade_state,
+    );
+
+    let instruction =
+        print_listing_receipt_instruction(&buyer_trade_state_address, &receipt, &payer_pubkey);
+    let result = process_instructions(&mut ctx, &[instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidSellerTradeState.into());
+}
+
+#[tokio::test]
+async fn print_bid_receipt() {
+    let mut program = auction_house_program_test();
+    let buyer_trade_state_address = Pubkey::new_unique();
+    let buyer_trade_state = trade_state(TradeSide::Bid, None);
+    add_program_account(
+        &mut program,
+        buyer_trade_state_address,
+        &buyer_trade_state,
+        TRADE_STATE_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let receipt = find_receipt(BID_RECEIPT, &buyer_trade_state_address, &buyer_trade_state);
+
+    let instruction =
+        print_bid_receipt_instruction(&buyer_trade_state_address, &receipt, &payer_pubkey);
+    process_instructions(&mut ctx, &[instruction], &[])
+        .await
+        .unwrap();
+
+    let receipt_account = ctx
+        .banks_client
+        .get_account(receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt_data = BidReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    assert_eq!(receipt_data.trade_state, buyer_trade_state_address);
+    assert_eq!(receipt_data.buyer, buyer_trade_state.wallet);
+    assert_eq!(receipt_data.price, buyer_trade_state.price);
+    assert_eq!(receipt_data.created_at, CREATED_AT);
+    assert_eq!(receipt_data.canceled_at, None);
+}
+
+#[tokio::test]
+async fn reject_bid_receipt_for_listing() {
+    let mut program = auction_house_program_test();
+    let seller_trade_state_address = Pubkey::new_unique();
+    let seller_trade_state = trade_state(TradeSide::Ask, None);
+    add_program_account(
+        &mut program,
+        seller_trade_state_address,
+        &seller_trade_state,
+        TRADE_STATE_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let receipt = find_receipt(
+        BID_RECEIPT,
+        &seller_trade_state_address,
+        &seller_trade_state,
+    );
+
+    let instruction =
+        print_bid_receipt_instruction(&seller_trade_state_address, &receipt, &payer_pubkey);
+    let result = process_instructions(&mut ctx, &[instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidBuyerTradeState.into());
+}
+
+#[tokio::test]
+async fn close_expired_bid_cancels_its_receipt() {
+    let mut program = auction_house_program_test();
+    let buyer_trade_state_address = Pubkey::new_unique();
+    let buyer_trade_state = trade_state(TradeSide::Bid, Some(CREATED_AT + 60));
+    add_program_account(
+        &mut program,
+        buyer_trade_state_address,
+        &buyer_trade_state,
+        TRADE_STATE_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let receipt = find_receipt(BID_RECEIPT, &buyer_trade_state_address, &buyer_trade_state);
+
+    let print_instruction =
+        print_bid_receipt_instruction(&buyer_trade_state_address, &receipt, &payer_pubkey);
+    let close_instruction = close_expired_bid_instruction(
+        &buyer_trade_state_address,
+        &buyer_trade_state.wallet,
+        &receipt,
+    );
+    process_instructions(&mut ctx, &[print_instruction, close_instruction], &[])
+        .await
+        .unwrap();
+
+    let receipt_account = ctx
+        .banks_client
+        .get_account(receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt_data = BidReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    assert!(receipt_data.canceled_at.is_some());
+    assert!(ctx
+        .banks_client
+        .get_account(buyer_trade_state_address)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn reject_close_expired_bid_without_its_receipt() {
+    let mut program = auction_house_program_test();
+    let buyer_trade_state_address = Pubkey::new_unique();
+    let buyer_trade_state = trade_state(TradeSide::Bid, Some(CREATED_AT + 60));
+    add_program_account(
+        &mut program,
+        buyer_trade_state_address,
+        &buyer_trade_state,
+        TRADE_STATE_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+
+    // any other account than the receipt of the order is rejected
+    let close_instruction = close_expired_bid_instruction(
+        &buyer_trade_state_address,
+        &buyer_trade_state.wallet,
+        &Pubkey::new_unique(),
+    );
+    let result = process_instructions(&mut ctx, &[close_instruction], &[]).await;
+    assert_custom_error(result, ErrorCode::ConstraintSeeds.into());
+}
+
+#[tokio::test]
+async fn reject_close_of_unexpired_bid() {
+    let mut program = auction_house_program_test();
+    let buyer_trade_state_address = Pubkey::new_unique();
+    let buyer_trade_state = trade_state(TradeSide::Bid, None);
+    add_program_account(
+        &mut program,
+        buyer_trade_state_address,
+        &buyer_trade_state,
+        TRADE_STATE_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let receipt = find_receipt(BID_RECEIPT, &buyer_trade_state_address, &buyer_trade_state);
+
+    let close_instruction = close_expired_bid_instruction(
+        &buyer_trade_state_address,
+        &buyer_trade_state.wallet,
+        &receipt,
+    );
+    let result = process_instructions(&mut ctx, &[close_instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::OrderNotExpired.into());
+}
+
+/// Listing and bid receipts of a sale, stamped with `purchase_receipt` when given
+fn sale_receipts(purchase_receipt: Option<Pubkey>) -> (ListingReceipt, BidReceipt) {
+    let listing_receipt = ListingReceipt {
+        trade_state: Pubkey::new_unique(),
+        auction_house: Pubkey::new_unique(),
+        seller: Pubkey::new_unique(),
+        asset_id: Pubkey::new_unique(),
+        merkle_tree: Pubkey::new_unique(),
+        leaf_index: 3,
+        price: 2_000_000,
+        created_at: CREATED_AT,
+        canceled_at: None,
+        purchased_at: purchase_receipt.map(|_| PURCHASED_AT),
+        purchase_receipt,
+        bump: 255,
+    };
+    let bid_receipt = BidReceipt {
+        trade_state: Pubkey::new_unique(),
+        auction_house: listing_receipt.auction_house,
+        buyer: Pubkey::new_unique(),
+        asset_id: listing_receipt.asset_id,
+        merkle_tree: listing_receipt.merkle_tree,
+        leaf_index: listing_receipt.leaf_index,
+        price: listing_receipt.price,
+        created_at: CREATED_AT,
+        canceled_at: None,
+        purchased_at: purchase_receipt.map(|_| PURCHASED_AT),
+        purchase_receipt,
+        bump: 255,
+    };
+    (listing_receipt, bid_receipt)
+}
+
+fn find_purchase_receipt(listing_receipt: &Pubkey, bid_receipt: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT.as_bytes(),
+            listing_receipt.as_ref(),
+            bid_receipt.as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn print_purchase_receipt_instruction(
+    listing_receipt: &Pubkey,
+    bid_receipt: &Pubkey,
+    purchase_receipt: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: PrintPurchaseReceiptAccounts {
+            listing_receipt: *listing_receipt,
+            bid_receipt: *bid_receipt,
+            purchase_receipt: *purchase_receipt,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: PrintPurchaseReceiptInstruction {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn print_purchase_receipt_at_sale_time() {
+    let mut program = auction_house_program_test();
+    let listing_receipt_address = Pubkey::new_unique();
+    let bid_receipt_address = Pubkey::new_unique();
+    let purchase_receipt = find_purchase_receipt(&listing_receipt_address, &bid_receipt_address);
+    let (listing_receipt, bid_receipt) = sale_receipts(Some(purchase_receipt));
+    add_program_account(
+        &mut program,
+        listing_receipt_address,
+        &listing_receipt,
+        LISTING_RECEIPT_SIZE,
+    );
+    add_program_account(
+        &mut program,
+        bid_receipt_address,
+        &bid_receipt,
+        BID_RECEIPT_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+
+    let instruction = print_purchase_receipt_instruction(
+        &listing_receipt_address,
+        &bid_receipt_address,
+        &purchase_receipt,
+        &payer_pubkey,
+    );
+    process_instructions(&mut ctx, &[instruction], &[])
+        .await
+        .unwrap();
+
+    let purchase_receipt_account = ctx
+        .banks_client
+        .get_account(purchase_receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let purchase_receipt_data =
+        PurchaseReceipt::try_deserialize(&mut purchase_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(purchase_receipt_data.seller, listing_receipt.seller);
+    assert_eq!(purchase_receipt_data.buyer, bid_receipt.buyer);
+    assert_eq!(purchase_receipt_data.asset_id, listing_receipt.asset_id);
+    assert_eq!(purchase_receipt_data.price, listing_receipt.price);
+    assert_eq!(purchase_receipt_data.created_at, PURCHASED_AT);
+}
+
+#[tokio::test]
+async fn reject_purchase_receipt_of_unsold_orders() {
+    let mut program = auction_house_program_test();
+    let listing_receipt_address = Pubkey::new_unique();
+    let bid_receipt_address = Pubkey::new_unique();
+    let (listing_receipt, bid_receipt) = sale_receipts(None);
+    add_program_account(
+        &mut program,
+        listing_receipt_address,
+        &listing_receipt,
+        LISTING_RECEIPT_SIZE,
+    );
+    add_program_account(
+        &mut program,
+        bid_receipt_address,
+        &bid_receipt,
+        BID_RECEIPT_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let purchase_receipt = find_purchase_receipt(&listing_receipt_address, &bid_receipt_address);
+
+    let instruction = print_purchase_receipt_instruction(
+        &listing_receipt_address,
+        &bid_receipt_address,
+        &purchase_receipt,
+        &payer_pubkey,
+    );
+    let result = process_instructions(&mut ctx, &[instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::ReceiptMismatch.into());
+}
+
+#[tokio::test]
+async fn print_purchase_receipt_without_bid_receipt() {
+    let mut program = auction_house_program_test();
+    let listing_receipt_address = Pubkey::new_unique();
+    let buyer = Pubkey::new_unique();
+    let purchase_receipt = find_purchase_receipt(&listing_receipt_address, &buyer);
+    let (listing_receipt, _) = sale_receipts(Some(purchase_receipt));
+    add_program_account(
+        &mut program,
+        listing_receipt_address,
+        &listing_receipt,
+        LISTING_RECEIPT_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+
+    // the buyer wallet stands in for the bid receipt that was never printed
+    let instruction = print_purchase_receipt_instruction(
+        &listing_receipt_address,
+        &buyer,
+        &purchase_receipt,
+        &payer_pubkey,
+    );
+    process_instructions(&mut ctx, &[instruction], &[])
+        .await
+        .unwrap();
+
+    let purchase_receipt_account = ctx
+        .banks_client
+        .get_account(purchase_receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let purchase_receipt_data =
+        PurchaseReceipt::try_deserialize(&mut purchase_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(purchase_receipt_data.seller, listing_receipt.seller);
+    assert_eq!(purchase_receipt_data.buyer, buyer);
+    assert_eq!(purchase_receipt_data.price, listing_receipt.price);
+    assert_eq!(purchase_receipt_data.created_at, PURCHASED_AT);
+}
+
+#[tokio::test]
+async fn reject_purchase_receipt_for_another_buyer() {
+    let mut program = auction_house_program_test();
+    let listing_receipt_address = Pubkey::new_unique();
+    let buyer = Pubkey::new_unique();
+    let impostor = Pubkey::new_unique();
+    let (listing_receipt, _) = sale_receipts(Some(find_purchase_receipt(
+        &listing_receipt_address,
+        &buyer,
+    )));
+    add_program_account(
+        &mut program,
+        listing_receipt_address,
+        &listing_receipt,
+        LISTING_RECEIPT_SIZE,
+    );
+    let mut ctx = program.start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+
+    let instruction = print_purchase_receipt_instruction(
+        &listing_receipt_address,
+        &impostor,
+        &find_purchase_receipt(&listing_receipt_address, &impostor),
+        &payer_pubkey,
+    );
+    let result = process_instructions(&mut ctx, &[instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::ReceiptMismatch.into());
+}
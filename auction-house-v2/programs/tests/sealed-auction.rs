@@ -0,0 +1,758 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::CancelSealedAuctionInstruction as CancelSealedAuctionAccounts,
+    accounts::CommitSealedBidInstruction as CommitSealedBidAccounts,
+    accounts::CreateSealedAuctionInstruction as CreateSealedAuctionAccounts,
+    accounts::RevealSealedBidInstruction as RevealSealedBidAccounts,
+    accounts::SettleSealedAuctionInstruction as SettleSealedAuctionAccounts,
+    accounts::WithdrawSealedBidInstruction as WithdrawSealedBidAccounts,
+    instruction::CancelSealedAuction as CancelSealedAuctionInstruction,
+    instruction::CommitSealedBid as CommitSealedBidInstruction,
+    instruction::CreateSealedAuction as CreateSealedAuctionInstruction,
+    instruction::RevealSealedBid as RevealSealedBidInstruction,
+    instruction::SettleSealedAuction as SettleSealedAuctionInstruction,
+    instruction::WithdrawSealedBid as WithdrawSealedBidInstruction, AuctionHouseV2Errors,
+    SealedAuction, SealedBid, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    keccak,
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    current_timestamp, fetch_program_account, find_auction_house, find_escrow,
+    find_program_as_signer, mint_market_leaf, process_instructions, warp_to_timestamp, Leaf, Tree,
+    AUCTION_SETTLEMENT_GRACE_PERIOD, SEALED_AUCTION, SEALED_BID,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+const DEPOSIT: u64 = 2 * PRICE;
+const PHASE_DURATION: i64 = 60 * 60;
+
+struct Market {
+    authority: Keypair,
+    seller: Keypair,
+    creator: Keypair,
+    tree: Tree,
+    leaf: Leaf,
+    /// Reveal end of the sealed auction once started
+    reveal_end_time: i64,
+}
+
+/// Mints a leaf of the seller paying 5% royalties on a native auction house
+/// taking 5% fees
+async fn create_market(ctx: &mut ProgramTestContext) -> Market {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [seller, creator] = create_wallets(ctx, 1_000_000_000).await;
+    let (tree, leaf) =
+        mint_market_leaf(ctx, &seller.pubkey(), &seller.pubkey(), &creator, true).await;
+    Market {
+        authority,
+        seller,
+        creator,
+        tree,
+        leaf,
+        reveal_end_time: 0,
+    }
+}
+
+fn find_sealed_auction(market: &Market) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    Pubkey::find_program_address(
+        &[
+            SEALED_AUCTION.as_ref(),
+            auction_house.as_ref(),
+            market.leaf.asset_id.as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn find_sealed_bid(market: &Market, bidder: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SEALED_BID.as_ref(),
+            find_sealed_auction(market).as_ref(),
+            bidder.as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn find_bid_escrow(market: &Market, bidder: &Pubkey) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    find_escrow(&auction_house, &find_sealed_bid(market, bidder))
+}
+
+fn create_sealed_auction_instruction(
+    market: &Market,
+    commit_end_time: i64,
+    reveal_end_time: i64,
+) -> Instruction {
+    let mut account_metas = CreateSealedAuctionAccounts {
+        auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+            .auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        tree_config: market.tree.tree_config,
+        owner: market.seller.pubkey(),
+        merkle_tree: market.tree.merkle_tree,
+        previous_leaf_delegate: market.leaf.delegate,
+        sealed_auction: find_sealed_auction(market),
+        asset_id: market.leaf.asset_id,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: CreateSealedAuctionInstruction {
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+            index: market.leaf.index,
+            commit_end_time,
+            reveal_end_time,
+            reserve_price: PRICE,
+            second_price: true,
+        }
+        .data(),
+    }
+}
+
+/// Starts a second price auction of the market leaf whose commit and reveal
+/// phases both last `PHASE_DURATION` seconds
+async fn start_sealed_auction(ctx: &mut ProgramTestContext, market: &mut Market) {
+    let commit_end_time = current_timestamp(ctx).await + PHASE_DURATION;
+    market.reveal_end_time = commit_end_time + PHASE_DURATION;
+    process_instructions(
+        ctx,
+        &[create_sealed_auction_instruction(
+            market,
+            commit_end_time,
+            market.reveal_end_time,
+        )],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+    market
+        .tree
+        .delegate(&mut market.leaf, &find_program_as_signer());
+}
+
+/// Salt of the bids of `bidder`
+fn salt(bidder: &Pubkey) -> [u8; 32] {
+    bidder.to_bytes()
+}
+
+fn commitment(market: &Market, bidder: &Pubkey, price: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &price.to_le_bytes(),
+        &salt(bidder),
+        bidder.as_ref(),
+        find_sealed_auction(market).as_ref(),
+    ])
+    .to_bytes()
+}
+
+fn commit_sealed_bid_instruction(market: &Market, bidder: &Pubkey, price: u64) -> Instruction {
+    commit_sealed_bid_instruction_with_commitment(market, bidder, commitment(market, bidder, price))
+}
+
+fn commit_sealed_bid_instruction_with_commitment(
+    market: &Market,
+    bidder: &Pubkey,
+    commitment: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: CommitSealedBidAccounts {
+            auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+                .auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            bidder: *bidder,
+            payment_account: *bidder,
+            sealed_auction: find_sealed_auction(market),
+            sealed_bid: find_sealed_bid(market, bidder),
+            bid_escrow: find_bid_escrow(market, bidder),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: CommitSealedBidInstruction {
+            commitment,
+            deposit: DEPOSIT,
+        }
+        .data(),
+    }
+}
+
+fn reveal_sealed_bid_instruction(
+    market: &Market,
+    bidder: &Pubkey,
+    price: u64,
+    salt: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: RevealSealedBidAccounts {
+            bidder: *bidder,
+            sealed_auction: find_sealed_auction(market),
+            sealed_bid: find_sealed_bid(market, bidder),
+        }
+        .to_account_metas(None),
+        data: RevealSealedBidInstruction { price, salt }.data(),
+    }
+}
+
+fn withdraw_sealed_bid_instruction(market: &Market, bidder: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: WithdrawSealedBidAccounts {
+            auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+                .auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            bidder: *bidder,
+            receipt_account: *bidder,
+            sealed_auction: find_sealed_auction(market),
+            sealed_bid: find_sealed_bid(market, bidder),
+            bid_escrow: find_bid_escrow(market, bidder),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: WithdrawSealedBidInstruction {}.data(),
+    }
+}
+
+fn settle_sealed_auction_instruction(market: &Market, buyer: &Pubkey) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let mut account_metas = SettleSealedAuctionAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        tree_config: market.tree.tree_config,
+        seller: market.seller.pubkey(),
+        seller_receipt_account: market.seller.pubkey(),
+        merkle_tree: market.tree.merkle_tree,
+        sealed_auction: find_sealed_auction(market),
+        buyer: *buyer,
+        buyer_receipt_account: *buyer,
+        sealed_bid: find_sealed_bid(market, buyer),
+        bid_escrow: find_bid_escrow(market, buyer),
+        auction_house_fee_account: accounts.fee_account,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.push(AccountMeta::new(market.creator.pubkey(), false));
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: SettleSealedAuctionInstruction {
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+            metadata: market.leaf.metadata_args(),
+        }
+        .data(),
+    }
+}
+
+fn cancel_sealed_auction_instruction(market: &Market) -> Instruction {
+    let mut account_metas = CancelSealedAuctionAccounts {
+        sealed_auction: find_sealed_auction(market),
+        seller: market.seller.pubkey(),
+        tree_config: market.tree.tree_config,
+        merkle_tree: market.tree.merkle_tree,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(market.tree.proof(market.leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: CancelSealedAuctionInstruction {
+            root: market.tree.root(),
+            data_hash: market.leaf.data_hash(),
+            creator_hash: market.leaf.creator_hash(),
+            nonce: market.leaf.nonce,
+        }
+        .data(),
+    }
+}
+
+/// Commits the `bids` of each bidder, then reveals them once the commit phase
+/// has closed
+async fn place_sealed_bids(
+    ctx: &mut ProgramTestContext,
+    market: &Market,
+    bids: &[(&Keypair, u64)],
+) {
+    for (bidder, price) in bids {
+        process_instructions(
+            ctx,
+            &[commit_sealed_bid_instruction(
+                market,
+                &bidder.pubkey(),
+                *price,
+            )],
+            &[bidder],
+        )
+        .await
+        .unwrap();
+    }
+    warp_to_timestamp(ctx, market.reveal_end_time - PHASE_DURATION).await;
+    for (bidder, price) in bids {
+        process_instructions(
+            ctx,
+            &[reveal_sealed_bid_instruction(
+                market,
+                &bidder.pubkey(),
+                *price,
+                salt(&bidder.pubkey()),
+            )],
+            &[bidder],
+        )
+        .await
+        .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn create_sealed_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+
+    start_sealed_auction(&mut ctx, &mut market).await;
+
+    let sealed_auction =
+        fetch_program_account::<SealedAuction>(&mut ctx, find_sealed_auction(&market)).await;
+    assert_eq!(sealed_auction.seller, market.seller.pubkey());
+    assert_eq!(sealed_auction.asset_id, market.leaf.asset_id);
+    assert_eq!(
+        sealed_auction.commit_end_time,
+        market.reveal_end_time - PHASE_DURATION
+    );
+    assert_eq!(sealed_auction.reveal_end_time, market.reveal_end_time);
+    assert_eq!(sealed_auction.reserve_price, PRICE);
+    assert!(sealed_auction.second_price);
+}
+
+#[tokio::test]
+async fn reject_sealed_auction_revealing_before_commit_end() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let commit_end_time = current_timestamp(&mut ctx).await + PHASE_DURATION;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[create_sealed_auction_instruction(
+            &market,
+            commit_end_time,
+            commit_end_time,
+        )],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidAuctionTimes.into());
+}
+
+#[tokio::test]
+async fn commit_sealed_bid() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+
+    process_instructions(
+        &mut ctx,
+        &[commit_sealed_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            PRICE,
+        )],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+
+    let sealed_bid =
+        fetch_program_account::<SealedBid>(&mut ctx, find_sealed_bid(&market, &bidder.pubkey()))
+            .await;
+    assert_eq!(sealed_bid.bidder, bidder.pubkey());
+    assert_eq!(sealed_bid.deposit, DEPOSIT);
+    assert_eq!(sealed_bid.revealed_price, None);
+    let escrow_balance = ctx
+        .banks_client
+        .get_balance(find_bid_escrow(&market, &bidder.pubkey()))
+        .await
+        .unwrap();
+    assert!(escrow_balance >= DEPOSIT);
+}
+
+#[tokio::test]
+async fn reject_sealed_bid_committed_after_commit_end() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    warp_to_timestamp(&mut ctx, market.reveal_end_time - PHASE_DURATION).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[commit_sealed_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            PRICE,
+        )],
+        &[&bidder],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::AuctionNotActive.into());
+}
+
+#[tokio::test]
+async fn reveal_sealed_bid() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [first_bidder, second_bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+
+    place_sealed_bids(
+        &mut ctx,
+        &market,
+        &[(&first_bidder, PRICE), (&second_bidder, PRICE + PRICE / 2)],
+    )
+    .await;
+
+    let sealed_auction =
+        fetch_program_account::<SealedAuction>(&mut ctx, find_sealed_auction(&market)).await;
+    assert_eq!(sealed_auction.highest_bidder, Some(second_bidder.pubkey()));
+    assert_eq!(sealed_auction.highest_bid, PRICE + PRICE / 2);
+    assert_eq!(sealed_auction.clearing_price(), PRICE);
+}
+
+#[tokio::test]
+async fn reject_sealed_bid_revealed_with_another_salt() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[commit_sealed_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            PRICE,
+        )],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+    warp_to_timestamp(&mut ctx, market.reveal_end_time - PHASE_DURATION).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[reveal_sealed_bid_instruction(
+            &market,
+            &bidder.pubkey(),
+            PRICE,
+            [0; 32],
+        )],
+        &[&bidder],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidCommitment.into());
+}
+
+#[tokio::test]
+async fn reject_sealed_bid_revealed_with_a_copied_commitment() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [victim, attacker] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[
+            commit_sealed_bid_instruction(&market, &victim.pubkey(), PRICE),
+            commit_sealed_bid_instruction_with_commitment(
+                &market,
+                &attacker.pubkey(),
+                commitment(&market, &victim.pubkey(), PRICE),
+            ),
+        ],
+        &[&victim, &attacker],
+    )
+    .await
+    .unwrap();
+    warp_to_timestamp(&mut ctx, market.reveal_end_time - PHASE_DURATION).await;
+
+    // the commitment binds the victim, so the attacker can't reveal it even
+    // with the price and salt of the victim
+    let result = process_instructions(
+        &mut ctx,
+        &[reveal_sealed_bid_instruction(
+            &market,
+            &attacker.pubkey(),
+            PRICE,
+            salt(&victim.pubkey()),
+        )],
+        &[&attacker],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidCommitment.into());
+}
+
+#[tokio::test]
+async fn withdraw_losing_sealed_bid() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [first_bidder, second_bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    place_sealed_bids(
+        &mut ctx,
+        &market,
+        &[(&first_bidder, PRICE), (&second_bidder, PRICE + PRICE / 2)],
+    )
+    .await;
+    warp_to_timestamp(&mut ctx, market.reveal_end_time).await;
+    let bidder_balance = ctx
+        .banks_client
+        .get_balance(first_bidder.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[withdraw_sealed_bid_instruction(
+            &market,
+            &first_bidder.pubkey(),
+        )],
+        &[&first_bidder],
+    )
+    .await
+    .unwrap();
+
+    let refunded = ctx
+        .banks_client
+        .get_balance(first_bidder.pubkey())
+        .await
+        .unwrap()
+        - bidder_balance;
+    assert!(refunded >= DEPOSIT);
+    let sealed_bid = ctx
+        .banks_client
+        .get_account(find_sealed_bid(&market, &first_bidder.pubkey()))
+        .await
+        .unwrap();
+    assert!(sealed_bid.is_none());
+}
+
+#[tokio::test]
+async fn reject_winning_sealed_bid_withdrawn_before_settlement_deadline() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    place_sealed_bids(&mut ctx, &market, &[(&bidder, PRICE)]).await;
+    warp_to_timestamp(&mut ctx, market.reveal_end_time).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[withdraw_sealed_bid_instruction(&market, &bidder.pubkey())],
+        &[&bidder],
+    )
+    .await;
+    assert_custom_error(
+        result,
+        AuctionHouseV2Errors::WinningBidCannotBeWithdrawn.into(),
+    );
+}
+
+#[tokio::test]
+async fn withdraw_winning_sealed_bid_after_settlement_deadline() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    place_sealed_bids(&mut ctx, &market, &[(&bidder, PRICE)]).await;
+    warp_to_timestamp(
+        &mut ctx,
+        market.reveal_end_time + AUCTION_SETTLEMENT_GRACE_PERIOD,
+    )
+    .await;
+
+    process_instructions(
+        &mut ctx,
+        &[withdraw_sealed_bid_instruction(&market, &bidder.pubkey())],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+
+    let sealed_auction =
+        fetch_program_account::<SealedAuction>(&mut ctx, find_sealed_auction(&market)).await;
+    assert_eq!(sealed_auction.highest_bidder, None);
+
+    // without a winner left the seller gets the cnft back
+    process_instructions(
+        &mut ctx,
+        &[cancel_sealed_auction_instruction(&market)],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn settle_sealed_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [first_bidder, second_bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    place_sealed_bids(
+        &mut ctx,
+        &market,
+        &[(&first_bidder, PRICE), (&second_bidder, PRICE + PRICE / 2)],
+    )
+    .await;
+    warp_to_timestamp(&mut ctx, market.reveal_end_time).await;
+    let creator_balance = ctx
+        .banks_client
+        .get_balance(market.creator.pubkey())
+        .await
+        .unwrap();
+    let buyer_balance = ctx
+        .banks_client
+        .get_balance(second_bidder.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[settle_sealed_auction_instruction(
+            &market,
+            &second_bidder.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // the winner pays the second highest price and gets the rest of its deposit back
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.creator.pubkey())
+            .await
+            .unwrap(),
+        creator_balance + PRICE / 20
+    );
+    let refunded = ctx
+        .banks_client
+        .get_balance(second_bidder.pubkey())
+        .await
+        .unwrap()
+        - buyer_balance;
+    assert!(refunded >= DEPOSIT - PRICE);
+    for address in [
+        find_sealed_auction(&market),
+        find_bid_escrow(&market, &second_bidder.pubkey()),
+    ] {
+        let account = ctx.banks_client.get_account(address).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn reject_settle_sealed_auction_before_reveal_end() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    place_sealed_bids(&mut ctx, &market, &[(&bidder, PRICE)]).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[settle_sealed_auction_instruction(&market, &bidder.pubkey())],
+        &[],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::AuctionNotEnded.into());
+}
+
+#[tokio::test]
+async fn cancel_sealed_auction() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    warp_to_timestamp(&mut ctx, market.reveal_end_time).await;
+
+    process_instructions(
+        &mut ctx,
+        &[cancel_sealed_auction_instruction(&market)],
+        &[&market.seller],
+    )
+    .await
+    .unwrap();
+
+    let sealed_auction = ctx
+        .banks_client
+        .get_account(find_sealed_auction(&market))
+        .await
+        .unwrap();
+    assert!(sealed_auction.is_none());
+}
+
+#[tokio::test]
+async fn reject_cancel_sealed_auction_with_winner() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    start_sealed_auction(&mut ctx, &mut market).await;
+    let [bidder] = create_wallets(&mut ctx, 1_000_000_000).await;
+    place_sealed_bids(&mut ctx, &market, &[(&bidder, PRICE)]).await;
+    warp_to_timestamp(&mut ctx, market.reveal_end_time).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[cancel_sealed_auction_instruction(&market)],
+        &[&market.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::AuctionHasBids.into());
+}
@@ -0,0 +1,533 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::AcceptSwapOrderInstruction as AcceptSwapOrderAccounts,
+    accounts::CancelSwapOrderInstruction as CancelSwapOrderAccounts,
+    accounts::CreateSwapOrderInstruction as CreateSwapOrderAccounts,
+    instruction::AcceptSwapOrder as AcceptSwapOrderInstruction,
+    instruction::CancelSwapOrder as CancelSwapOrderInstruction,
+    instruction::CreateSwapOrder as CreateSwapOrderInstruction, AuctionHouseV2Errors, SwapLeafArgs,
+    SwapOrder, SwapTarget, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    fetch_program_account, find_auction_house, find_escrow, find_program_as_signer,
+    mint_market_leaf, process_instructions, Leaf, Tree, SWAP_ORDER,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+
+struct Market {
+    authority: Keypair,
+    maker: Keypair,
+    maker_creator: Keypair,
+    maker_tree: Tree,
+    maker_leaf: Leaf,
+    taker: Keypair,
+    taker_creator: Keypair,
+    taker_tree: Tree,
+    taker_leaf: Leaf,
+}
+
+/// Mints a leaf to the maker and one to the taker, each paying 5% royalties
+/// to its own creator, on a native auction house taking 5% fees. The taker
+/// leaf is delegated to the program as it would be before accepting a swap.
+async fn create_market(ctx: &mut ProgramTestContext) -> Market {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [maker, maker_creator, taker, taker_creator] = create_wallets(ctx, 1_000_000_000).await;
+    let mut leaves = Vec::with_capacity(2);
+    for (owner, delegate, creator) in [
+        (&maker, maker.pubkey(), &maker_creator),
+        (&taker, find_program_as_signer(), &taker_creator),
+    ] {
+        leaves.push(mint_market_leaf(ctx, &owner.pubkey(), &delegate, creator, true).await);
+    }
+    let (taker_tree, taker_leaf) = leaves.pop().unwrap();
+    let (maker_tree, maker_leaf) = leaves.pop().unwrap();
+    Market {
+        authority,
+        maker,
+        maker_creator,
+        maker_tree,
+        maker_leaf,
+        taker,
+        taker_creator,
+        taker_tree,
+        taker_leaf,
+    }
+}
+
+fn find_swap_order(market: &Market) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    Pubkey::find_program_address(
+        &[
+            SWAP_ORDER.as_ref(),
+            market.maker.pubkey().as_ref(),
+            auction_house.as_ref(),
+            market.maker_leaf.asset_id.as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn find_swap_escrow(market: &Market) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    find_escrow(&auction_house, &find_swap_order(market))
+}
+
+fn create_swap_order_instruction(
+    market: &Market,
+    requested: SwapTarget,
+    maker_payment: u64,
+    taker_payment: u64,
+) -> Instruction {
+    let mut account_metas = CreateSwapOrderAccounts {
+        auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+            .auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        tree_config: market.maker_tree.tree_config,
+        maker: market.maker.pubkey(),
+        payment_account: market.maker.pubkey(),
+        merkle_tree: market.maker_tree.merkle_tree,
+        previous_leaf_delegate: market.maker_leaf.delegate,
+        asset_id: market.maker_leaf.asset_id,
+        swap_order: find_swap_order(market),
+        swap_escrow: find_swap_escrow(market),
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        log_wrapper: SPL_NOOP_ID,
+        rent: sysvar::rent::ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(market.maker_tree.proof(market.maker_leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: CreateSwapOrderInstruction {
+            root: market.maker_tree.root(),
+            data_hash: market.maker_leaf.data_hash(),
+            creator_hash: market.maker_leaf.creator_hash(),
+            nonce: market.maker_leaf.nonce,
+            index: market.maker_leaf.index,
+            requested,
+            maker_payment,
+            taker_payment,
+            expiry: None,
+        }
+        .data(),
+    }
+}
+
+/// Offers the maker leaf in exchange for the taker leaf along with the given payments
+async fn create_swap_order(
+    ctx: &mut ProgramTestContext,
+    market: &mut Market,
+    maker_payment: u64,
+    taker_payment: u64,
+) {
+    process_instructions(
+        ctx,
+        &[create_swap_order_instruction(
+            market,
+            SwapTarget::Asset {
+                asset_id: market.taker_leaf.asset_id,
+            },
+            maker_payment,
+            taker_payment,
+        )],
+        &[&market.maker],
+    )
+    .await
+    .unwrap();
+    market
+        .maker_tree
+        .delegate(&mut market.maker_leaf, &find_program_as_signer());
+}
+
+fn swap_leaf_args(tree: &Tree, leaf: &Leaf) -> SwapLeafArgs {
+    SwapLeafArgs {
+        root: tree.root(),
+        data_hash: leaf.data_hash(),
+        creator_hash: leaf.creator_hash(),
+        nonce: leaf.nonce,
+        index: leaf.index,
+    }
+}
+
+/// Accepts the swap order with the taker leaf, `paid_creator` being the
+/// creator of the leaf the payment buys
+fn accept_swap_order_instruction(
+    market: &Market,
+    paid_creator: Option<&Pubkey>,
+    maker_metadata: bool,
+    taker_metadata: bool,
+) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let mut account_metas = AcceptSwapOrderAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        maker: market.maker.pubkey(),
+        maker_receipt_account: market.maker.pubkey(),
+        swap_order: find_swap_order(market),
+        swap_escrow: find_swap_escrow(market),
+        taker: market.taker.pubkey(),
+        taker_payment_account: market.taker.pubkey(),
+        taker_receipt_account: market.taker.pubkey(),
+        maker_tree_config: market.maker_tree.tree_config,
+        maker_merkle_tree: market.maker_tree.merkle_tree,
+        taker_tree_config: market.taker_tree.tree_config,
+        taker_merkle_tree: market.taker_tree.merkle_tree,
+        auction_house_fee_account: accounts.fee_account,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    if let Some(paid_creator) = paid_creator {
+        account_metas.push(AccountMeta::new(*paid_creator, false));
+    }
+    let maker_proof = market.maker_tree.proof(market.maker_leaf.index);
+    let maker_proof_length = maker_proof.len() as u8;
+    account_metas.extend(maker_proof);
+    account_metas.extend(market.taker_tree.proof(market.taker_leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: AcceptSwapOrderInstruction {
+            maker_leaf: swap_leaf_args(&market.maker_tree, &market.maker_leaf),
+            maker_metadata: maker_metadata.then(|| market.maker_leaf.metadata_args()),
+            maker_proof_length,
+            taker_leaf: swap_leaf_args(&market.taker_tree, &market.taker_leaf),
+            taker_metadata: taker_metadata.then(|| market.taker_leaf.metadata_args()),
+        }
+        .data(),
+    }
+}
+
+/// Cancels the swap order, resetting the maker leaf delegate when `reset_delegate`
+fn cancel_swap_order_instruction(
+    market: &Market,
+    maker: &Pubkey,
+    reset_delegate: bool,
+) -> Instruction {
+    let mut account_metas = CancelSwapOrderAccounts {
+        auction_house: find_auction_house(&market.authority.pubkey(), &native_mint::id())
+            .auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        maker: *maker,
+        receipt_account: *maker,
+        swap_order: find_swap_order(market),
+        swap_escrow: find_swap_escrow(market),
+        tree_config: market.maker_tree.tree_config,
+        merkle_tree: market.maker_tree.merkle_tree,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        log_wrapper: SPL_NOOP_ID,
+        rent: sysvar::rent::ID,
+    }
+    .to_account_metas(None);
+    if reset_delegate {
+        account_metas.extend(market.maker_tree.proof(market.maker_leaf.index));
+    }
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: CancelSwapOrderInstruction {
+            maker_leaf: reset_delegate
+                .then(|| swap_leaf_args(&market.maker_tree, &market.maker_leaf)),
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn create_swap_order_with_maker_payment() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+
+    create_swap_order(&mut ctx, &mut market, PRICE, 0).await;
+
+    let swap_order = fetch_program_account::<SwapOrder>(&mut ctx, find_swap_order(&market)).await;
+    assert_eq!(swap_order.maker, market.maker.pubkey());
+    assert_eq!(swap_order.asset_id, market.maker_leaf.asset_id);
+    assert_eq!(
+        swap_order.requested,
+        SwapTarget::Asset {
+            asset_id: market.taker_leaf.asset_id
+        }
+    );
+    assert_eq!(swap_order.maker_payment, PRICE);
+    let escrow_balance = ctx
+        .banks_client
+        .get_balance(find_swap_escrow(&market))
+        .await
+        .unwrap();
+    assert!(escrow_balance >= PRICE);
+}
+
+#[tokio::test]
+async fn reject_swap_order_paid_by_both_sides() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[create_swap_order_instruction(
+            &market,
+            SwapTarget::Asset {
+                asset_id: market.taker_leaf.asset_id,
+            },
+            PRICE,
+            PRICE,
+        )],
+        &[&market.maker],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidSwapPayment.into());
+}
+
+#[tokio::test]
+async fn accept_swap_order_with_maker_payment() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    create_swap_order(&mut ctx, &mut market, PRICE, 0).await;
+    let taker_creator_balance = ctx
+        .banks_client
+        .get_balance(market.taker_creator.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[accept_swap_order_instruction(
+            &market,
+            Some(&market.taker_creator.pubkey()),
+            false,
+            true,
+        )],
+        &[&market.taker],
+    )
+    .await
+    .unwrap();
+
+    // the maker payment buys the taker leaf, royalties go to its creator
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.taker_creator.pubkey())
+            .await
+            .unwrap(),
+        taker_creator_balance + PRICE / 20
+    );
+    for address in [find_swap_order(&market), find_swap_escrow(&market)] {
+        let account = ctx.banks_client.get_account(address).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn accept_swap_order_with_taker_payment() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    create_swap_order(&mut ctx, &mut market, 0, PRICE).await;
+    let maker_creator_balance = ctx
+        .banks_client
+        .get_balance(market.maker_creator.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[accept_swap_order_instruction(
+            &market,
+            Some(&market.maker_creator.pubkey()),
+            true,
+            false,
+        )],
+        &[&market.taker],
+    )
+    .await
+    .unwrap();
+
+    // the taker payment buys the maker leaf, royalties go to its creator
+    assert_eq!(
+        ctx.banks_client
+            .get_balance(market.maker_creator.pubkey())
+            .await
+            .unwrap(),
+        maker_creator_balance + PRICE / 20
+    );
+}
+
+#[tokio::test]
+async fn reject_taker_payment_without_maker_metadata() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    create_swap_order(&mut ctx, &mut market, 0, PRICE).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[accept_swap_order_instruction(
+            &market,
+            Some(&market.maker_creator.pubkey()),
+            false,
+            false,
+        )],
+        &[&market.taker],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::MetadataRequired.into());
+}
+
+#[tokio::test]
+async fn reject_swap_of_another_asset() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    process_instructions(
+        &mut ctx,
+        &[create_swap_order_instruction(
+            &market,
+            SwapTarget::Asset {
+                asset_id: Pubkey::new_unique(),
+            },
+            0,
+            0,
+        )],
+        &[&market.maker],
+    )
+    .await
+    .unwrap();
+    market
+        .maker_tree
+        .delegate(&mut market.maker_leaf, &find_program_as_signer());
+
+    let result = process_instructions(
+        &mut ctx,
+        &[accept_swap_order_instruction(&market, None, false, false)],
+        &[&market.taker],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::SwapAssetMismatch.into());
+}
+
+#[tokio::test]
+async fn cancel_swap_order() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    create_swap_order(&mut ctx, &mut market, PRICE, 0).await;
+
+    process_instructions(
+        &mut ctx,
+        &[cancel_swap_order_instruction(
+            &market,
+            &market.maker.pubkey(),
+            true,
+        )],
+        &[&market.maker],
+    )
+    .await
+    .unwrap();
+
+    for address in [find_swap_order(&market), find_swap_escrow(&market)] {
+        let account = ctx.banks_client.get_account(address).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn cancel_swap_order_of_transferred_leaf() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    create_swap_order(&mut ctx, &mut market, PRICE, 0).await;
+    let [new_owner] = create_wallets(&mut ctx, 1_000_000_000).await;
+    process_instructions(
+        &mut ctx,
+        &[market
+            .maker_tree
+            .transfer_instruction(&market.maker_leaf, &new_owner.pubkey())],
+        &[&market.maker],
+    )
+    .await
+    .unwrap();
+    market
+        .maker_tree
+        .transfer(&mut market.maker_leaf, &new_owner.pubkey());
+    let maker_balance = ctx
+        .banks_client
+        .get_balance(market.maker.pubkey())
+        .await
+        .unwrap();
+
+    // the maker no longer owns the leaf, only the payment is refunded
+    process_instructions(
+        &mut ctx,
+        &[cancel_swap_order_instruction(
+            &market,
+            &market.maker.pubkey(),
+            false,
+        )],
+        &[&market.maker],
+    )
+    .await
+    .unwrap();
+
+    assert!(
+        ctx.banks_client
+            .get_balance(market.maker.pubkey())
+            .await
+            .unwrap()
+            > maker_balance + PRICE
+    );
+    for address in [find_swap_order(&market), find_swap_escrow(&market)] {
+        let account = ctx.banks_client.get_account(address).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn reject_cancel_swap_order_by_another_wallet() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let mut market = create_market(&mut ctx).await;
+    create_swap_order(&mut ctx, &mut market, PRICE, 0).await;
+
+    let result = process_instructions(
+        &mut ctx,
+        &[cancel_swap_order_instruction(
+            &market,
+            &market.taker.pubkey(),
+            true,
+        )],
+        &[&market.taker],
+    )
+    .await;
+    assert_custom_error(
+        result,
+        anchor_lang::error::ErrorCode::ConstraintSeeds.into(),
+    );
+}
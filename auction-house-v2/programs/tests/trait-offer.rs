@@ -0,0 +1,372 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::AcceptTraitOfferInstruction as AcceptTraitOfferAccounts,
+    accounts::CancelTraitOfferInstruction as CancelTraitOfferAccounts,
+    accounts::TraitOfferInstruction as TraitOfferAccounts,
+    instruction::AcceptTraitOffer as AcceptTraitOfferInstruction,
+    instruction::CancelTraitOffer as CancelTraitOfferInstruction,
+    instruction::TraitOffer as TraitOfferInstruction, AuctionHouseV2Errors, TraitOffer, ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    keccak,
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    assert_custom_error, auction_house_program_test, create_market_auction_house, create_wallets,
+    fetch_program_account, find_auction_house, find_escrow, mint_market_leaf, process_instructions,
+    Leaf, Tree, TRAIT_OFFER,
+};
+
+pub mod utils;
+
+const PRICE: u64 = 100_000_000;
+
+struct Market {
+    authority: Keypair,
+    buyer: Keypair,
+}
+
+async fn create_market(ctx: &mut ProgramTestContext) -> Market {
+    let authority = create_market_auction_house(ctx, 500).await;
+    let [buyer] = create_wallets(ctx, 1_000_000_000).await;
+    Market { authority, buyer }
+}
+
+/// Root of the set of `eligible_asset` and `other_asset`, with the proof of
+/// `eligible_asset`
+fn eligible_assets(eligible_asset: &Pubkey, other_asset: &Pubkey) -> ([u8; 32], Vec<[u8; 32]>) {
+    let leaf = keccak::hashv(&[eligible_asset.as_ref()]).to_bytes();
+    let sibling = keccak::hashv(&[other_asset.as_ref()]).to_bytes();
+    let root = if leaf <= sibling {
+        keccak::hashv(&[&leaf, &sibling])
+    } else {
+        keccak::hashv(&[&sibling, &leaf])
+    };
+    (root.to_bytes(), vec![sibling])
+}
+
+fn find_trait_offer(market: &Market, eligible_assets_root: &[u8; 32]) -> Pubkey {
+    let auction_house =
+        find_auction_house(&market.authority.pubkey(), &native_mint::id()).auction_house;
+    Pubkey::find_program_address(
+        &[
+            TRAIT_OFFER.as_ref(),
+            market.buyer.pubkey().as_ref(),
+            auction_house.as_ref(),
+            eligible_assets_root.as_ref(),
+            PRICE.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn trait_offer_instruction(
+    market: &Market,
+    eligible_assets_root: [u8; 32],
+    expiry: Option<i64>,
+) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    Instruction {
+        program_id: ID,
+        accounts: TraitOfferAccounts {
+            auction_house: accounts.auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            bidder: market.buyer.pubkey(),
+            payment_account: market.buyer.pubkey(),
+            buyer_escrow: find_escrow(&accounts.auction_house, &market.buyer.pubkey()),
+            trait_offer: find_trait_offer(market, &eligible_assets_root),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: TraitOfferInstruction {
+            buyer_price: PRICE,
+            eligible_assets_root,
+            quantity: 2,
+            expiry,
+        }
+        .data(),
+    }
+}
+
+fn cancel_trait_offer_instruction(
+    market: &Market,
+    eligible_assets_root: &[u8; 32],
+    wallet: &Pubkey,
+) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    Instruction {
+        program_id: ID,
+        accounts: CancelTraitOfferAccounts {
+            auction_house: accounts.auction_house,
+            auction_house_authority: market.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            wallet: *wallet,
+            receipt_account: *wallet,
+            buyer_escrow: find_escrow(&accounts.auction_house, wallet),
+            trait_offer: find_trait_offer(market, eligible_assets_root),
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: CancelTraitOfferInstruction {
+            buyer_price: PRICE,
+            refund_escrow: true,
+        }
+        .data(),
+    }
+}
+
+fn accept_trait_offer_instruction(
+    market: &Market,
+    eligible_assets_root: &[u8; 32],
+    eligibility_proof: Vec<[u8; 32]>,
+    seller: &Keypair,
+    creator: &Pubkey,
+    tree: &Tree,
+    leaf: &Leaf,
+) -> Instruction {
+    let accounts = find_auction_house(&market.authority.pubkey(), &native_mint::id());
+    let mut account_metas = AcceptTraitOfferAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: market.authority.pubkey(),
+        treasury_mint: native_mint::id(),
+        treasury_account: accounts.treasury_account,
+        tree_config: tree.tree_config,
+        seller: seller.pubkey(),
+        seller_receipt_account: seller.pubkey(),
+        merkle_tree: tree.merkle_tree,
+        buyer: market.buyer.pubkey(),
+        buyer_escrow: find_escrow(&accounts.auction_house, &market.buyer.pubkey()),
+        trait_offer: find_trait_offer(market, eligible_assets_root),
+        auction_house_fee_account: accounts.fee_account,
+        previous_leaf_delegate: leaf.delegate,
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.push(AccountMeta::new(*creator, false));
+    account_metas.extend(tree.proof(leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: AcceptTraitOfferInstruction {
+            buyer_price: PRICE,
+            root: tree.root(),
+            data_hash: leaf.data_hash(),
+            creator_hash: leaf.creator_hash(),
+            nonce: leaf.nonce,
+            index: leaf.index,
+            metadata: leaf.metadata_args(),
+            eligibility_proof,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn place_trait_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let (eligible_assets_root, _) = eligible_assets(&Pubkey::new_unique(), &Pubkey::new_unique());
+
+    process_instructions(
+        &mut ctx,
+        &[trait_offer_instruction(&market, eligible_assets_root, None)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let trait_offer = fetch_program_account::<TraitOffer>(
+        &mut ctx,
+        find_trait_offer(&market, &eligible_assets_root),
+    )
+    .await;
+    assert_eq!(trait_offer.wallet, market.buyer.pubkey());
+    assert_eq!(trait_offer.eligible_assets_root, eligible_assets_root);
+    assert_eq!(trait_offer.price, PRICE);
+    assert_eq!(trait_offer.quantity, 2);
+}
+
+#[tokio::test]
+async fn reject_trait_offer_expired_at_placement() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let (eligible_assets_root, _) = eligible_assets(&Pubkey::new_unique(), &Pubkey::new_unique());
+
+    let result = process_instructions(
+        &mut ctx,
+        &[trait_offer_instruction(
+            &market,
+            eligible_assets_root,
+            Some(0),
+        )],
+        &[&market.buyer],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidExpiry.into());
+}
+
+#[tokio::test]
+async fn cancel_trait_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let (eligible_assets_root, _) = eligible_assets(&Pubkey::new_unique(), &Pubkey::new_unique());
+    process_instructions(
+        &mut ctx,
+        &[trait_offer_instruction(&market, eligible_assets_root, None)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+    let buyer_balance = ctx
+        .banks_client
+        .get_balance(market.buyer.pubkey())
+        .await
+        .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[cancel_trait_offer_instruction(
+            &market,
+            &eligible_assets_root,
+            &market.buyer.pubkey(),
+        )],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let trait_offer = ctx
+        .banks_client
+        .get_account(find_trait_offer(&market, &eligible_assets_root))
+        .await
+        .unwrap();
+    assert!(trait_offer.is_none());
+    let refunded = ctx
+        .banks_client
+        .get_balance(market.buyer.pubkey())
+        .await
+        .unwrap()
+        - buyer_balance;
+    assert!(refunded >= 2 * PRICE);
+}
+
+#[tokio::test]
+async fn reject_cancel_of_another_wallet_trait_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let [other_wallet] = create_wallets(&mut ctx, 1_000_000_000).await;
+    let (eligible_assets_root, _) = eligible_assets(&Pubkey::new_unique(), &Pubkey::new_unique());
+    process_instructions(
+        &mut ctx,
+        &[trait_offer_instruction(&market, eligible_assets_root, None)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let result = process_instructions(
+        &mut ctx,
+        &[cancel_trait_offer_instruction(
+            &market,
+            &eligible_assets_root,
+            &other_wallet.pubkey(),
+        )],
+        &[&other_wallet],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::ConstraintSeeds.into());
+}
+
+#[tokio::test]
+async fn accept_trait_offer() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let [seller, creator] = create_wallets(&mut ctx, 1_000_000_000).await;
+    let (tree, leaf) =
+        mint_market_leaf(&mut ctx, &seller.pubkey(), &seller.pubkey(), &creator, true).await;
+    let (eligible_assets_root, eligibility_proof) =
+        eligible_assets(&leaf.asset_id, &Pubkey::new_unique());
+    process_instructions(
+        &mut ctx,
+        &[trait_offer_instruction(&market, eligible_assets_root, None)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    process_instructions(
+        &mut ctx,
+        &[accept_trait_offer_instruction(
+            &market,
+            &eligible_assets_root,
+            eligibility_proof,
+            &seller,
+            &creator.pubkey(),
+            &tree,
+            &leaf,
+        )],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    // the offer stays open for the second asset
+    let trait_offer = fetch_program_account::<TraitOffer>(
+        &mut ctx,
+        find_trait_offer(&market, &eligible_assets_root),
+    )
+    .await;
+    assert_eq!(trait_offer.quantity, 1);
+}
+
+#[tokio::test]
+async fn reject_accept_trait_offer_with_ineligible_asset() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let market = create_market(&mut ctx).await;
+    let [seller, creator] = create_wallets(&mut ctx, 1_000_000_000).await;
+    let (tree, leaf) =
+        mint_market_leaf(&mut ctx, &seller.pubkey(), &seller.pubkey(), &creator, true).await;
+    let (eligible_assets_root, eligibility_proof) =
+        eligible_assets(&Pubkey::new_unique(), &Pubkey::new_unique());
+    process_instructions(
+        &mut ctx,
+        &[trait_offer_instruction(&market, eligible_assets_root, None)],
+        &[&market.buyer],
+    )
+    .await
+    .unwrap();
+
+    let result = process_instructions(
+        &mut ctx,
+        &[accept_trait_offer_instruction(
+            &market,
+            &eligible_assets_root,
+            eligibility_proof,
+            &seller,
+            &creator.pubkey(),
+            &tree,
+            &leaf,
+        )],
+        &[&seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::AssetNotEligible.into());
+}
@@ -0,0 +1,485 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::UpdateAuctionHouseInstruction as UpdateAuctionHouseAccounts,
+    instruction::UpdateAuctionHouse as UpdateAuctionHouseInstruction, AuctionHouseV2Data,
+    AuctionHouseV2Errors, RoyaltyMode, RoyaltyPolicy, ID,
+};
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+use utils::{
+    assert_custom_error, auction_house_program_test, create_auction_house_instruction, create_mint,
+    fetch_program_account, find_auction_house, process_instructions, AUCTION_HOUSE,
+    MAX_AUCTION_HOUSE_SIZE,
+};
+
+pub mod utils;
+
+const ROYALTY_POLICY: RoyaltyPolicy = RoyaltyPolicy {
+    mode: RoyaltyMode::Enforced,
+    min_basis_points: 0,
+    max_basis_points: 9500,
+};
+
+/// Auction house account as created before royalty policies
+#[derive(AnchorSerialize)]
+struct LegacyAuctionHouse {
+    authority: Pubkey,
+    treasury_mint: Pubkey,
+    seller_fee_basis_points: u16,
+    requires_sign_off: bool,
+    treasury_account: Pubkey,
+    treasury_withdrawal_account: Pubkey,
+    fee_account: Pubkey,
+    fee_withdrawal_account: Pubkey,
+    bump: u8,
+    treasury_bump: u8,
+    fee_account_bump: u8,
+}
+
+fn update_auction_house_instruction(
+    auction_house: &Pubkey,
+    authority: &Pubkey,
+    treasury_mint: &Pubkey,
+    treasury_withdrawal_account: Option<Pubkey>,
+    treasury_withdrawal_owner: Option<Pubkey>,
+    fee_withdrawal_account: Option<Pubkey>,
+    seller_fee_basis_points: Option<u16>,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: UpdateAuctionHouseAccounts {
+            auction_house: *auction_house,
+            authority: *authority,
+            treasury_mint: *treasury_mint,
+            treasury_withdrawal_account,
+            treasury_withdrawal_owner,
+            fee_withdrawal_account,
+            payer: *authority,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: UpdateAuctionHouseInstruction {
+            seller_fee_basis_points,
+            requires_sign_off: None,
+            royalty_policy: None,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn update_native_auction_house() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let treasury_mint = native_mint::id();
+    let auction_house = find_auction_house(&payer_pubkey, &treasury_mint).auction_house;
+    let withdrawal_account = Keypair::new();
+    let new_withdrawal_account = Keypair::new();
+
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &payer_pubkey,
+        &treasury_mint,
+        &withdrawal_account.pubkey(),
+        &payer_pubkey,
+        &withdrawal_account.pubkey(),
+        500,
+        ROYALTY_POLICY,
+    );
+    let update_auction_house_instruction = update_auction_house_instruction(
+        &auction_house,
+        &payer_pubkey,
+        &treasury_mint,
+        Some(new_withdrawal_account.pubkey()),
+        Some(payer_pubkey),
+        Some(withdrawal_account.pubkey()),
+        Some(250),
+    );
+    process_instructions(
+        &mut ctx,
+        &[
+            create_auction_house_instruction,
+            update_auction_house_instruction,
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let auction_house_account = ctx
+        .banks_client
+        .get_account(auction_house)
+        .await
+        .unwrap()
+        .unwrap();
+    let auction_house_data =
+        AuctionHouseV2Data::try_deserialize(&mut auction_house_account.data.as_ref()).unwrap();
+    assert_eq!(auction_house_data.seller_fee_basis_points, 250);
+    assert!(!auction_house_data.requires_sign_off);
+    assert_eq!(
+        auction_house_data.treasury_withdrawal_account,
+        new_withdrawal_account.pubkey()
+    );
+    assert_eq!(
+        auction_house_data.fee_withdrawal_account,
+        withdrawal_account.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn update_fee_withdrawal_account_only() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let treasury_mint = native_mint::id();
+    let auction_house = find_auction_house(&payer_pubkey, &treasury_mint).auction_house;
+    let withdrawal_account = Keypair::new();
+    let new_fee_withdrawal_account = Keypair::new();
+
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &payer_pubkey,
+        &treasury_mint,
+        &withdrawal_account.pubkey(),
+        &payer_pubkey,
+        &withdrawal_account.pubkey(),
+        500,
+        ROYALTY_POLICY,
+    );
+    let update_auction_house_instruction = update_auction_house_instruction(
+        &auction_house,
+        &payer_pubkey,
+        &treasury_mint,
+        None,
+        None,
+        Some(new_fee_withdrawal_account.pubkey()),
+        None,
+    );
+    process_instructions(
+        &mut ctx,
+        &[
+            create_auction_house_instruction,
+            update_auction_house_instruction,
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let auction_house_account = ctx
+        .banks_client
+        .get_account(auction_house)
+        .await
+        .unwrap()
+        .unwrap();
+    let auction_house_data =
+        AuctionHouseV2Data::try_deserialize(&mut auction_house_account.data.as_ref()).unwrap();
+    assert_eq!(auction_house_data.seller_fee_basis_points, 500);
+    assert_eq!(
+        auction_house_data.treasury_withdrawal_account,
+        withdrawal_account.pubkey()
+    );
+    assert_eq!(
+        auction_house_data.fee_withdrawal_account,
+        new_fee_withdrawal_account.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn reject_seller_fee_basis_points_above_maximum() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let treasury_mint = native_mint::id();
+    let auction_house = find_auction_house(&payer_pubkey, &treasury_mint).auction_house;
+    let withdrawal_account = Keypair::new();
+
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &payer_pubkey,
+        &treasury_mint,
+        &withdrawal_account.pubkey(),
+        &payer_pubkey,
+        &withdrawal_account.pubkey(),
+        500,
+        ROYALTY_POLICY,
+    );
+    process_instructions(&mut ctx, &[create_auction_house_instruction], &[])
+        .await
+        .unwrap();
+
+    let update_auction_house_instruction = update_auction_house_instruction(
+        &auction_house,
+        &payer_pubkey,
+        &treasury_mint,
+        None,
+        None,
+        None,
+        Some(10001),
+    );
+    let result = process_instructions(&mut ctx, &[update_auction_house_instruction], &[]).await;
+    assert_custom_error(
+        result,
+        AuctionHouseV2Errors::InvalidSellerFeeBasisPoints.into(),
+    );
+}
+
+#[tokio::test]
+async fn reject_update_from_non_authority() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let treasury_mint = native_mint::id();
+    let auction_house = find_auction_house(&payer_pubkey, &treasury_mint).auction_house;
+    let withdrawal_account = Keypair::new();
+    let impostor = Keypair::new();
+
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &payer_pubkey,
+        &treasury_mint,
+        &withdrawal_account.pubkey(),
+        &payer_pubkey,
+        &withdrawal_account.pubkey(),
+        500,
+        ROYALTY_POLICY,
+    );
+    process_instructions(&mut ctx, &[create_auction_house_instruction], &[])
+        .await
+        .unwrap();
+
+    // the impostor signs for the auction house of the real authority
+    let mut update_auction_house_instruction = update_auction_house_instruction(
+        &auction_house,
+        &payer_pubkey,
+        &treasury_mint,
+        None,
+        None,
+        None,
+        Some(0),
+    );
+    update_auction_house_instruction.accounts[1].pubkey = impostor.pubkey();
+    update_auction_house_instruction.accounts[6].pubkey = impostor.pubkey();
+    let result =
+        process_instructions(&mut ctx, &[update_auction_house_instruction], &[&impostor]).await;
+    assert_custom_error(result, ErrorCode::ConstraintSeeds.into());
+}
+
+#[tokio::test]
+async fn recreate_treasury_withdrawal_token_account() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let treasury_mint = create_mint(&mut ctx, &payer_pubkey).await;
+    let auction_house = find_auction_house(&payer_pubkey, &treasury_mint).auction_house;
+    let withdrawal_account = get_associated_token_address(&payer_pubkey, &treasury_mint);
+    let new_withdrawal_owner = Keypair::new();
+    let new_withdrawal_account =
+        get_associated_token_address(&new_withdrawal_owner.pubkey(), &treasury_mint);
+
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &payer_pubkey,
+        &treasury_mint,
+        &withdrawal_account,
+        &payer_pubkey,
+        &withdrawal_account,
+        500,
+        ROYALTY_POLICY,
+    );
+    let update_auction_house_instruction = update_auction_house_instruction(
+        &auction_house,
+        &payer_pubkey,
+        &treasury_mint,
+        Some(new_withdrawal_account),
+        Some(new_withdrawal_owner.pubkey()),
+        None,
+        None,
+    );
+    process_instructions(
+        &mut ctx,
+        &[
+            create_auction_house_instruction,
+            update_auction_house_instruction,
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let new_withdrawal_token_account = ctx
+        .banks_client
+        .get_account(new_withdrawal_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let token_account =
+        spl_token::state::Account::unpack(&new_withdrawal_token_account.data).unwrap();
+    assert_eq!(token_account.owner, new_withdrawal_owner.pubkey());
+    assert_eq!(token_account.mint, treasury_mint);
+
+    let auction_house_account = ctx
+        .banks_client
+        .get_account(auction_house)
+        .await
+        .unwrap()
+        .unwrap();
+    let auction_house_data =
+        AuctionHouseV2Data::try_deserialize(&mut auction_house_account.data.as_ref()).unwrap();
+    assert_eq!(
+        auction_house_data.treasury_withdrawal_account,
+        new_withdrawal_account
+    );
+}
+
+#[tokio::test]
+async fn reject_seller_fee_basis_points_above_royalty_cap() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let treasury_mint = native_mint::id();
+    let auction_house = find_auction_house(&payer_pubkey, &treasury_mint).auction_house;
+    let withdrawal_account = Keypair::new();
+
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &payer_pubkey,
+        &treasury_mint,
+        &withdrawal_account.pubkey(),
+        &payer_pubkey,
+        &withdrawal_account.pubkey(),
+        500,
+        ROYALTY_POLICY,
+    );
+    process_instructions(&mut ctx, &[create_auction_house_instruction], &[])
+        .await
+        .unwrap();
+
+    // a 6% fee and the 95% royalty cap would take more than the sale price
+    let update_auction_house_instruction = update_auction_house_instruction(
+        &auction_house,
+        &payer_pubkey,
+        &treasury_mint,
+        None,
+        None,
+        None,
+        Some(600),
+    );
+    let result = process_instructions(&mut ctx, &[update_auction_house_instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidRoyaltyPolicy.into());
+}
+
+/// Stores an auction house of the payer taking 5% fees in the layout used
+/// before royalty policies
+fn set_legacy_auction_house(ctx: &mut ProgramTestContext) -> Pubkey {
+    let payer_pubkey = ctx.payer.pubkey();
+    let treasury_mint = native_mint::id();
+    let accounts = find_auction_house(&payer_pubkey, &treasury_mint);
+    let (_, bump) = Pubkey::find_program_address(
+        &[
+            AUCTION_HOUSE.as_ref(),
+            payer_pubkey.as_ref(),
+            treasury_mint.as_ref(),
+        ],
+        &ID,
+    );
+
+    // legacy accounts lack the royalty policy bytes
+    let space = MAX_AUCTION_HOUSE_SIZE - (1 + 2 * 2);
+    let mut data = AuctionHouseV2Data::discriminator().to_vec();
+    LegacyAuctionHouse {
+        authority: payer_pubkey,
+        treasury_mint,
+        seller_fee_basis_points: 500,
+        requires_sign_off: false,
+        treasury_account: accounts.treasury_account,
+        treasury_withdrawal_account: payer_pubkey,
+        fee_account: accounts.fee_account,
+        fee_withdrawal_account: payer_pubkey,
+        bump,
+        treasury_bump: 0,
+        fee_account_bump: 0,
+    }
+    .serialize(&mut data)
+    .unwrap();
+    data.resize(space, 0);
+    let account = Account {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner: ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    ctx.set_account(&accounts.auction_house, &AccountSharedData::from(account));
+    accounts.auction_house
+}
+
+#[tokio::test]
+async fn update_legacy_auction_house_without_royalty_cap() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let auction_house = set_legacy_auction_house(&mut ctx);
+    let withdrawal_account = Keypair::new();
+
+    let update_auction_house_instruction = update_auction_house_instruction(
+        &auction_house,
+        &payer_pubkey,
+        &native_mint::id(),
+        None,
+        None,
+        Some(withdrawal_account.pubkey()),
+        None,
+    );
+    process_instructions(&mut ctx, &[update_auction_house_instruction], &[])
+        .await
+        .unwrap();
+
+    let auction_house_data =
+        fetch_program_account::<AuctionHouseV2Data>(&mut ctx, auction_house).await;
+    assert_eq!(
+        auction_house_data.fee_withdrawal_account,
+        withdrawal_account.pubkey()
+    );
+    assert_eq!(
+        auction_house_data.royalty_policy,
+        RoyaltyPolicy {
+            mode: RoyaltyMode::Enforced,
+            min_basis_points: 0,
+            max_basis_points: 0,
+        }
+    );
+    // the uncapped policy keeps paying the royalty of the metadata up to what
+    // the fee leaves of the sale price
+    let royalty_policy = auction_house_data.royalty_policy;
+    assert_eq!(royalty_policy.royalty_basis_points(500, 750, None), 750);
+    assert_eq!(royalty_policy.royalty_basis_points(500, 10000, None), 9500);
+    assert_eq!(
+        RoyaltyPolicy {
+            mode: RoyaltyMode::Optional,
+            ..royalty_policy
+        }
+        .royalty_basis_points(500, 750, Some(10000)),
+        9500
+    );
+}
+
+#[tokio::test]
+async fn reject_seller_fee_basis_points_update_without_royalty_cap() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer_pubkey = ctx.payer.pubkey();
+    let auction_house = set_legacy_auction_house(&mut ctx);
+
+    // an uncapped royalty leaves no room for any fee
+    let update_auction_house_instruction = update_auction_house_instruction(
+        &auction_house,
+        &payer_pubkey,
+        &native_mint::id(),
+        None,
+        None,
+        None,
+        Some(250),
+    );
+    let result = process_instructions(&mut ctx, &[update_auction_house_instruction], &[]).await;
+    assert_custom_error(result, AuctionHouseV2Errors::InvalidRoyaltyPolicy.into());
+}
@@ -0,0 +1,264 @@
+use anchor_lang::{system_program, AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::PrintListingReceiptInstruction as PrintListingReceiptAccounts,
+    accounts::UpdateListingPriceInstruction as UpdateListingPriceAccounts,
+    instruction::PrintListingReceipt as PrintListingReceiptInstruction,
+    instruction::UpdateListingPrice as UpdateListingPriceInstruction, AuctionHouseV2Errors,
+    ListingReceipt, RoyaltyMode, RoyaltyPolicy, TradeSide, TradeState, ID,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{signature::Keypair, signer::Signer};
+use utils::{
+    add_program_account, assert_custom_error, auction_house_program_test,
+    create_auction_house_instruction, find_auction_house, process_instructions, LISTING_RECEIPT,
+    TRADE_STATE, TRADE_STATE_SIZE,
+};
+
+pub mod utils;
+
+const CREATED_AT: i64 = 1_700_000_000;
+
+fn find_trade_state(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    asset_id: &Pubkey,
+    price: u64,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            TRADE_STATE.as_ref(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            asset_id.as_ref(),
+            price.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn find_listing_receipt(trade_state: &Pubkey, created_at: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            LISTING_RECEIPT.as_bytes(),
+            trade_state.as_ref(),
+            created_at.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+struct Listing {
+    authority: Keypair,
+    seller: Keypair,
+    auction_house: Pubkey,
+    asset_id: Pubkey,
+    trade_state: Pubkey,
+}
+
+/// Adds a listing of `price` on a native auction house that still has to be created
+fn add_listing(program: &mut ProgramTest, price: u64, expiry: Option<i64>) -> Listing {
+    let authority = Keypair::new();
+    let seller = Keypair::new();
+    let auction_house = find_auction_house(&authority.pubkey(), &native_mint::id()).auction_house;
+    let asset_id = Pubkey::new_unique();
+    let trade_state = find_trade_state(&seller.pubkey(), &auction_house, &asset_id, price);
+    let (_, trade_state_bump) = Pubkey::find_program_address(
+        &[
+            TRADE_STATE.as_ref(),
+            seller.pubkey().as_ref(),
+            auction_house.as_ref(),
+            asset_id.as_ref(),
+            price.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    );
+    add_program_account(
+        program,
+        trade_state,
+        &TradeState {
+            wallet: seller.pubkey(),
+            auction_house,
+            asset_id,
+            merkle_tree: Pubkey::new_unique(),
+            leaf_index: 0,
+            price,
+            side: TradeSide::Ask,
+            created_at: CREATED_AT,
+            bump: trade_state_bump,
+            expiry,
+            allowed_buyer: None,
+        },
+        TRADE_STATE_SIZE,
+    );
+    Listing {
+        authority,
+        seller,
+        auction_house,
+        asset_id,
+        trade_state,
+    }
+}
+
+async fn create_auction_house(ctx: &mut ProgramTestContext, listing: &Listing) {
+    let create_auction_house_instruction = create_auction_house_instruction(
+        &listing.authority.pubkey(),
+        &native_mint::id(),
+        &listing.authority.pubkey(),
+        &listing.authority.pubkey(),
+        &listing.authority.pubkey(),
+        500,
+        RoyaltyPolicy {
+            mode: RoyaltyMode::Enforced,
+            min_basis_points: 0,
+            max_basis_points: 9500,
+        },
+    );
+    // the authority pays for the auction house accounts
+    let fund_authority_instruction = solana_program::system_instruction::transfer(
+        &ctx.payer.pubkey(),
+        &listing.authority.pubkey(),
+        1_000_000_000,
+    );
+    process_instructions(
+        ctx,
+        &[fund_authority_instruction, create_auction_house_instruction],
+        &[&listing.authority],
+    )
+    .await
+    .unwrap();
+}
+
+fn print_listing_receipt_instruction(listing: &Listing, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: PrintListingReceiptAccounts {
+            seller_trade_state: listing.trade_state,
+            receipt: find_listing_receipt(&listing.trade_state, CREATED_AT),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: PrintListingReceiptInstruction {}.data(),
+    }
+}
+
+fn update_listing_price_instruction(
+    listing: &Listing,
+    seller_price: u64,
+    new_seller_price: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: UpdateListingPriceAccounts {
+            auction_house: listing.auction_house,
+            auction_house_authority: listing.authority.pubkey(),
+            treasury_mint: native_mint::id(),
+            owner: listing.seller.pubkey(),
+            asset_id: listing.asset_id,
+            seller_trade_state: listing.trade_state,
+            new_seller_trade_state: find_trade_state(
+                &listing.seller.pubkey(),
+                &listing.auction_house,
+                &listing.asset_id,
+                new_seller_price,
+            ),
+            system_program: system_program::ID,
+            listing_receipt: find_listing_receipt(&listing.trade_state, CREATED_AT),
+        }
+        .to_account_metas(None),
+        data: UpdateListingPriceInstruction {
+            seller_price,
+            new_seller_price,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn update_listing_price() {
+    let mut program = auction_house_program_test();
+    let listing = add_listing(&mut program, 1000, None);
+    let mut ctx = program.start_with_context().await;
+    create_auction_house(&mut ctx, &listing).await;
+    let payer_pubkey = ctx.payer.pubkey();
+
+    let fund_seller_instruction = solana_program::system_instruction::transfer(
+        &payer_pubkey,
+        &listing.seller.pubkey(),
+        1_000_000_000,
+    );
+    process_instructions(
+        &mut ctx,
+        &[
+            fund_seller_instruction,
+            print_listing_receipt_instruction(&listing, &payer_pubkey),
+            update_listing_price_instruction(&listing, 1000, 2000),
+        ],
+        &[&listing.seller],
+    )
+    .await
+    .unwrap();
+
+    let old_trade_state = ctx
+        .banks_client
+        .get_account(listing.trade_state)
+        .await
+        .unwrap();
+    assert!(old_trade_state.is_none());
+
+    let new_trade_state_address = find_trade_state(
+        &listing.seller.pubkey(),
+        &listing.auction_house,
+        &listing.asset_id,
+        2000,
+    );
+    let new_trade_state_account = ctx
+        .banks_client
+        .get_account(new_trade_state_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let new_trade_state =
+        TradeState::try_deserialize(&mut new_trade_state_account.data.as_ref()).unwrap();
+    assert_eq!(new_trade_state.price, 2000);
+    assert_eq!(new_trade_state.side, TradeSide::Ask);
+    assert_eq!(new_trade_state.wallet, listing.seller.pubkey());
+    assert_eq!(new_trade_state.asset_id, listing.asset_id);
+
+    let receipt_account = ctx
+        .banks_client
+        .get_account(find_listing_receipt(&listing.trade_state, CREATED_AT))
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt = ListingReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    assert!(receipt.canceled_at.is_some());
+}
+
+#[tokio::test]
+async fn reject_update_of_expired_listing() {
+    let mut program = auction_house_program_test();
+    let listing = add_listing(&mut program, 1000, Some(CREATED_AT));
+    let mut ctx = program.start_with_context().await;
+    create_auction_house(&mut ctx, &listing).await;
+
+    let fund_seller_instruction = solana_program::system_instruction::transfer(
+        &ctx.payer.pubkey(),
+        &listing.seller.pubkey(),
+        1_000_000_000,
+    );
+    let result = process_instructions(
+        &mut ctx,
+        &[
+            fund_seller_instruction,
+            update_listing_price_instruction(&listing, 1000, 2000),
+        ],
+        &[&listing.seller],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::OrderExpired.into());
+}
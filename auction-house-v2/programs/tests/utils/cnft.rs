@@ -0,0 +1,305 @@
+use super::process_instructions;
+use borsh::{BorshDeserialize, BorshSerialize};
+use mpl_bubblegum::{
+    accounts::TreeConfig,
+    hash::{hash_creators, hash_metadata},
+    instructions::{CreateTreeConfigBuilder, MintV1Builder, TransferBuilder},
+    programs::SPL_ACCOUNT_COMPRESSION_ID,
+    types::{Creator, LeafSchema, MetadataArgs, TokenProgramVersion, TokenStandard},
+    utils::get_asset_id,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    keccak,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{AccountSharedData, WritableAccount},
+    signature::Keypair,
+    signer::Signer,
+};
+
+pub const MAX_DEPTH: usize = 5;
+pub const MAX_BUFFER_SIZE: usize = 8;
+const MERKLE_TREE_HEADER_SIZE: usize = 56;
+/// Size of a concurrent merkle tree account of `MAX_DEPTH` and `MAX_BUFFER_SIZE` without canopy
+pub const MERKLE_TREE_SIZE: usize = MERKLE_TREE_HEADER_SIZE
+    + 24
+    + MAX_BUFFER_SIZE * (32 + 32 * MAX_DEPTH + 8)
+    + (32 * MAX_DEPTH + 32 + 8);
+
+/// Compressed nft minted to a `Tree`
+#[derive(Clone)]
+pub struct Leaf {
+    pub asset_id: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub nonce: u64,
+    pub index: u32,
+    pub metadata: MetadataArgs,
+}
+
+impl Leaf {
+    pub fn data_hash(&self) -> [u8; 32] {
+        hash_metadata(&self.metadata).unwrap()
+    }
+
+    pub fn creator_hash(&self) -> [u8; 32] {
+        hash_creators(&self.metadata.creators)
+    }
+
+    /// Metadata of the leaf as the auction house instructions take it
+    pub fn metadata_args(&self) -> auction_house_v2::MetadataArgs {
+        auction_house_v2::MetadataArgs::try_from_slice(&self.metadata.try_to_vec().unwrap())
+            .unwrap()
+    }
+
+    fn hash(&self) -> [u8; 32] {
+        LeafSchema::V1 {
+            id: self.asset_id,
+            owner: self.owner,
+            delegate: self.delegate,
+            nonce: self.nonce,
+            data_hash: self.data_hash(),
+            creator_hash: self.creator_hash(),
+        }
+        .hash()
+    }
+}
+
+/// Metadata of a leaf paying `seller_fee_basis_points` to `creators`, all verified
+pub fn leaf_metadata(seller_fee_basis_points: u16, creators: &[(Pubkey, u8)]) -> MetadataArgs {
+    MetadataArgs {
+        name: String::from("Leaf"),
+        symbol: String::from("LEAF"),
+        uri: String::from("https://example.com/leaf.json"),
+        seller_fee_basis_points,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: None,
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: creators
+            .iter()
+            .map(|(address, share)| Creator {
+                address: *address,
+                verified: true,
+                share: *share,
+            })
+            .collect(),
+    }
+}
+
+/// Merkle tree mirrored off chain to provide the root and the proofs of its leaves
+pub struct Tree {
+    pub merkle_tree: Pubkey,
+    pub tree_config: Pubkey,
+    leaves: Vec<[u8; 32]>,
+    num_minted: u64,
+}
+
+impl Tree {
+    pub async fn create(ctx: &mut ProgramTestContext) -> Tree {
+        let merkle_tree = Keypair::new();
+        let payer = ctx.payer.pubkey();
+        let (tree_config, _) = TreeConfig::find_pda(&merkle_tree.pubkey());
+        let rent = ctx.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &merkle_tree.pubkey(),
+                rent.minimum_balance(MERKLE_TREE_SIZE),
+                MERKLE_TREE_SIZE as u64,
+                &SPL_ACCOUNT_COMPRESSION_ID,
+            ),
+            CreateTreeConfigBuilder::new()
+                .tree_config(tree_config)
+                .merkle_tree(merkle_tree.pubkey())
+                .payer(payer)
+                .tree_creator(payer)
+                .max_depth(MAX_DEPTH as u32)
+                .max_buffer_size(MAX_BUFFER_SIZE as u32)
+                .instruction(),
+        ];
+        process_instructions(ctx, &instructions, &[&merkle_tree])
+            .await
+            .unwrap();
+        Tree {
+            merkle_tree: merkle_tree.pubkey(),
+            tree_config,
+            leaves: vec![[0; 32]; 1 << MAX_DEPTH],
+            num_minted: 0,
+        }
+    }
+
+    /// Mints a leaf of `metadata` to `owner`, the verified creators of the
+    /// metadata sign the mint
+    pub async fn mint(
+        &mut self,
+        ctx: &mut ProgramTestContext,
+        owner: &Pubkey,
+        delegate: &Pubkey,
+        metadata: MetadataArgs,
+        creators: &[&Keypair],
+    ) -> Leaf {
+        let payer = ctx.payer.pubkey();
+        let mut builder = MintV1Builder::new();
+        builder
+            .tree_config(self.tree_config)
+            .leaf_owner(*owner)
+            .leaf_delegate(*delegate)
+            .merkle_tree(self.merkle_tree)
+            .payer(payer)
+            .tree_creator_or_delegate(payer)
+            .metadata(metadata.clone());
+        for creator in creators {
+            builder.add_remaining_account(AccountMeta::new_readonly(creator.pubkey(), true));
+        }
+        process_instructions(ctx, &[builder.instruction()], creators)
+            .await
+            .unwrap();
+
+        let nonce = self.num_minted;
+        self.num_minted += 1;
+        let leaf = Leaf {
+            asset_id: get_asset_id(&self.merkle_tree, nonce),
+            owner: *owner,
+            delegate: *delegate,
+            nonce,
+            index: nonce as u32,
+            metadata,
+        };
+        self.leaves[leaf.index as usize] = leaf.hash();
+        leaf
+    }
+
+    /// Writes a leaf bubblegum would only mint with token metadata accounts,
+    /// such as one of a verified collection, straight into the tree account.
+    /// Leaves can't be minted once a leaf was forged.
+    pub async fn forge(
+        &mut self,
+        ctx: &mut ProgramTestContext,
+        owner: &Pubkey,
+        delegate: &Pubkey,
+        metadata: MetadataArgs,
+    ) -> Leaf {
+        let nonce = self.num_minted;
+        self.num_minted += 1;
+        let leaf = Leaf {
+            asset_id: get_asset_id(&self.merkle_tree, nonce),
+            owner: *owner,
+            delegate: *delegate,
+            nonce,
+            index: nonce as u32,
+            metadata,
+        };
+        self.leaves[leaf.index as usize] = leaf.hash();
+
+        // the tree keeps a single change log of the rightmost leaf leading to
+        // the current root, with the proof of that leaf for the next append
+        let layers = self.layers();
+        let mut path = Vec::with_capacity(32 * MAX_DEPTH);
+        let mut index = leaf.index as usize;
+        for layer in &layers[..MAX_DEPTH] {
+            path.extend_from_slice(&layer[index]);
+            index /= 2;
+        }
+        let proof: Vec<u8> = self
+            .proof(leaf.index)
+            .iter()
+            .flat_map(|node| node.pubkey.to_bytes())
+            .collect();
+        let mut tree = Vec::with_capacity(MERKLE_TREE_SIZE - MERKLE_TREE_HEADER_SIZE);
+        tree.extend_from_slice(&self.num_minted.to_le_bytes());
+        tree.extend_from_slice(&0u64.to_le_bytes());
+        tree.extend_from_slice(&1u64.to_le_bytes());
+        tree.extend_from_slice(&self.root());
+        tree.extend_from_slice(&path);
+        tree.extend_from_slice(&leaf.index.to_le_bytes());
+        tree.extend_from_slice(&[0; 4]);
+        tree.resize(
+            tree.len() + (MAX_BUFFER_SIZE - 1) * (32 + 32 * MAX_DEPTH + 8),
+            0,
+        );
+        tree.extend_from_slice(&proof);
+        tree.extend_from_slice(&self.leaves[leaf.index as usize]);
+        tree.extend_from_slice(&(leaf.index + 1).to_le_bytes());
+        tree.extend_from_slice(&[0; 4]);
+
+        let mut account: AccountSharedData = ctx
+            .banks_client
+            .get_account(self.merkle_tree)
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        account.data_as_mut_slice()[MERKLE_TREE_HEADER_SIZE..].copy_from_slice(&tree);
+        ctx.set_account(&self.merkle_tree, &account);
+        leaf
+    }
+
+    /// Bubblegum transfer of `leaf` to `new_owner` signed by the leaf owner
+    pub fn transfer_instruction(&self, leaf: &Leaf, new_owner: &Pubkey) -> Instruction {
+        TransferBuilder::new()
+            .tree_config(self.tree_config)
+            .leaf_owner(leaf.owner, true)
+            .leaf_delegate(leaf.delegate, false)
+            .new_leaf_owner(*new_owner)
+            .merkle_tree(self.merkle_tree)
+            .root(self.root())
+            .data_hash(leaf.data_hash())
+            .creator_hash(leaf.creator_hash())
+            .nonce(leaf.nonce)
+            .index(leaf.index)
+            .add_remaining_accounts(&self.proof(leaf.index))
+            .instruction()
+    }
+
+    /// Records that `leaf` was transferred to `owner` on chain
+    pub fn transfer(&mut self, leaf: &mut Leaf, owner: &Pubkey) {
+        leaf.owner = *owner;
+        leaf.delegate = *owner;
+        self.leaves[leaf.index as usize] = leaf.hash();
+    }
+
+    /// Records that `leaf` was delegated to `delegate` on chain
+    pub fn delegate(&mut self, leaf: &mut Leaf, delegate: &Pubkey) {
+        leaf.delegate = *delegate;
+        self.leaves[leaf.index as usize] = leaf.hash();
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers().last().unwrap()[0]
+    }
+
+    /// Proof of the leaf at `index`, passed as the last remaining accounts
+    pub fn proof(&self, index: u32) -> Vec<AccountMeta> {
+        let mut index = index as usize;
+        self.layers()[..MAX_DEPTH]
+            .iter()
+            .map(|layer| {
+                let sibling = layer[index ^ 1];
+                index /= 2;
+                AccountMeta::new_readonly(Pubkey::new_from_array(sibling), false)
+            })
+            .collect()
+    }
+
+    fn layers(&self) -> Vec<Vec<[u8; 32]>> {
+        let mut layers = vec![self.leaves.clone()];
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| keccak::hashv(&[&pair[0], &pair[1]]).to_bytes())
+                .collect();
+            layers.push(layer);
+        }
+        layers
+    }
+}
@@ -5,6 +5,22 @@ pub const SIGNER: &str = "signer";
 pub const FEE: &str = "fee";
 pub const TREASURY: &str = "treasury";
 pub const ESCROW: &str = "escrow";
+pub const LISTING_RECEIPT: &str = "listing_receipt";
+pub const BID_RECEIPT: &str = "bid_receipt";
+pub const PURCHASE_RECEIPT: &str = "purchase_receipt";
+pub const COLLECTION_OFFER: &str = "collection_offer";
+pub const TRAIT_OFFER: &str = "trait_offer";
+pub const AUCTION: &str = "auction";
+pub const DUTCH_AUCTION: &str = "dutch_auction";
+pub const SEALED_AUCTION: &str = "sealed_auction";
+pub const SEALED_BID: &str = "sealed_bid";
+pub const BUNDLE: &str = "bundle";
+pub const SWAP_ORDER: &str = "swap_order";
 
-pub const TRADE_STATE_SIZE: usize = 32 * 3 + 8 + 1;
-pub const MAX_AUCTION_HOUSE_SIZE: usize = 32 * 7 + 8 + 1 * 3 + 8;
+pub const MAX_BUNDLE_ITEMS: u8 = 3;
+pub const AUCTION_SETTLEMENT_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+pub const TRADE_STATE_SIZE: usize = 8 + 32 * 4 + 4 + 8 + 1 + 8 + 1 + (1 + 8) + (1 + 32);
+pub const MAX_AUCTION_HOUSE_SIZE: usize = 32 * 7 + 8 + 1 * 3 + 8 + (1 + 2 * 2);
+pub const LISTING_RECEIPT_SIZE: usize = 8 + 32 * 5 + 4 + 8 + 8 + (1 + 8) * 2 + (1 + 32) + 1;
+pub const BID_RECEIPT_SIZE: usize = 8 + 32 * 5 + 4 + 8 + 8 + (1 + 8) * 2 + (1 + 32) + 1;
@@ -1,10 +1,374 @@
-use auction_house_v2::ID;
-use mpl_bubblegum;
+use super::{
+    leaf_metadata, Leaf, Tree, AUCTION_HOUSE, ESCROW, FEE, PROGRAM, SIGNER, TRADE_STATE, TREASURY,
+};
+use anchor_lang::{
+    system_program, AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas,
+};
+use auction_house_v2::{
+    accounts::CreateInstruction as CreateAuctionHouseAccounts,
+    accounts::SellInstruction as SellAccounts,
+    instruction::Create as CreateAuctionHouseInstruction, instruction::Sell as SellInstruction,
+    RoyaltyMode, RoyaltyPolicy, ID,
+};
+use mpl_bubblegum::{
+    self,
+    programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID},
+};
+use solana_program::{
+    clock::Clock, instruction::Instruction, program_pack::Pack, pubkey::Pubkey, system_instruction,
+};
 use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    instruction::InstructionError,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
 
 pub fn auction_house_program_test() -> ProgramTest {
     let mut program = ProgramTest::new("auction_house_v2", ID, None);
     program.add_program("mpl_bubblegum", mpl_bubblegum::ID, None);
-    program.set_compute_max_units(u64::MAX);
+    program.add_program("spl_account_compression", SPL_ACCOUNT_COMPRESSION_ID, None);
+    program.add_program("spl_noop", SPL_NOOP_ID, None);
+    program.set_compute_max_units(i64::MAX as u64);
     return program;
 }
+
+/// Adds an account of the program with `space` bytes holding `account`
+pub fn add_program_account<T: AccountSerialize>(
+    program: &mut ProgramTest,
+    address: Pubkey,
+    account: &T,
+    space: usize,
+) {
+    let mut data = Vec::with_capacity(space);
+    account.try_serialize(&mut data).unwrap();
+    data.resize(space, 0);
+    program.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(space),
+            data,
+            owner: ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+pub struct AuctionHouseAccounts {
+    pub auction_house: Pubkey,
+    pub treasury_account: Pubkey,
+    pub fee_account: Pubkey,
+}
+
+pub fn find_auction_house(authority: &Pubkey, treasury_mint: &Pubkey) -> AuctionHouseAccounts {
+    let (auction_house, _) = Pubkey::find_program_address(
+        &[
+            AUCTION_HOUSE.as_ref(),
+            authority.as_ref(),
+            treasury_mint.as_ref(),
+        ],
+        &ID,
+    );
+    let (treasury_account, _) =
+        Pubkey::find_program_address(&[TREASURY.as_bytes(), auction_house.as_ref()], &ID);
+    let (fee_account, _) =
+        Pubkey::find_program_address(&[FEE.as_bytes(), auction_house.as_ref()], &ID);
+    AuctionHouseAccounts {
+        auction_house,
+        treasury_account,
+        fee_account,
+    }
+}
+
+pub fn create_auction_house_instruction(
+    authority: &Pubkey,
+    treasury_mint: &Pubkey,
+    treasury_withdrawal_account: &Pubkey,
+    treasury_withdrawal_owner: &Pubkey,
+    fee_withdrawal_account: &Pubkey,
+    seller_fee_basis_points: u16,
+    royalty_policy: RoyaltyPolicy,
+) -> Instruction {
+    let accounts = find_auction_house(authority, treasury_mint);
+    Instruction {
+        program_id: ID,
+        accounts: CreateAuctionHouseAccounts {
+            auction_house: accounts.auction_house,
+            authority: *authority,
+            treasury_mint: *treasury_mint,
+            treasury_withdrawal_account: *treasury_withdrawal_account,
+            treasury_withdrawal_owner: *treasury_withdrawal_owner,
+            treasury_account: accounts.treasury_account,
+            fee_account: accounts.fee_account,
+            fee_withdrawal_account: *fee_withdrawal_account,
+            payer: *authority,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: CreateAuctionHouseInstruction {
+            seller_fee_basis_points,
+            requires_sign_off: false,
+            royalty_policy,
+        }
+        .data(),
+    }
+}
+
+/// Creates a native auction house of a new authority, returning the authority
+pub async fn create_native_auction_house(
+    ctx: &mut ProgramTestContext,
+    seller_fee_basis_points: u16,
+    royalty_policy: RoyaltyPolicy,
+) -> Keypair {
+    let authority = Keypair::new();
+    let payer = ctx.payer.pubkey();
+    let instructions = [
+        system_instruction::transfer(&payer, &authority.pubkey(), 1_000_000_000),
+        create_auction_house_instruction(
+            &authority.pubkey(),
+            &spl_token::native_mint::id(),
+            &authority.pubkey(),
+            &authority.pubkey(),
+            &authority.pubkey(),
+            seller_fee_basis_points,
+            royalty_policy,
+        ),
+    ];
+    process_instructions(ctx, &instructions, &[&authority])
+        .await
+        .unwrap();
+    authority
+}
+
+/// Creates a native auction house taking `seller_fee_basis_points` fees that
+/// enforces royalties up to what the fees leave of the price, returning the
+/// authority
+pub async fn create_market_auction_house(
+    ctx: &mut ProgramTestContext,
+    seller_fee_basis_points: u16,
+) -> Keypair {
+    create_native_auction_house(
+        ctx,
+        seller_fee_basis_points,
+        RoyaltyPolicy {
+            mode: RoyaltyMode::Enforced,
+            min_basis_points: 0,
+            max_basis_points: 10000 - seller_fee_basis_points,
+        },
+    )
+    .await
+}
+
+/// Mints to `owner` a leaf paying 5% royalties to `creator` on a new tree,
+/// the leaf was already sold once unless `primary_sale_happened` is false
+pub async fn mint_market_leaf(
+    ctx: &mut ProgramTestContext,
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    creator: &Keypair,
+    primary_sale_happened: bool,
+) -> (Tree, Leaf) {
+    let mut tree = Tree::create(ctx).await;
+    let mut metadata = leaf_metadata(500, &[(creator.pubkey(), 100)]);
+    metadata.primary_sale_happened = primary_sale_happened;
+    let leaf = tree.mint(ctx, owner, delegate, metadata, &[creator]).await;
+    (tree, leaf)
+}
+
+/// Creates wallets funded with `lamports` by the payer
+pub async fn create_wallets<const N: usize>(
+    ctx: &mut ProgramTestContext,
+    lamports: u64,
+) -> [Keypair; N] {
+    let wallets = [(); N].map(|_| Keypair::new());
+    let payer = ctx.payer.pubkey();
+    let instructions: Vec<Instruction> = wallets
+        .iter()
+        .map(|wallet| system_instruction::transfer(&payer, &wallet.pubkey(), lamports))
+        .collect();
+    process_instructions(ctx, &instructions, &[]).await.unwrap();
+    wallets
+}
+
+pub fn find_escrow(auction_house: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[ESCROW.as_ref(), auction_house.as_ref(), wallet.as_ref()],
+        &ID,
+    )
+    .0
+}
+
+pub fn find_program_as_signer() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM.as_bytes(), SIGNER.as_bytes()], &ID).0
+}
+
+pub fn find_trade_state(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    asset_id: &Pubkey,
+    price: u64,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            TRADE_STATE.as_ref(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            asset_id.as_ref(),
+            price.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+/// Receipt of the order held by `trade_state`, `prefix` being the listing or bid receipt seed
+pub fn find_order_receipt(prefix: &str, trade_state: &Pubkey, created_at: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            prefix.as_bytes(),
+            trade_state.as_ref(),
+            created_at.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+pub async fn fetch_program_account<T: AccountDeserialize>(
+    ctx: &mut ProgramTestContext,
+    address: Pubkey,
+) -> T {
+    let account = ctx
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    T::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+/// Lists `leaf` of its owner for `seller_price`, delegating it to the program
+pub fn sell_instruction(
+    authority: &Pubkey,
+    treasury_mint: &Pubkey,
+    tree: &Tree,
+    leaf: &Leaf,
+    seller_price: u64,
+    expiry: Option<i64>,
+    allowed_buyer: Option<Pubkey>,
+) -> Instruction {
+    let accounts = find_auction_house(authority, treasury_mint);
+    let mut account_metas = SellAccounts {
+        auction_house: accounts.auction_house,
+        auction_house_authority: *authority,
+        treasury_mint: *treasury_mint,
+        tree_config: tree.tree_config,
+        owner: leaf.owner,
+        merkle_tree: tree.merkle_tree,
+        previous_leaf_delegate: leaf.delegate,
+        seller_trade_state: find_trade_state(
+            &leaf.owner,
+            &accounts.auction_house,
+            &leaf.asset_id,
+            seller_price,
+        ),
+        asset_id: leaf.asset_id,
+        auction_house_fee_account: accounts.fee_account,
+        program_as_signer: find_program_as_signer(),
+        bubblegum_program: mpl_bubblegum::ID,
+        compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+        system_program: system_program::ID,
+        log_wrapper: SPL_NOOP_ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(tree.proof(leaf.index));
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: SellInstruction {
+            seller_price,
+            root: tree.root(),
+            data_hash: leaf.data_hash(),
+            creator_hash: leaf.creator_hash(),
+            nonce: leaf.nonce,
+            index: leaf.index,
+            expiry,
+            allowed_buyer,
+        }
+        .data(),
+    }
+}
+
+pub async fn process_instructions(
+    ctx: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let last_blockhash = ctx.banks_client.get_latest_blockhash().await?;
+    let signed_tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&ctx.payer.pubkey()),
+        &all_signers,
+        last_blockhash,
+    );
+    ctx.banks_client.process_transaction(signed_tx).await
+}
+
+/// Unix timestamp of the test validator clock
+pub async fn current_timestamp(ctx: &mut ProgramTestContext) -> i64 {
+    let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp
+}
+
+/// Moves the test validator clock to `unix_timestamp`
+pub async fn warp_to_timestamp(ctx: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    ctx.set_sysvar(&clock);
+}
+
+pub async fn create_mint(ctx: &mut ProgramTestContext, mint_authority: &Pubkey) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &ctx.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::ID,
+            &mint.pubkey(),
+            mint_authority,
+            None,
+            0,
+        )
+        .unwrap(),
+    ];
+    process_instructions(ctx, &instructions, &[&mint])
+        .await
+        .unwrap();
+    mint.pubkey()
+}
+
+/// Asserts that a transaction failed with the custom error `code`, either one
+/// of the program errors or an anchor framework error
+pub fn assert_custom_error(result: Result<(), BanksClientError>, code: u32) {
+    match result.unwrap_err() {
+        BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(error_code),
+        )) => assert_eq!(error_code, code),
+        error => panic!("expected custom error {}, got {:?}", code, error),
+    }
+}
@@ -3,3 +3,6 @@ pub use helpers::*;
 
 pub mod constants;
 pub use constants::*;
+
+pub mod cnft;
+pub use cnft::*;
@@ -0,0 +1,242 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::native_mint;
+use auction_house_v2::{
+    accounts::WithdrawFromTreasuryInstruction as WithdrawFromTreasuryAccounts,
+    instruction::WithdrawFromTreasury as WithdrawFromTreasuryInstruction, AuctionHouseV2Errors,
+    RoyaltyMode, RoyaltyPolicy, ID,
+};
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey, sysvar};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{rent::Rent, signer::Signer, system_instruction};
+use spl_associated_token_account::get_associated_token_address;
+use utils::{
+    assert_custom_error, auction_house_program_test, create_auction_house_instruction, create_mint,
+    find_auction_house, process_instructions,
+};
+
+pub mod utils;
+
+const ROYALTY_POLICY: RoyaltyPolicy = RoyaltyPolicy {
+    mode: RoyaltyMode::Enforced,
+    min_basis_points: 0,
+    max_basis_points: 9500,
+};
+
+fn withdraw_from_treasury_instruction(
+    authority: &Pubkey,
+    treasury_mint: &Pubkey,
+    treasury_withdrawal_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = find_auction_house(authority, treasury_mint);
+    Instruction {
+        program_id: ID,
+        accounts: WithdrawFromTreasuryAccounts {
+            auction_house: accounts.auction_house,
+            authority: *authority,
+            treasury_mint: *treasury_mint,
+            treasury_account: accounts.treasury_account,
+            treasury_withdrawal_account: *treasury_withdrawal_account,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: WithdrawFromTreasuryInstruction { amount }.data(),
+    }
+}
+
+/// Creates a native auction house of the payer withdrawing its treasury to a
+/// new account, and funds the treasury with `lamports`
+async fn create_native_treasury(ctx: &mut ProgramTestContext, lamports: u64) -> Pubkey {
+    let payer = ctx.payer.pubkey();
+    let treasury_withdrawal_account = Pubkey::new_unique();
+    let treasury_account = find_auction_house(&payer, &native_mint::id()).treasury_account;
+    process_instructions(
+        ctx,
+        &[
+            create_auction_house_instruction(
+                &payer,
+                &native_mint::id(),
+                &treasury_withdrawal_account,
+                &treasury_withdrawal_account,
+                &payer,
+                500,
+                ROYALTY_POLICY,
+            ),
+            system_instruction::transfer(&payer, &treasury_account, lamports),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+    treasury_withdrawal_account
+}
+
+#[tokio::test]
+async fn withdraw_from_native_treasury() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    let treasury_withdrawal_account = create_native_treasury(&mut ctx, 1_000_000_000).await;
+    let treasury_account = find_auction_house(&payer, &native_mint::id()).treasury_account;
+
+    process_instructions(
+        &mut ctx,
+        &[withdraw_from_treasury_instruction(
+            &payer,
+            &native_mint::id(),
+            &treasury_withdrawal_account,
+            400_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let withdrawn = ctx
+        .banks_client
+        .get_balance(treasury_withdrawal_account)
+        .await
+        .unwrap();
+    assert_eq!(withdrawn, 400_000_000);
+    let treasury = ctx
+        .banks_client
+        .get_balance(treasury_account)
+        .await
+        .unwrap();
+    assert_eq!(treasury, 600_000_000);
+}
+
+#[tokio::test]
+async fn reject_native_treasury_withdrawal_below_rent_exempt() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    let treasury_withdrawal_account = create_native_treasury(&mut ctx, 1_000_000_000).await;
+    let treasury_account = find_auction_house(&payer, &native_mint::id()).treasury_account;
+
+    // the whole balance would leave the treasury without its rent reserve
+    let result = process_instructions(
+        &mut ctx,
+        &[withdraw_from_treasury_instruction(
+            &payer,
+            &native_mint::id(),
+            &treasury_withdrawal_account,
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await;
+    assert_custom_error(result, AuctionHouseV2Errors::NotEnoughFunds.into());
+
+    // everything above the rent reserve can be withdrawn
+    let withdrawable = 1_000_000_000 - Rent::default().minimum_balance(0);
+    process_instructions(
+        &mut ctx,
+        &[withdraw_from_treasury_instruction(
+            &payer,
+            &native_mint::id(),
+            &treasury_withdrawal_account,
+            withdrawable,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let treasury = ctx
+        .banks_client
+        .get_balance(treasury_account)
+        .await
+        .unwrap();
+    assert_eq!(treasury, Rent::default().minimum_balance(0));
+}
+
+#[tokio::test]
+async fn withdraw_from_token_treasury() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    let treasury_mint = create_mint(&mut ctx, &payer).await;
+    let treasury_account = find_auction_house(&payer, &treasury_mint).treasury_account;
+    let treasury_withdrawal_account = get_associated_token_address(&payer, &treasury_mint);
+
+    process_instructions(
+        &mut ctx,
+        &[
+            create_auction_house_instruction(
+                &payer,
+                &treasury_mint,
+                &treasury_withdrawal_account,
+                &payer,
+                &payer,
+                500,
+                ROYALTY_POLICY,
+            ),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &treasury_mint,
+                &treasury_account,
+                &payer,
+                &[],
+                1_000,
+            )
+            .unwrap(),
+            withdraw_from_treasury_instruction(
+                &payer,
+                &treasury_mint,
+                &treasury_withdrawal_account,
+                400,
+            ),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    for (token_account, amount) in [(treasury_withdrawal_account, 400), (treasury_account, 600)] {
+        let account = ctx
+            .banks_client
+            .get_account(token_account)
+            .await
+            .unwrap()
+            .unwrap();
+        let token_account = spl_token::state::Account::unpack(&account.data).unwrap();
+        assert_eq!(token_account.amount, amount);
+    }
+}
+
+#[tokio::test]
+async fn reject_token_treasury_withdrawal_to_another_account() {
+    let mut ctx = auction_house_program_test().start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    let treasury_mint = create_mint(&mut ctx, &payer).await;
+    let treasury_withdrawal_account = get_associated_token_address(&payer, &treasury_mint);
+
+    process_instructions(
+        &mut ctx,
+        &[create_auction_house_instruction(
+            &payer,
+            &treasury_mint,
+            &treasury_withdrawal_account,
+            &payer,
+            &payer,
+            500,
+            ROYALTY_POLICY,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let result = process_instructions(
+        &mut ctx,
+        &[withdraw_from_treasury_instruction(
+            &payer,
+            &treasury_mint,
+            &Pubkey::new_unique(),
+            1,
+        )],
+        &[],
+    )
+    .await;
+    assert_custom_error(result, ErrorCode::ConstraintHasOne.into());
+}